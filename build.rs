@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::str;
 use std::sync::LazyLock;
+use std::sync::Mutex;
 use std::sync::OnceLock;
 
 // On Ubuntu server, we need the following packages:
@@ -41,6 +42,8 @@ static DPDK_CFLAGS: OnceLock<Vec<String>> = OnceLock::new();
 
 static DPDK_LINK_OPTIONS: OnceLock<Vec<String>> = OnceLock::new();
 
+static GENERATED_MODULES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
 fn main() {
     CUREENT_DIR.get_or_init(|| std::fs::canonicalize("./").unwrap());
 
@@ -59,6 +62,15 @@ fn main() {
         force = true;
     }
 
+    // meson only builds net_pcap if it finds libpcap at configure time, so a
+    // DPDK configured before libpcap was installed has to be configured again
+    // when the pcap feature asks for it.
+    let pcap_requested = env::var_os("CARGO_FEATURE_PCAP").is_some();
+    let pcap_found = probe_pcap(pcap_requested);
+    if pcap_requested && check_step("install") && !pcap_built() {
+        force = true;
+    }
+
     if force || !check_step("configure") {
         configure();
         force = true;
@@ -73,6 +85,19 @@ fn main() {
         install();
     }
 
+    if pcap_found && !pcap_built() {
+        if pcap_requested {
+            panic!(
+                "libpcap was found but DPDK was built without net_pcap, see {}/meson-logs/meson-log.txt",
+                BUILD_DIR
+            );
+        }
+        println!(
+            "cargo:warning=libpcap was found but DPDK was built without net_pcap, \
+             enable the pcap feature to rebuild it"
+        );
+    }
+
     generate_library();
 
     println!("cargo:rerun-if-changed=build.rs");
@@ -81,6 +106,37 @@ fn main() {
     println!("cargo:rerun-if-changed=csrc/header.h");
 }
 
+/// Whether libpcap is present. A missing libpcap is an error if the `pcap`
+/// feature is `requested`.
+fn probe_pcap(requested: bool) -> bool {
+    let found = Command::new("pkg-config")
+        .args(["--exists", "libpcap"])
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+        || Command::new("pcap-config")
+            .arg("--libs")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+
+    if requested && !found {
+        panic!(
+            "The pcap feature needs libpcap to build the net_pcap PMD. \
+             Please install libpcap (apt install libpcap-dev)."
+        );
+    }
+
+    found
+}
+
+/// Whether the installed DPDK has the net_pcap PMD.
+fn pcap_built() -> bool {
+    std::fs::read_to_string(format!("{}/include/rte_build_config.h", INSTALL_DIR))
+        .map(|config| config.contains("#define RTE_NET_PCAP "))
+        .unwrap_or(false)
+}
+
 fn install() {
     std::fs::remove_file("deps/install.ok").unwrap_or_default();
 
@@ -293,6 +349,19 @@ fn generate_library() {
             "mbuf",
             "mempool",
             "ethdev",
+            "dev",
+            "ring",
+            "net",
+            "ip_frag",
+            "gro",
+            "gso",
+            "net_ring",
+            "pcapng",
+            "pdump",
+            "memzone",
+            "malloc",
+            "mp",
+            "telemetry",
             "build_config",
             "config",
             "errno",
@@ -300,6 +369,29 @@ fn generate_library() {
         "eal",
     );
     add_module(["power"], "power");
+    add_module(["hash"], "hash");
+    add_module(["lpm", "fib"], "lpm");
+    add_module(["acl"], "acl");
+    add_wrapper("error", "eal");
+    add_wrapper("ethdev", "eal");
+    add_wrapper("dev", "eal");
+    add_wrapper("lcore", "eal");
+    add_wrapper("mbuf", "eal");
+    add_wrapper("ring", "eal");
+    add_wrapper("hash_table", "hash");
+    add_wrapper("init", "eal");
+    add_wrapper("net", "eal");
+    add_wrapper("frag", "eal");
+    add_wrapper("gro", "eal");
+    add_wrapper("gso", "eal");
+    add_wrapper("capture", "eal");
+    add_wrapper("memory", "eal");
+    add_wrapper("process", "eal");
+    add_wrapper("telemetry", "eal");
+    add_wrapper("pcap", "pcap");
+    add_wrapper("route", "lpm");
+    add_wrapper("classify", "acl");
+    generate_layout_checks();
     link_dpdk();
 }
 
@@ -320,6 +412,10 @@ fn add_module<S: AsRef<str>, I: IntoIterator<Item = S>>(dpdk_libs: I, module_nam
     DpdkLib::build(selected_libs, module_name);
 }
 
+fn add_wrapper(name: &'static str, feature: &'static str) {
+    DpdkLib::add_wrapper(name, feature);
+}
+
 fn pkgconfig() {
     let mut pkg_config_path = env::var("PKG_CONFIG_PATH").unwrap_or_default();
     if pkg_config_path.is_empty() {
@@ -403,6 +499,149 @@ fn pkgconfig() {
     });
 }
 
+/// Write `$OUT_DIR/layout.rs`, listing the size, alignment and field offsets
+/// of every struct and union of the generated bindings, which tests/layout.rs
+/// turns into C static asserts.
+///
+/// Bitfields and anonymous members have no C name and are skipped, as are
+/// opaque types and types generated in more than one module, which the glob
+/// re-exports make ambiguous. Types named `*_t` are assumed to be typedefs,
+/// the others struct or union tags.
+fn generate_layout_checks() {
+    let modules = GENERATED_MODULES.lock().unwrap();
+    let types: Vec<(&str, Vec<RustType>)> = modules
+        .iter()
+        .map(|module| {
+            let src = std::fs::read_to_string(format!("src/{}.rs", module))
+                .expect("Failed to read the generated bindings");
+            (*module, RustType::parse_all(&src))
+        })
+        .collect();
+
+    let mut out = String::from(
+        "// Generated by build.rs from the bindgen output, see tests/layout.rs.\n\n\
+         fn layouts() -> Vec<Layout> {\n    let mut layouts = Vec::new();\n",
+    );
+    for (module, module_types) in types.iter() {
+        out.push_str(&format!("    #[cfg(feature = \"{}\")]\n    {{\n", module));
+        for t in module_types {
+            let duplicated = types
+                .iter()
+                .filter(|(_, other)| other.iter().any(|o| o.name == t.name))
+                .count()
+                > 1;
+            if duplicated {
+                continue;
+            }
+            out.push_str(&t.layout_entry());
+        }
+        out.push_str("    }\n");
+    }
+    out.push_str("    layouts\n}\n");
+
+    let path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("layout.rs");
+    std::fs::write(path, out).expect("Failed to write layout.rs");
+}
+
+/// A struct or union of the generated bindings.
+struct RustType {
+    name: String,
+    is_union: bool,
+    fields: Vec<String>,
+}
+
+impl RustType {
+    fn parse_all(src: &str) -> Vec<Self> {
+        let mut types = vec![];
+        let mut current: Option<(Self, bool)> = None;
+
+        for line in src.lines() {
+            let line = line.trim();
+            if let Some((mut t, mut opaque)) = current.take() {
+                if line == "}" {
+                    if !opaque && !t.name.contains("__bindgen") && t.name.starts_with("rte_") {
+                        types.push(t);
+                    }
+                    continue;
+                }
+                // Opaque types only have a private `_unused` member.
+                if line.starts_with("_unused:") {
+                    opaque = true;
+                }
+                let field = line
+                    .strip_prefix("pub ")
+                    .and_then(|f| f.split_once(':'))
+                    .map(|(field, _)| field);
+                if let Some(field) = field {
+                    let is_ident = field
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '#');
+                    if is_ident && !field.starts_with('_') {
+                        t.fields.push(field.to_string());
+                    }
+                }
+                current = Some((t, opaque));
+                continue;
+            }
+
+            let (decl, is_union) = if let Some(decl) = line.strip_prefix("pub struct ") {
+                (decl, false)
+            } else if let Some(decl) = line.strip_prefix("pub union ") {
+                (decl, true)
+            } else {
+                continue;
+            };
+            if let Some(name) = decl.strip_suffix(" {") {
+                if !name.contains('<') {
+                    let t = Self {
+                        name: name.to_string(),
+                        is_union,
+                        fields: vec![],
+                    };
+                    current = Some((t, false));
+                }
+            }
+        }
+
+        types
+    }
+
+    fn c_type(&self) -> String {
+        if self.name.ends_with("_t") {
+            self.name.clone()
+        } else if self.is_union {
+            format!("union {}", self.name)
+        } else {
+            format!("struct {}", self.name)
+        }
+    }
+
+    fn layout_entry(&self) -> String {
+        let rust = format!("dpdk_sys::{}", self.name);
+        let mut entry = format!(
+            "        layouts.push(Layout {{\n            c_type: \"{}\",\n            \
+             size: ::std::mem::size_of::<{}>(),\n            \
+             align: ::std::mem::align_of::<{}>(),\n            fields: vec![\n",
+            self.c_type(),
+            rust,
+            rust
+        );
+        // Every union member is at offset 0, only their sizes matter.
+        if !self.is_union {
+            for field in self.fields.iter() {
+                entry.push_str(&format!(
+                    "                (\"{}\", ::std::mem::offset_of!({}, {})),\n",
+                    field.trim_start_matches("r#"),
+                    rust,
+                    field
+                ));
+            }
+        }
+        entry.push_str("            ],\n        });\n");
+        entry
+    }
+}
+
 fn link_dpdk() {
     let mut cbuild = cc::Build::new();
     cbuild.opt_level(3);
@@ -411,6 +650,19 @@ fn link_dpdk() {
     }
     cbuild.file("csrc/impl.c").compile("impl");
 
+    // tests/layout.rs compiles its checks exactly as csrc/impl.c.
+    let compiler = cbuild.get_compiler();
+    println!("cargo:rustc-env=DPDK_SYS_CC={}", compiler.path().display());
+    println!(
+        "cargo:rustc-env=DPDK_SYS_CFLAGS={}",
+        compiler
+            .args()
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+
     for ldflag in DPDK_LINK_OPTIONS.get().unwrap().iter() {
         if ldflag.starts_with("-L") {
             println!("cargo:rustc-link-search=native={}", &ldflag[2..]);
@@ -482,11 +734,48 @@ impl DpdkLib {
             .unwrap()
             .write_to_file(format!("src/{}.rs", module))
             .unwrap();
+        GENERATED_MODULES.lock().unwrap().push(module);
 
         Self::add_module(module);
     }
 
     fn add_module(name: &str) {
+        let mut w = Self::lib_writer();
+
+        w.write_all(
+            format!(
+                "
+#[cfg(feature = \"{}\")]
+mod {};
+#[cfg(feature = \"{}\")]
+pub use {}::*;
+        ",
+                name, name, name, name
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    }
+
+    // Hand-written safe wrappers live next to the generated bindings, so
+    // their module declarations are emitted into src/lib.rs as well.
+    fn add_wrapper(name: &str, feature: &str) {
+        let mut w = Self::lib_writer();
+
+        w.write_all(
+            format!(
+                "
+#[cfg(feature = \"{}\")]
+pub mod {};
+        ",
+                feature, name
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    }
+
+    fn lib_writer() -> BufWriter<std::fs::File> {
         static INIT: OnceLock<()> = OnceLock::new();
 
         let mut first = false;
@@ -519,19 +808,7 @@ impl DpdkLib {
             .unwrap();
         }
 
-        w.write_all(
-            format!(
-                "
-#[cfg(feature = \"{}\")]
-mod {};
-#[cfg(feature = \"{}\")]
-pub use {}::*;
-        ",
-                name, name, name, name
-            )
-            .as_bytes(),
-        )
-        .unwrap();
+        w
     }
 
     fn clear(&mut self) {
@@ -1,13 +1,42 @@
 #define _GNU_SOURCE
+#include <rte_acl.h>
 #include <rte_build_config.h>
+#include <rte_bus.h>
 #include <rte_config.h>
+#include <rte_cycles.h>
+#include <rte_dev.h>
 #include <rte_eal.h>
 #include <rte_errno.h>
+#include <rte_eth_ring.h>
 #include <rte_ethdev.h>
+#include <rte_ether.h>
+#include <rte_fib.h>
+#include <rte_fib6.h>
+#include <rte_gro.h>
+#include <rte_gso.h>
+#include <rte_gtp.h>
+#include <rte_hash.h>
+#include <rte_hash_crc.h>
+#include <rte_ip.h>
+#include <rte_ip_frag.h>
+#include <rte_jhash.h>
+#include <rte_launch.h>
 #include <rte_lcore.h>
+#include <rte_lpm.h>
+#include <rte_lpm6.h>
+#include <rte_malloc.h>
 #include <rte_mbuf.h>
 #include <rte_mempool.h>
+#include <rte_memzone.h>
+#include <rte_net.h>
+#include <rte_pcapng.h>
+#include <rte_pdump.h>
 #include <rte_power.h>
+#include <rte_ring.h>
+#include <rte_tcp.h>
+#include <rte_telemetry.h>
+#include <rte_udp.h>
+#include <rte_vxlan.h>
 
 // Add wrapper definitions for functions that bindgen can not generate.
 //
@@ -36,10 +65,96 @@ int rte_pktmbuf_alloc_bulk_(struct rte_mempool *pool, struct rte_mbuf **mbufs,
 
 void rte_pktmbuf_free_(struct rte_mbuf *m);
 
+char *rte_pktmbuf_append_(struct rte_mbuf *m, uint16_t len);
+
 uint16_t rte_eth_rx_burst_(uint16_t port_id, uint16_t queue_id,
                            struct rte_mbuf **rx_pkts, const uint16_t nb_pkts);
 
 uint16_t rte_eth_tx_burst_(uint16_t port_id, uint16_t queue_id,
                            struct rte_mbuf **tx_pkts, uint16_t nb_pkts);
 
-int rte_errno_();
\ No newline at end of file
+uint16_t rte_eth_tx_prepare_(uint16_t port_id, uint16_t queue_id,
+                             struct rte_mbuf **tx_pkts, uint16_t nb_pkts);
+
+int rte_errno_();
+
+uint64_t rte_rdtsc_();
+
+unsigned int rte_ring_enqueue_bulk_(struct rte_ring *r, void *const *obj_table,
+                                    unsigned int n, unsigned int *free_space);
+
+unsigned int rte_ring_enqueue_burst_(struct rte_ring *r,
+                                     void *const *obj_table, unsigned int n,
+                                     unsigned int *free_space);
+
+unsigned int rte_ring_dequeue_bulk_(struct rte_ring *r, void **obj_table,
+                                    unsigned int n, unsigned int *available);
+
+unsigned int rte_ring_dequeue_burst_(struct rte_ring *r, void **obj_table,
+                                     unsigned int n, unsigned int *available);
+
+unsigned int rte_ring_enqueue_bulk_elem_(struct rte_ring *r,
+                                         const void *obj_table,
+                                         unsigned int esize, unsigned int n,
+                                         unsigned int *free_space);
+
+unsigned int rte_ring_enqueue_burst_elem_(struct rte_ring *r,
+                                          const void *obj_table,
+                                          unsigned int esize, unsigned int n,
+                                          unsigned int *free_space);
+
+unsigned int rte_ring_dequeue_bulk_elem_(struct rte_ring *r, void *obj_table,
+                                         unsigned int esize, unsigned int n,
+                                         unsigned int *available);
+
+unsigned int rte_ring_dequeue_burst_elem_(struct rte_ring *r, void *obj_table,
+                                          unsigned int esize, unsigned int n,
+                                          unsigned int *available);
+
+unsigned int rte_ring_count_(const struct rte_ring *r);
+
+unsigned int rte_ring_free_count_(const struct rte_ring *r);
+
+unsigned int rte_ring_get_size_(const struct rte_ring *r);
+
+unsigned int rte_ring_get_capacity_(const struct rte_ring *r);
+
+int rte_ring_empty_(const struct rte_ring *r);
+
+int rte_ring_full_(const struct rte_ring *r);
+
+uint32_t rte_jhash_(const void *key, uint32_t length, uint32_t initval);
+
+uint32_t rte_hash_crc_(const void *data, uint32_t data_len, uint32_t init_val);
+
+int rte_lpm_lookup_(struct rte_lpm *lpm, uint32_t ip, uint32_t *next_hop);
+
+int rte_lpm_lookup_bulk_(const struct rte_lpm *lpm, const uint32_t *ips,
+                         uint32_t *next_hops, const unsigned n);
+
+uint16_t rte_raw_cksum_(const void *buf, size_t len);
+
+uint16_t rte_ipv4_cksum_(const struct rte_ipv4_hdr *ipv4_hdr);
+
+uint16_t rte_ipv4_phdr_cksum_(const struct rte_ipv4_hdr *ipv4_hdr,
+                              uint64_t ol_flags);
+
+uint16_t rte_ipv4_udptcp_cksum_(const struct rte_ipv4_hdr *ipv4_hdr,
+                                const void *l4_hdr);
+
+int rte_ipv4_udptcp_cksum_verify_(const struct rte_ipv4_hdr *ipv4_hdr,
+                                  const void *l4_hdr);
+
+uint16_t rte_ipv6_phdr_cksum_(const struct rte_ipv6_hdr *ipv6_hdr,
+                              uint64_t ol_flags);
+
+uint16_t rte_ipv6_udptcp_cksum_(const struct rte_ipv6_hdr *ipv6_hdr,
+                                const void *l4_hdr);
+
+int rte_ipv6_udptcp_cksum_verify_(const struct rte_ipv6_hdr *ipv6_hdr,
+                                  const void *l4_hdr);
+
+int rte_net_intel_cksum_prepare_(struct rte_mbuf *m);
+
+// Free the replies of rte_mp_request_sync(), allocated with malloc().
+void rte_mp_reply_free_(struct rte_mp_reply *reply);
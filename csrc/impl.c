@@ -1,5 +1,7 @@
 #include "header.h"
 
+#include <stdlib.h>
+
 unsigned rte_lcore_id_() { return rte_lcore_id(); }
 
 int rte_mempool_full_(const struct rte_mempool *mp) {
@@ -17,6 +19,10 @@ int rte_pktmbuf_alloc_bulk_(struct rte_mempool *pool, struct rte_mbuf **mbufs,
 
 void rte_pktmbuf_free_(struct rte_mbuf *m) { rte_pktmbuf_free(m); }
 
+char *rte_pktmbuf_append_(struct rte_mbuf *m, uint16_t len) {
+  return rte_pktmbuf_append(m, len);
+}
+
 uint16_t rte_eth_rx_burst_(uint16_t port_id, uint16_t queue_id,
                            struct rte_mbuf **rx_pkts, const uint16_t nb_pkts) {
   return rte_eth_rx_burst(port_id, queue_id, rx_pkts, nb_pkts);
@@ -27,4 +33,139 @@ uint16_t rte_eth_tx_burst_(uint16_t port_id, uint16_t queue_id,
   return rte_eth_tx_burst(port_id, queue_id, tx_pkts, nb_pkts);
 }
 
-int rte_errno_() { return rte_errno; }
\ No newline at end of file
+uint16_t rte_eth_tx_prepare_(uint16_t port_id, uint16_t queue_id,
+                             struct rte_mbuf **tx_pkts, uint16_t nb_pkts) {
+  return rte_eth_tx_prepare(port_id, queue_id, tx_pkts, nb_pkts);
+}
+
+int rte_errno_() { return rte_errno; }
+
+uint64_t rte_rdtsc_() { return rte_rdtsc(); }
+
+unsigned int rte_ring_enqueue_bulk_(struct rte_ring *r, void *const *obj_table,
+                                    unsigned int n, unsigned int *free_space) {
+  return rte_ring_enqueue_bulk(r, obj_table, n, free_space);
+}
+
+unsigned int rte_ring_enqueue_burst_(struct rte_ring *r,
+                                     void *const *obj_table, unsigned int n,
+                                     unsigned int *free_space) {
+  return rte_ring_enqueue_burst(r, obj_table, n, free_space);
+}
+
+unsigned int rte_ring_dequeue_bulk_(struct rte_ring *r, void **obj_table,
+                                    unsigned int n, unsigned int *available) {
+  return rte_ring_dequeue_bulk(r, obj_table, n, available);
+}
+
+unsigned int rte_ring_dequeue_burst_(struct rte_ring *r, void **obj_table,
+                                     unsigned int n, unsigned int *available) {
+  return rte_ring_dequeue_burst(r, obj_table, n, available);
+}
+
+unsigned int rte_ring_enqueue_bulk_elem_(struct rte_ring *r,
+                                         const void *obj_table,
+                                         unsigned int esize, unsigned int n,
+                                         unsigned int *free_space) {
+  return rte_ring_enqueue_bulk_elem(r, obj_table, esize, n, free_space);
+}
+
+unsigned int rte_ring_enqueue_burst_elem_(struct rte_ring *r,
+                                          const void *obj_table,
+                                          unsigned int esize, unsigned int n,
+                                          unsigned int *free_space) {
+  return rte_ring_enqueue_burst_elem(r, obj_table, esize, n, free_space);
+}
+
+unsigned int rte_ring_dequeue_bulk_elem_(struct rte_ring *r, void *obj_table,
+                                         unsigned int esize, unsigned int n,
+                                         unsigned int *available) {
+  return rte_ring_dequeue_bulk_elem(r, obj_table, esize, n, available);
+}
+
+unsigned int rte_ring_dequeue_burst_elem_(struct rte_ring *r, void *obj_table,
+                                          unsigned int esize, unsigned int n,
+                                          unsigned int *available) {
+  return rte_ring_dequeue_burst_elem(r, obj_table, esize, n, available);
+}
+
+unsigned int rte_ring_count_(const struct rte_ring *r) {
+  return rte_ring_count(r);
+}
+
+unsigned int rte_ring_free_count_(const struct rte_ring *r) {
+  return rte_ring_free_count(r);
+}
+
+unsigned int rte_ring_get_size_(const struct rte_ring *r) {
+  return rte_ring_get_size(r);
+}
+
+unsigned int rte_ring_get_capacity_(const struct rte_ring *r) {
+  return rte_ring_get_capacity(r);
+}
+
+int rte_ring_empty_(const struct rte_ring *r) { return rte_ring_empty(r); }
+
+int rte_ring_full_(const struct rte_ring *r) { return rte_ring_full(r); }
+
+uint32_t rte_jhash_(const void *key, uint32_t length, uint32_t initval) {
+  return rte_jhash(key, length, initval);
+}
+
+uint32_t rte_hash_crc_(const void *data, uint32_t data_len, uint32_t init_val) {
+  return rte_hash_crc(data, data_len, init_val);
+}
+
+int rte_lpm_lookup_(struct rte_lpm *lpm, uint32_t ip, uint32_t *next_hop) {
+  return rte_lpm_lookup(lpm, ip, next_hop);
+}
+
+int rte_lpm_lookup_bulk_(const struct rte_lpm *lpm, const uint32_t *ips,
+                         uint32_t *next_hops, const unsigned n) {
+  return rte_lpm_lookup_bulk(lpm, ips, next_hops, n);
+}
+
+uint16_t rte_raw_cksum_(const void *buf, size_t len) {
+  return rte_raw_cksum(buf, len);
+}
+
+uint16_t rte_ipv4_cksum_(const struct rte_ipv4_hdr *ipv4_hdr) {
+  return rte_ipv4_cksum(ipv4_hdr);
+}
+
+uint16_t rte_ipv4_phdr_cksum_(const struct rte_ipv4_hdr *ipv4_hdr,
+                              uint64_t ol_flags) {
+  return rte_ipv4_phdr_cksum(ipv4_hdr, ol_flags);
+}
+
+uint16_t rte_ipv4_udptcp_cksum_(const struct rte_ipv4_hdr *ipv4_hdr,
+                                const void *l4_hdr) {
+  return rte_ipv4_udptcp_cksum(ipv4_hdr, l4_hdr);
+}
+
+int rte_ipv4_udptcp_cksum_verify_(const struct rte_ipv4_hdr *ipv4_hdr,
+                                  const void *l4_hdr) {
+  return rte_ipv4_udptcp_cksum_verify(ipv4_hdr, l4_hdr);
+}
+
+uint16_t rte_ipv6_phdr_cksum_(const struct rte_ipv6_hdr *ipv6_hdr,
+                              uint64_t ol_flags) {
+  return rte_ipv6_phdr_cksum(ipv6_hdr, ol_flags);
+}
+
+uint16_t rte_ipv6_udptcp_cksum_(const struct rte_ipv6_hdr *ipv6_hdr,
+                                const void *l4_hdr) {
+  return rte_ipv6_udptcp_cksum(ipv6_hdr, l4_hdr);
+}
+
+int rte_ipv6_udptcp_cksum_verify_(const struct rte_ipv6_hdr *ipv6_hdr,
+                                  const void *l4_hdr) {
+  return rte_ipv6_udptcp_cksum_verify(ipv6_hdr, l4_hdr);
+}
+
+int rte_net_intel_cksum_prepare_(struct rte_mbuf *m) {
+  return rte_net_intel_cksum_prepare(m);
+}
+
+void rte_mp_reply_free_(struct rte_mp_reply *reply) { free(reply->msgs); }
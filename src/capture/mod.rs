@@ -0,0 +1,5 @@
+mod pcapng;
+mod pdump;
+
+pub use pcapng::*;
+pub use pdump::*;
@@ -0,0 +1,225 @@
+use std::ffi::{c_char, CString};
+use std::io;
+use std::os::fd::{AsRawFd, IntoRawFd, OwnedFd};
+use std::ptr::{self, NonNull};
+
+use crate::error::{Error, Result, EINVAL, ENOBUFS};
+use crate::ethdev::Port;
+use crate::mbuf::{Mbuf, Mempool};
+use crate::*;
+
+/// The direction of a captured packet, as recorded in the pcapng file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    #[default]
+    Unknown,
+    In,
+    Out,
+}
+
+impl Direction {
+    fn as_raw(&self) -> rte_pcapng_direction {
+        match self {
+            Direction::Unknown => rte_pcapng_direction_RTE_PCAPNG_DIRECTION_UNKNOWN,
+            Direction::In => rte_pcapng_direction_RTE_PCAPNG_DIRECTION_IN,
+            Direction::Out => rte_pcapng_direction_RTE_PCAPNG_DIRECTION_OUT,
+        }
+    }
+}
+
+/// The size of the mbufs needed to capture `len` bytes of a packet, pcapng
+/// block included.
+pub fn pcapng_mbuf_size(len: u32) -> u32 {
+    unsafe { rte_pcapng_mbuf_size(len) }
+}
+
+/// The section header written by a [`PcapngWriter`].
+#[derive(Debug, Clone)]
+pub struct PcapngConfig {
+    /// The name of the capturing application.
+    pub appname: Option<String>,
+    pub comment: Option<String>,
+    /// The number of bytes of each packet written, the whole packet by
+    /// default.
+    pub snaplen: u32,
+}
+
+impl Default for PcapngConfig {
+    fn default() -> Self {
+        Self {
+            appname: None,
+            comment: None,
+            snaplen: u32::MAX,
+        }
+    }
+}
+
+/// A pcapng capture file, `rte_pcapng_t`, written to a file descriptor.
+///
+/// Packets are copied with their pcapng block to mbufs of the capture pool,
+/// whose data room must hold [`pcapng_mbuf_size`] of the captured length,
+/// and written from there. The original packets are left untouched.
+///
+/// Every port must be added with [`add_interface`](Self::add_interface)
+/// before its packets are written.
+pub struct PcapngWriter<'a> {
+    raw: NonNull<rte_pcapng_t>,
+    pool: &'a Mempool,
+    snaplen: u32,
+    interfaces: Vec<u16>,
+}
+
+unsafe impl Send for PcapngWriter<'_> {}
+
+fn cstring(s: Option<&str>) -> Result<Option<CString>> {
+    s.map(|s| CString::new(s).map_err(|_| Error::new(EINVAL)))
+        .transpose()
+}
+
+fn as_ptr(s: &Option<CString>) -> *const c_char {
+    s.as_ref().map_or(ptr::null(), |s| s.as_ptr())
+}
+
+/// Convert the return value of a pcapng function that writes to the file,
+/// which fails with `errno` set by the write.
+fn check_write(ret: isize) -> Result<usize> {
+    if ret < 0 {
+        Err(Error::new(
+            io::Error::last_os_error().raw_os_error().unwrap_or(EINVAL),
+        ))
+    } else {
+        Ok(ret as usize)
+    }
+}
+
+impl<'a> PcapngWriter<'a> {
+    /// Start a capture file on `fd`, e.g. a [`File`](std::fs::File) or a
+    /// socket, by writing its section header. The descriptor is closed with
+    /// the writer.
+    pub fn new<F: Into<OwnedFd>>(fd: F, pool: &'a Mempool, config: &PcapngConfig) -> Result<Self> {
+        let fd = fd.into();
+        let appname = cstring(config.appname.as_deref())?;
+        let comment = cstring(config.comment.as_deref())?;
+        let raw = unsafe {
+            rte_pcapng_fdopen(
+                fd.as_raw_fd(),
+                ptr::null(),
+                ptr::null(),
+                as_ptr(&appname),
+                as_ptr(&comment),
+            )
+        };
+        let raw = NonNull::new(raw).ok_or_else(Error::last)?;
+        // rte_pcapng_close() closes the descriptor.
+        let _ = fd.into_raw_fd();
+        Ok(Self {
+            raw,
+            pool,
+            snaplen: config.snaplen,
+            interfaces: Vec::new(),
+        })
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_pcapng_t {
+        self.raw.as_ptr()
+    }
+
+    /// Describe `port` in the file, under `name` or the name of the device.
+    pub fn add_interface(
+        &mut self,
+        port: Port,
+        name: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<()> {
+        if self.interfaces.contains(&port.id()) {
+            return Ok(());
+        }
+        let name = cstring(name)?;
+        let description = cstring(description)?;
+        let ret = unsafe {
+            rte_pcapng_add_interface(
+                self.as_ptr(),
+                port.id(),
+                as_ptr(&name),
+                as_ptr(&description),
+                ptr::null(),
+            )
+        };
+        check_write(ret as isize)?;
+        self.interfaces.push(port.id());
+        Ok(())
+    }
+
+    fn check_interface(&self, port: u16) -> Result<()> {
+        if self.interfaces.contains(&port) {
+            Ok(())
+        } else {
+            Err(Error::new(EINVAL))
+        }
+    }
+
+    /// Write a burst of packets seen on `queue` of `port`, and return the
+    /// number of bytes written.
+    pub fn write(
+        &mut self,
+        port: Port,
+        queue: u16,
+        direction: Direction,
+        pkts: &[Mbuf],
+    ) -> Result<usize> {
+        self.check_interface(port.id())?;
+        let mut copies = Vec::with_capacity(pkts.len());
+        for pkt in pkts {
+            let copy = unsafe {
+                rte_pcapng_copy(
+                    port.id(),
+                    queue as u32,
+                    pkt.as_ptr(),
+                    self.pool.as_ptr(),
+                    self.snaplen,
+                    direction.as_raw(),
+                    ptr::null(),
+                )
+            };
+            copies.push(unsafe { Mbuf::from_raw(copy) }.ok_or(Error::new(ENOBUFS))?);
+        }
+        self.write_captured(&mut copies)
+    }
+
+    /// Write packets already copied with their pcapng block, e.g. dequeued
+    /// from the ring of a [`Pdump`](super::Pdump), and return the number of
+    /// bytes written. The packets are not freed.
+    pub fn write_captured(&mut self, pkts: &mut [Mbuf]) -> Result<usize> {
+        for pkt in pkts.iter() {
+            self.check_interface(pkt.port())?;
+        }
+        let mut written = 0;
+        for chunk in pkts.chunks_mut(u16::MAX as usize) {
+            let ret = unsafe {
+                rte_pcapng_write_packets(
+                    self.as_ptr(),
+                    chunk.as_mut_ptr() as *mut *mut rte_mbuf,
+                    chunk.len() as u16,
+                )
+            };
+            written += check_write(ret)?;
+        }
+        Ok(written)
+    }
+
+    /// Write the packet counters of `port`, e.g. from its
+    /// [`stats`](Port::stats), and return the number of bytes written.
+    pub fn write_stats(&mut self, port: Port, received: u64, dropped: u64) -> Result<usize> {
+        self.check_interface(port.id())?;
+        let ret = unsafe {
+            rte_pcapng_write_stats(self.as_ptr(), port.id(), received, dropped, ptr::null())
+        };
+        check_write(ret)
+    }
+}
+
+impl Drop for PcapngWriter<'_> {
+    fn drop(&mut self) {
+        unsafe { rte_pcapng_close(self.as_ptr()) };
+    }
+}
@@ -0,0 +1,101 @@
+use std::marker::PhantomData;
+
+use crate::error::{check_errno, Result};
+use crate::ethdev::Port;
+use crate::mbuf::{Mbuf, Mempool};
+use crate::ring::Ring;
+use crate::*;
+
+/// Serve the capture requests of secondary processes. Called once by the
+/// primary process, after the EAL is initialized.
+pub fn pdump_init() -> Result<()> {
+    check_errno(unsafe { rte_pdump_init() })?;
+    Ok(())
+}
+
+/// Stop serving capture requests.
+pub fn pdump_uninit() -> Result<()> {
+    check_errno(unsafe { rte_pdump_uninit() })?;
+    Ok(())
+}
+
+/// The packets of a queue captured by a [`Pdump`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PdumpDirection {
+    Rx,
+    Tx,
+    #[default]
+    Both,
+}
+
+impl PdumpDirection {
+    fn flags(&self) -> u32 {
+        match self {
+            PdumpDirection::Rx => RTE_PDUMP_FLAG_RX,
+            PdumpDirection::Tx => RTE_PDUMP_FLAG_TX,
+            PdumpDirection::Both => RTE_PDUMP_FLAG_RXTX,
+        }
+    }
+}
+
+/// A capture of the packets of a port of the primary process, requested from
+/// a secondary process through `rte_pdump_enable_bpf`.
+///
+/// The primary copies the packets in pcapng format to mbufs of `pool` and
+/// enqueues them to `ring`, from where they can be written with
+/// [`PcapngWriter::write_captured`](super::PcapngWriter::write_captured).
+/// The capture stops on drop.
+pub struct Pdump<'a> {
+    port: Port,
+    queue: u16,
+    flags: u32,
+    _resources: PhantomData<(&'a Ring<Mbuf>, &'a Mempool)>,
+}
+
+impl<'a> Pdump<'a> {
+    /// Capture the packets of `queue`, or of all queues if `None`, of
+    /// `port`, keeping at most `snaplen` bytes of each.
+    ///
+    /// The ring must be multi-producer if several queues are captured, and
+    /// the data room of the pool must hold
+    /// [`pcapng_mbuf_size`](super::pcapng_mbuf_size) of `snaplen`.
+    pub fn enable(
+        port: Port,
+        queue: Option<u16>,
+        direction: PdumpDirection,
+        snaplen: u32,
+        ring: &'a Ring<Mbuf>,
+        pool: &'a Mempool,
+    ) -> Result<Self> {
+        // RTE_PDUMP_ALL_QUEUES
+        let queue = queue.unwrap_or(u16::MAX);
+        let flags = direction.flags() | RTE_PDUMP_FLAG_PCAPNG;
+        check_errno(unsafe {
+            rte_pdump_enable_bpf(
+                port.id(),
+                queue,
+                flags,
+                snaplen,
+                ring.as_ptr(),
+                pool.as_ptr(),
+                std::ptr::null(),
+            )
+        })?;
+        Ok(Self {
+            port,
+            queue,
+            flags,
+            _resources: PhantomData,
+        })
+    }
+
+    pub fn port(&self) -> Port {
+        self.port
+    }
+}
+
+impl Drop for Pdump<'_> {
+    fn drop(&mut self) {
+        unsafe { rte_pdump_disable(self.port.id(), self.queue, self.flags) };
+    }
+}
@@ -0,0 +1,211 @@
+use crate::error::{Error, Result, EINVAL};
+use crate::*;
+
+/// A 1 byte field matched against a value and a bitmask, e.g. the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U8Field(pub(crate) u8);
+
+/// A 4 byte field matched against an IPv4 prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ipv4Field(pub(crate) u8);
+
+/// A 16 byte field matched against an IPv6 prefix, made of 4 ACL fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ipv6Field(pub(crate) u8);
+
+/// A 2 byte field matched against a range, e.g. a TCP or UDP port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortField(pub(crate) u8);
+
+/// The fields of the keys classified by an [`Acl`](super::Acl), built
+/// instead of an `rte_acl_field_def` array.
+///
+/// Offsets are relative to the start of the key, e.g. the L3 header when
+/// classifying packets in place. Keys are read in network byte order.
+#[derive(Debug, Clone)]
+pub struct AclLayout {
+    pub(crate) defs: Vec<rte_acl_field_def>,
+}
+
+impl AclLayout {
+    pub fn builder() -> AclLayoutBuilder {
+        AclLayoutBuilder {
+            defs: Vec::new(),
+            input_index: 0,
+        }
+    }
+
+    pub fn num_fields(&self) -> usize {
+        self.defs.len()
+    }
+
+    /// The number of bytes a key must have to hold all fields.
+    ///
+    /// The ACL library reads each input group as a whole 4 byte word from
+    /// the offset of its first field, so a key extends to the end of the
+    /// word of its last group even if the fields end before.
+    pub fn key_len(&self) -> usize {
+        self.defs
+            .iter()
+            .map(|def| {
+                let start = self
+                    .defs
+                    .iter()
+                    .filter(|other| other.input_index == def.input_index)
+                    .map(|other| other.offset)
+                    .min()
+                    .unwrap_or(def.offset);
+                (start as usize + 4).max(def.offset as usize + def.size as usize)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Builder for an [`AclLayout`].
+///
+/// The ACL library reads keys 4 bytes at a time after a leading 1 byte
+/// field, so the first field must be a [`U8Field`], and the other fields
+/// are packed in 4 byte words: each IPv4 field or IPv6 quarter takes a word,
+/// and ports come in pairs.
+#[derive(Debug)]
+pub struct AclLayoutBuilder {
+    defs: Vec<rte_acl_field_def>,
+    input_index: u8,
+}
+
+impl AclLayoutBuilder {
+    fn push(&mut self, type_: u32, size: u32, offset: u32, input_index: u8) -> u8 {
+        let field_index = self.defs.len() as u8;
+        self.defs.push(rte_acl_field_def {
+            type_: type_ as u8,
+            size: size as u8,
+            field_index,
+            input_index,
+            offset,
+        });
+        field_index
+    }
+
+    fn next_input(&mut self) -> u8 {
+        let input = self.input_index;
+        self.input_index += 1;
+        input
+    }
+
+    /// A 1 byte bitmask field, such as the IPv4 protocol or the IPv6 next
+    /// header.
+    pub fn u8_field(&mut self, offset: u32) -> U8Field {
+        let input = self.next_input();
+        U8Field(self.push(RTE_ACL_FIELD_TYPE_BITMASK, 1, offset, input))
+    }
+
+    pub fn ipv4(&mut self, offset: u32) -> Ipv4Field {
+        let input = self.next_input();
+        Ipv4Field(self.push(RTE_ACL_FIELD_TYPE_MASK, 4, offset, input))
+    }
+
+    pub fn ipv6(&mut self, offset: u32) -> Ipv6Field {
+        let mut first = 0;
+        for i in 0..4 {
+            let input = self.next_input();
+            let index = self.push(RTE_ACL_FIELD_TYPE_MASK, 4, offset + i * 4, input);
+            if i == 0 {
+                first = index;
+            }
+        }
+        Ipv6Field(first)
+    }
+
+    /// Two consecutive 2 byte range fields, the source and destination ports
+    /// of TCP, UDP and SCTP.
+    pub fn ports(&mut self, offset: u32) -> (PortField, PortField) {
+        let input = self.next_input();
+        let src = self.push(RTE_ACL_FIELD_TYPE_RANGE, 2, offset, input);
+        let dst = self.push(RTE_ACL_FIELD_TYPE_RANGE, 2, offset + 2, input);
+        (PortField(src), PortField(dst))
+    }
+
+    pub fn build(self) -> Result<AclLayout> {
+        match self.defs.first() {
+            Some(def) if def.size == 1 => {}
+            _ => return Err(Error::new(EINVAL)),
+        }
+        if self.defs.len() > RTE_ACL_MAX_FIELDS as usize {
+            return Err(Error::new(EINVAL));
+        }
+        Ok(AclLayout { defs: self.defs })
+    }
+}
+
+/// The 5-tuple of IPv4 packets without options, classified in place from
+/// the start of the IPv4 header.
+#[derive(Debug, Clone)]
+pub struct Ipv4FiveTuple {
+    pub layout: AclLayout,
+    pub proto: U8Field,
+    pub src: Ipv4Field,
+    pub dst: Ipv4Field,
+    pub src_port: PortField,
+    pub dst_port: PortField,
+}
+
+impl Ipv4FiveTuple {
+    pub fn new() -> Self {
+        let mut b = AclLayout::builder();
+        let proto = b.u8_field(9);
+        let src = b.ipv4(12);
+        let dst = b.ipv4(16);
+        let (src_port, dst_port) = b.ports(20);
+        Self {
+            layout: b.build().unwrap(),
+            proto,
+            src,
+            dst,
+            src_port,
+            dst_port,
+        }
+    }
+}
+
+impl Default for Ipv4FiveTuple {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The 5-tuple of IPv6 packets without extension headers, classified in
+/// place from the start of the IPv6 header.
+#[derive(Debug, Clone)]
+pub struct Ipv6FiveTuple {
+    pub layout: AclLayout,
+    pub proto: U8Field,
+    pub src: Ipv6Field,
+    pub dst: Ipv6Field,
+    pub src_port: PortField,
+    pub dst_port: PortField,
+}
+
+impl Ipv6FiveTuple {
+    pub fn new() -> Self {
+        let mut b = AclLayout::builder();
+        let proto = b.u8_field(6);
+        let src = b.ipv6(8);
+        let dst = b.ipv6(24);
+        let (src_port, dst_port) = b.ports(40);
+        Self {
+            layout: b.build().unwrap(),
+            proto,
+            src,
+            dst,
+            src_port,
+            dst_port,
+        }
+    }
+}
+
+impl Default for Ipv6FiveTuple {
+    fn default() -> Self {
+        Self::new()
+    }
+}
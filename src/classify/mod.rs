@@ -0,0 +1,230 @@
+mod layout;
+mod rule;
+
+pub use layout::*;
+pub use rule::*;
+
+use std::ffi::CString;
+use std::mem;
+use std::ptr::NonNull;
+
+use crate::error::{check, Error, Result, EINVAL, SOCKET_ID_ANY};
+use crate::mbuf::Mbuf;
+use crate::*;
+
+/// The classification method used by an [`Acl`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassifyAlg {
+    /// The best method supported by the CPU.
+    Default,
+    Scalar,
+    Sse,
+    Avx2,
+    Neon,
+    Altivec,
+    Avx512x16,
+    Avx512x32,
+}
+
+impl ClassifyAlg {
+    fn as_raw(&self) -> rte_acl_classify_alg {
+        match self {
+            ClassifyAlg::Default => rte_acl_classify_alg_RTE_ACL_CLASSIFY_DEFAULT,
+            ClassifyAlg::Scalar => rte_acl_classify_alg_RTE_ACL_CLASSIFY_SCALAR,
+            ClassifyAlg::Sse => rte_acl_classify_alg_RTE_ACL_CLASSIFY_SSE,
+            ClassifyAlg::Avx2 => rte_acl_classify_alg_RTE_ACL_CLASSIFY_AVX2,
+            ClassifyAlg::Neon => rte_acl_classify_alg_RTE_ACL_CLASSIFY_NEON,
+            ClassifyAlg::Altivec => rte_acl_classify_alg_RTE_ACL_CLASSIFY_ALTIVEC,
+            ClassifyAlg::Avx512x16 => rte_acl_classify_alg_RTE_ACL_CLASSIFY_AVX512X16,
+            ClassifyAlg::Avx512x32 => rte_acl_classify_alg_RTE_ACL_CLASSIFY_AVX512X32,
+        }
+    }
+}
+
+/// An ACL context, `rte_acl_ctx`, classifying keys against a set of rules.
+///
+/// Rules are added with [`add_rules`](Self::add_rules) and take effect once
+/// [`build`](Self::build) compiled them.
+pub struct Acl {
+    raw: NonNull<rte_acl_ctx>,
+    layout: AclLayout,
+    categories: u32,
+    built: bool,
+}
+
+unsafe impl Send for Acl {}
+unsafe impl Sync for Acl {}
+
+impl Acl {
+    pub fn new(name: &str, layout: AclLayout, max_rules: u32) -> Result<Self> {
+        Self::with_socket(name, layout, max_rules, None)
+    }
+
+    pub fn with_socket(
+        name: &str,
+        layout: AclLayout,
+        max_rules: u32,
+        socket_id: Option<u32>,
+    ) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let param = rte_acl_param {
+            name: name.as_ptr(),
+            socket_id: socket_id.map_or(SOCKET_ID_ANY, |id| id as i32),
+            rule_size: Self::rule_slots(&layout) as u32 * mem::size_of::<rte_acl_field>() as u32,
+            max_rule_num: max_rules,
+        };
+        let raw = NonNull::new(unsafe { rte_acl_create(&param) }).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            layout,
+            categories: 1,
+            built: false,
+        })
+    }
+
+    /// The size of a rule in `rte_acl_field`s, the rule data taking the
+    /// first one.
+    fn rule_slots(layout: &AclLayout) -> usize {
+        mem::size_of::<rte_acl_rule>() / mem::size_of::<rte_acl_field>() + layout.num_fields()
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_acl_ctx {
+        self.raw.as_ptr()
+    }
+
+    pub fn layout(&self) -> &AclLayout {
+        &self.layout
+    }
+
+    pub fn add_rules(&mut self, rules: &[AclRule]) -> Result<()> {
+        let slots = Self::rule_slots(&self.layout);
+        let header = slots - self.layout.num_fields();
+        let mut buf: Vec<rte_acl_field> = vec![unsafe { mem::zeroed() }; slots * rules.len()];
+        for (rule, chunk) in rules.iter().zip(buf.chunks_mut(slots)) {
+            if rule.userdata == 0
+                || rule.priority < RTE_ACL_MIN_PRIORITY as i32
+                || rule.priority > RTE_ACL_MAX_PRIORITY as i32
+            {
+                return Err(Error::new(EINVAL));
+            }
+            let data = chunk.as_mut_ptr() as *mut rte_acl_rule_data;
+            unsafe {
+                (*data).category_mask = rule.category_mask;
+                (*data).priority = rule.priority;
+                (*data).userdata = rule.userdata;
+            }
+            rule.encode(&self.layout, &mut chunk[header..]);
+        }
+        check(unsafe {
+            rte_acl_add_rules(
+                self.as_ptr(),
+                buf.as_ptr() as *const rte_acl_rule,
+                rules.len() as u32,
+            )
+        })
+        .map(|_| ())
+    }
+
+    /// Delete all rules. The rules of the last build stay in effect until
+    /// the next one.
+    pub fn reset_rules(&mut self) {
+        unsafe { rte_acl_reset_rules(self.as_ptr()) };
+    }
+
+    /// The number of categories classified at once, 1 by default. Takes
+    /// effect on the next build.
+    pub fn set_categories(&mut self, categories: u32) -> Result<()> {
+        if categories == 0
+            || categories > RTE_ACL_MAX_CATEGORIES
+            || (categories > 1 && !categories.is_multiple_of(RTE_ACL_RESULTS_MULTIPLIER))
+        {
+            return Err(Error::new(EINVAL));
+        }
+        self.categories = categories;
+        Ok(())
+    }
+
+    pub fn categories(&self) -> u32 {
+        self.categories
+    }
+
+    /// Compile the rules added so far.
+    pub fn build(&mut self) -> Result<()> {
+        let mut cfg: rte_acl_config = unsafe { mem::zeroed() };
+        cfg.num_categories = self.categories;
+        cfg.num_fields = self.layout.num_fields() as u32;
+        cfg.defs[..self.layout.defs.len()].copy_from_slice(&self.layout.defs);
+        check(unsafe { rte_acl_build(self.as_ptr(), &cfg) })?;
+        self.built = true;
+        Ok(())
+    }
+
+    pub fn set_algorithm(&mut self, alg: ClassifyAlg) -> Result<()> {
+        check(unsafe { rte_acl_set_ctx_classify(self.as_ptr(), alg.as_raw()) }).map(|_| ())
+    }
+
+    /// Classify a burst of keys laid out as [`layout`](Self::layout), in
+    /// network byte order.
+    ///
+    /// Returns the userdata of the best matching rule for each key and
+    /// category, key after key, `None` where no rule matched.
+    pub fn classify(&self, keys: &[&[u8]]) -> Result<Vec<Option<u32>>> {
+        if !self.built {
+            return Err(Error::new(EINVAL));
+        }
+        let key_len = self.layout.key_len();
+        if keys.iter().any(|key| key.len() < key_len) {
+            return Err(Error::new(EINVAL));
+        }
+        let mut data: Vec<*const u8> = keys.iter().map(|key| key.as_ptr()).collect();
+        let mut results = vec![0u32; keys.len() * self.categories as usize];
+        check(unsafe {
+            rte_acl_classify(
+                self.as_ptr(),
+                data.as_mut_ptr(),
+                results.as_mut_ptr(),
+                keys.len() as u32,
+                self.categories,
+            )
+        })?;
+        Ok(results
+            .into_iter()
+            .map(|r| if r == 0 { None } else { Some(r) })
+            .collect())
+    }
+
+    /// Classify a burst of packets in place, their keys starting `offset`
+    /// bytes into the first segment, e.g. at the L3 header.
+    ///
+    /// Packets too short to hold a key get no match.
+    pub fn classify_mbufs(&self, mbufs: &[Mbuf], offset: usize) -> Result<Vec<Option<u32>>> {
+        let key_len = self.layout.key_len();
+        let keys: Vec<(usize, &[u8])> = mbufs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| {
+                let key = m.data().get(offset..)?;
+                (key.len() >= key_len).then_some((i, key))
+            })
+            .collect();
+        let found = self.classify(&keys.iter().map(|(_, key)| *key).collect::<Vec<_>>())?;
+
+        let categories = self.categories as usize;
+        let mut results = vec![None; mbufs.len() * categories];
+        for ((i, _), found) in keys.iter().zip(found.chunks(categories)) {
+            results[i * categories..(i + 1) * categories].copy_from_slice(found);
+        }
+        Ok(results)
+    }
+
+    /// Print the context to the log.
+    pub fn dump(&self) {
+        unsafe { rte_acl_dump(self.as_ptr()) };
+    }
+}
+
+impl Drop for Acl {
+    fn drop(&mut self) {
+        unsafe { rte_acl_free(self.raw.as_ptr()) };
+    }
+}
@@ -0,0 +1,102 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::RangeInclusive;
+
+use super::{AclLayout, Ipv4Field, Ipv6Field, PortField, U8Field};
+use crate::*;
+
+/// A classification rule: the values matched by each field of an
+/// [`AclLayout`], a priority and the userdata returned on match.
+///
+/// Fields left unset match anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AclRule {
+    pub(crate) priority: i32,
+    pub(crate) userdata: u32,
+    pub(crate) category_mask: u32,
+    // (field index, value, mask or range end)
+    fields: Vec<(u8, u64, u64)>,
+}
+
+impl AclRule {
+    /// A rule returning `userdata`, which must not be 0, when it is the
+    /// highest `priority` match.
+    pub fn new(priority: i32, userdata: u32) -> Self {
+        Self {
+            priority,
+            userdata,
+            category_mask: 1,
+            fields: Vec::new(),
+        }
+    }
+
+    /// The categories the rule applies to, as a bitmask. Defaults to the
+    /// first category.
+    pub fn categories(mut self, mask: u32) -> Self {
+        self.category_mask = mask;
+        self
+    }
+
+    fn set(mut self, index: u8, value: u64, mask_range: u64) -> Self {
+        self.fields.retain(|(i, _, _)| *i != index);
+        self.fields.push((index, value, mask_range));
+        self
+    }
+
+    /// Match the bits of `value` selected by `mask`.
+    pub fn bitmask(self, field: U8Field, value: u8, mask: u8) -> Self {
+        self.set(field.0, value as u64, mask as u64)
+    }
+
+    pub fn proto(self, field: U8Field, proto: u8) -> Self {
+        self.bitmask(field, proto, u8::MAX)
+    }
+
+    pub fn ipv4(self, field: Ipv4Field, addr: Ipv4Addr, prefix_len: u8) -> Self {
+        self.set(field.0, u32::from(addr) as u64, prefix_len.min(32) as u64)
+    }
+
+    pub fn ipv6(mut self, field: Ipv6Field, addr: Ipv6Addr, prefix_len: u8) -> Self {
+        let addr = u128::from(addr);
+        let prefix_len = prefix_len.min(128) as u64;
+        // Each quarter of the address is its own field, with the part of the
+        // prefix length that falls into it.
+        for i in 0..4u8 {
+            let word = (addr >> (96 - 32 * i as u32)) as u32;
+            let len = prefix_len.saturating_sub(32 * i as u64).min(32);
+            self = self.set(field.0 + i, word as u64, len);
+        }
+        self
+    }
+
+    pub fn port_range(self, field: PortField, range: RangeInclusive<u16>) -> Self {
+        self.set(field.0, *range.start() as u64, *range.end() as u64)
+    }
+
+    pub fn port(self, field: PortField, port: u16) -> Self {
+        self.port_range(field, port..=port)
+    }
+
+    /// Write the fields of the rule as laid out by `layout`.
+    pub(crate) fn encode(&self, layout: &AclLayout, fields: &mut [rte_acl_field]) {
+        for (def, field) in layout.defs.iter().zip(fields.iter_mut()) {
+            let (value, mask_range) = match self.fields.iter().find(|f| f.0 == def.field_index) {
+                Some(&(_, value, mask_range)) => (value, mask_range),
+                None if def.type_ as u32 == RTE_ACL_FIELD_TYPE_RANGE => {
+                    (0, u64::MAX >> (64 - 8 * def.size as u32))
+                }
+                None => (0, 0),
+            };
+            field.value = field_value(def.size, value);
+            field.mask_range = field_value(def.size, mask_range);
+        }
+    }
+}
+
+fn field_value(size: u8, value: u64) -> rte_acl_field_types {
+    match size {
+        1 => rte_acl_field_types { u8_: value as u8 },
+        2 => rte_acl_field_types { u16_: value as u16 },
+        4 => rte_acl_field_types { u32_: value as u32 },
+        _ => rte_acl_field_types { u64_: value },
+    }
+}
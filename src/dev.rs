@@ -0,0 +1,193 @@
+use std::ffi::CString;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{check, Error, Result, EINVAL};
+use crate::ethdev::{ports_matching, Device, Port};
+use crate::*;
+
+/// A device argument string, `[bus:]name[,key=value...]`, as accepted by
+/// `rte_dev_probe` and the `--vdev`/`-a` EAL options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Devargs {
+    bus: Option<String>,
+    name: String,
+    args: Vec<(String, String)>,
+}
+
+impl Devargs {
+    /// Devargs for `name`, leaving the bus to be detected from the name.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            bus: None,
+            name: name.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// A virtual device such as `net_null0`, `net_ring0`, `net_tap0`,
+    /// `net_memif0` or `net_af_packet0`.
+    pub fn vdev<S: Into<String>>(name: S) -> Self {
+        Self::new(name).bus("vdev")
+    }
+
+    /// A PCI device, `addr` being its `domain:bus:device.function` address.
+    pub fn pci<S: Into<String>>(addr: S) -> Self {
+        Self::new(addr).bus("pci")
+    }
+
+    pub fn bus<S: Into<String>>(mut self, bus: S) -> Self {
+        self.bus = Some(bus.into());
+        self
+    }
+
+    /// Append a driver argument.
+    pub fn arg<K: Into<String>, V: ToString>(mut self, key: K, value: V) -> Self {
+        self.args.push((key.into(), value.to_string()));
+        self
+    }
+
+    pub fn bus_name(&self) -> Option<&str> {
+        self.bus.as_deref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.args.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// The driver arguments joined as `key=value,...`.
+    pub fn drvargs(&self) -> String {
+        self.args
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Probe the device and return the ports it created.
+    pub fn attach(&self) -> Result<Vec<Port>> {
+        let devargs = CString::new(self.to_string()).map_err(|_| Error::new(EINVAL))?;
+        check(unsafe { rte_dev_probe(devargs.as_ptr()) })?;
+        Ok(ports_matching(&self.to_string())?.collect())
+    }
+
+    /// Remove the device. Its ports should be stopped and closed first.
+    pub fn detach(&self) -> Result<()> {
+        let bus = match &self.bus {
+            Some(bus) => bus.clone(),
+            None => self
+                .device()?
+                .and_then(|dev| dev.bus_name())
+                .ok_or(Error::new(EINVAL))?,
+        };
+        let bus = CString::new(bus).map_err(|_| Error::new(EINVAL))?;
+        let name = CString::new(self.name.as_str()).map_err(|_| Error::new(EINVAL))?;
+        check(unsafe { rte_eal_hotplug_remove(bus.as_ptr(), name.as_ptr()) }).map(|_| ())
+    }
+
+    fn device(&self) -> Result<Option<Device>> {
+        match ports_matching(&self.to_string())?.next() {
+            // Only used before detaching, while the device is attached.
+            Some(port) => unsafe { port.device() },
+            None => Ok(None),
+        }
+    }
+}
+
+impl fmt::Display for Devargs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(bus) = &self.bus {
+            write!(f, "{}:", bus)?;
+        }
+        f.write_str(&self.name)?;
+        for (k, v) in self.args.iter() {
+            write!(f, ",{}={}", k, v)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Devargs {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split(',');
+        let dev = parts.next().filter(|d| !d.is_empty());
+        let dev = dev.ok_or(Error::new(EINVAL))?;
+        // PCI addresses contain ':' themselves, so only a known bus prefix is
+        // split off.
+        let mut devargs = match dev.split_once(':') {
+            Some((bus, name)) if KNOWN_BUSES.contains(&bus) => Devargs::new(name).bus(bus),
+            _ => Devargs::new(dev),
+        };
+        for part in parts {
+            let (k, v) = part.split_once('=').ok_or(Error::new(EINVAL))?;
+            devargs = devargs.arg(k, v);
+        }
+        Ok(devargs)
+    }
+}
+
+const KNOWN_BUSES: &[&str] = &[
+    "auxiliary",
+    "cdx",
+    "dpaa_bus",
+    "fslmc",
+    "ifpga",
+    "pci",
+    "platform",
+    "vdev",
+    "vmbus",
+];
+
+impl Device {
+    /// Remove the device. Its ports should be stopped and closed first.
+    ///
+    /// [`Devargs::detach`] removes a device by bus and name instead.
+    ///
+    /// # Safety
+    ///
+    /// The device must still be attached, and neither this handle nor its
+    /// copies may be used afterwards.
+    pub unsafe fn remove(self) -> Result<()> {
+        check(rte_dev_remove(self.as_ptr() as *mut rte_device)).map(|_| ())
+    }
+}
+
+/// Attach a device by bus and name, as `rte_eal_hotplug_add`.
+pub fn hotplug_add(bus: &str, name: &str, drvargs: &str) -> Result<()> {
+    let bus = CString::new(bus).map_err(|_| Error::new(EINVAL))?;
+    let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+    let drvargs = CString::new(drvargs).map_err(|_| Error::new(EINVAL))?;
+    check(unsafe { rte_eal_hotplug_add(bus.as_ptr(), name.as_ptr(), drvargs.as_ptr()) }).map(|_| ())
+}
+
+/// Detach a device by bus and name, as `rte_eal_hotplug_remove`.
+pub fn hotplug_remove(bus: &str, name: &str) -> Result<()> {
+    let bus = CString::new(bus).map_err(|_| Error::new(EINVAL))?;
+    let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+    check(unsafe { rte_eal_hotplug_remove(bus.as_ptr(), name.as_ptr()) }).map(|_| ())
+}
+
+/// Start monitoring kernel uevents, so hot-added and hot-removed devices are
+/// reported to the EAL. With `handle_removal`, the EAL also handles the
+/// SIGBUS raised when a removed device is still accessed.
+pub fn start_event_monitor(handle_removal: bool) -> Result<()> {
+    check(unsafe { rte_dev_event_monitor_start() })?;
+    if handle_removal {
+        if let Err(e) = check(unsafe { rte_dev_hotplug_handle_enable() }) {
+            unsafe { rte_dev_event_monitor_stop() };
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+pub fn stop_event_monitor() -> Result<()> {
+    unsafe { rte_dev_hotplug_handle_disable() };
+    check(unsafe { rte_dev_event_monitor_stop() }).map(|_| ())
+}
@@ -0,0 +1,78 @@
+use std::ffi::CStr;
+use std::fmt;
+
+use crate::{rte_errno_, rte_strerror};
+
+/// An error reported by DPDK, identified by its errno value.
+///
+/// DPDK functions report failures either by returning a negative errno or by
+/// returning `-1` and setting `rte_errno`. Both are converted to this type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Error {
+    errno: i32,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// errno values reported by DPDK, as defined on Linux.
+pub const EPERM: i32 = 1;
+pub const ENOENT: i32 = 2;
+pub const E2BIG: i32 = 7;
+pub const EAGAIN: i32 = 11;
+pub const ENOMEM: i32 = 12;
+pub const EBUSY: i32 = 16;
+pub const EEXIST: i32 = 17;
+pub const ENODEV: i32 = 19;
+pub const EINVAL: i32 = 22;
+pub const ENOSPC: i32 = 28;
+pub const ENOBUFS: i32 = 105;
+pub const ETIMEDOUT: i32 = 110;
+
+/// `SOCKET_ID_ANY`, for allocations that may use any NUMA socket.
+pub(crate) const SOCKET_ID_ANY: i32 = -1;
+
+impl Error {
+    pub const fn new(errno: i32) -> Self {
+        Self { errno }
+    }
+
+    /// Build an error from the current value of `rte_errno`.
+    pub fn last() -> Self {
+        Self::new(unsafe { rte_errno_() })
+    }
+
+    pub const fn errno(&self) -> i32 {
+        self.errno
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = unsafe { CStr::from_ptr(rte_strerror(self.errno)) };
+        write!(f, "{} (errno {})", msg.to_string_lossy(), self.errno)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Convert the return value of a DPDK function that returns a negative errno
+/// on failure.
+#[inline]
+pub(crate) fn check(ret: i32) -> Result<i32> {
+    if ret < 0 {
+        Err(Error::new(-ret))
+    } else {
+        Ok(ret)
+    }
+}
+
+/// Convert the return value of a DPDK function that returns `-1` and sets
+/// `rte_errno` on failure.
+#[inline]
+pub(crate) fn check_errno(ret: i32) -> Result<i32> {
+    if ret < 0 {
+        Err(Error::last())
+    } else {
+        Ok(ret)
+    }
+}
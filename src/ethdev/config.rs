@@ -0,0 +1,154 @@
+use std::mem::MaybeUninit;
+use std::ops::{BitOr, BitOrAssign};
+
+use super::{EtherAddr, Port};
+use crate::error::{check, Error, Result, EINVAL};
+use crate::*;
+
+/// VLAN offloads toggled with [`Port::set_vlan_offload`], a combination of
+/// the `RTE_ETH_*_OFFLOAD` flags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VlanOffload(u32);
+
+impl VlanOffload {
+    pub const STRIP: Self = Self(RTE_ETH_VLAN_STRIP_OFFLOAD);
+    pub const FILTER: Self = Self(RTE_ETH_VLAN_FILTER_OFFLOAD);
+    pub const EXTEND: Self = Self(RTE_ETH_VLAN_EXTEND_OFFLOAD);
+    pub const QINQ_STRIP: Self = Self(RTE_ETH_QINQ_STRIP_OFFLOAD);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for VlanOffload {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for VlanOffload {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl Port {
+    pub fn mac_addr(&self) -> Result<EtherAddr> {
+        let mut addr = MaybeUninit::<rte_ether_addr>::zeroed();
+        check(unsafe { rte_eth_macaddr_get(self.id(), addr.as_mut_ptr()) })?;
+        Ok(EtherAddr::from(unsafe { addr.assume_init() }))
+    }
+
+    /// Replace the default MAC address of the port.
+    pub fn set_default_mac_addr(&self, addr: EtherAddr) -> Result<()> {
+        let mut addr = addr;
+        check(unsafe { rte_eth_dev_default_mac_addr_set(self.id(), addr.as_mut_ptr()) }).map(|_| ())
+    }
+
+    /// Add a secondary MAC address to the VMDq `pool`, 0 if VMDq is not used.
+    pub fn add_mac_addr(&self, addr: EtherAddr, pool: u32) -> Result<()> {
+        let mut addr = addr;
+        check(unsafe { rte_eth_dev_mac_addr_add(self.id(), addr.as_mut_ptr(), pool) }).map(|_| ())
+    }
+
+    pub fn remove_mac_addr(&self, addr: EtherAddr) -> Result<()> {
+        let mut addr = addr;
+        check(unsafe { rte_eth_dev_mac_addr_remove(self.id(), addr.as_mut_ptr()) }).map(|_| ())
+    }
+
+    /// Replace the multicast addresses the port accepts. An empty slice
+    /// flushes the list.
+    pub fn set_mc_addr_list(&self, addrs: &[EtherAddr]) -> Result<()> {
+        let mut addrs: Vec<rte_ether_addr> = addrs.iter().map(|a| (*a).into()).collect();
+        let ptr = if addrs.is_empty() {
+            std::ptr::null_mut()
+        } else {
+            addrs.as_mut_ptr()
+        };
+        check(unsafe { rte_eth_dev_set_mc_addr_list(self.id(), ptr, addrs.len() as u32) })
+            .map(|_| ())
+    }
+
+    pub fn mtu(&self) -> Result<u16> {
+        let mut mtu = 0;
+        check(unsafe { rte_eth_dev_get_mtu(self.id(), &mut mtu) })?;
+        Ok(mtu)
+    }
+
+    pub fn set_mtu(&self, mtu: u16) -> Result<()> {
+        check(unsafe { rte_eth_dev_set_mtu(self.id(), mtu) }).map(|_| ())
+    }
+
+    pub fn promiscuous(&self) -> Result<bool> {
+        let ret = unsafe { rte_eth_promiscuous_get(self.id()) };
+        if ret < 0 {
+            return Err(Error::new(EINVAL));
+        }
+        Ok(ret == 1)
+    }
+
+    pub fn set_promiscuous(&self, on: bool) -> Result<()> {
+        let ret = if on {
+            unsafe { rte_eth_promiscuous_enable(self.id()) }
+        } else {
+            unsafe { rte_eth_promiscuous_disable(self.id()) }
+        };
+        check(ret).map(|_| ())
+    }
+
+    pub fn allmulticast(&self) -> Result<bool> {
+        let ret = unsafe { rte_eth_allmulticast_get(self.id()) };
+        if ret < 0 {
+            return Err(Error::new(EINVAL));
+        }
+        Ok(ret == 1)
+    }
+
+    pub fn set_allmulticast(&self, on: bool) -> Result<()> {
+        let ret = if on {
+            unsafe { rte_eth_allmulticast_enable(self.id()) }
+        } else {
+            unsafe { rte_eth_allmulticast_disable(self.id()) }
+        };
+        check(ret).map(|_| ())
+    }
+
+    /// Accept (`on`) or drop packets tagged with `vlan_id`. Requires the VLAN
+    /// filter offload.
+    pub fn vlan_filter(&self, vlan_id: u16, on: bool) -> Result<()> {
+        if vlan_id as u32 > RTE_ETH_VLAN_ID_MAX {
+            return Err(Error::new(EINVAL));
+        }
+        check(unsafe { rte_eth_dev_vlan_filter(self.id(), vlan_id, on as i32) }).map(|_| ())
+    }
+
+    pub fn vlan_offload(&self) -> Result<VlanOffload> {
+        let ret = check(unsafe { rte_eth_dev_get_vlan_offload(self.id()) })?;
+        Ok(VlanOffload(ret as u32))
+    }
+
+    /// Set the VLAN offloads of the port. Offloads missing from `offload` are
+    /// disabled.
+    pub fn set_vlan_offload(&self, offload: VlanOffload) -> Result<()> {
+        check(unsafe { rte_eth_dev_set_vlan_offload(self.id(), offload.bits() as i32) }).map(|_| ())
+    }
+
+    /// Set (`on`) or clear the port based VLAN id inserted on transmit.
+    pub fn set_vlan_pvid(&self, pvid: u16, on: bool) -> Result<()> {
+        if pvid as u32 > RTE_ETH_VLAN_ID_MAX {
+            return Err(Error::new(EINVAL));
+        }
+        check(unsafe { rte_eth_dev_set_vlan_pvid(self.id(), pvid, on as i32) }).map(|_| ())
+    }
+}
@@ -0,0 +1,113 @@
+use std::ffi::{CStr, CString};
+use std::mem::MaybeUninit;
+
+use super::Port;
+use crate::error::{check, Error, Result, EINVAL};
+use crate::*;
+
+/// Iterate over all usable ports, with the semantics of `RTE_ETH_FOREACH_DEV`:
+/// port ids may have holes, and ports owned by another entity are skipped.
+pub fn ports() -> Ports {
+    Ports { next: 0 }
+}
+
+/// The number of ports returned by [`ports`].
+pub fn port_count() -> u16 {
+    unsafe { rte_eth_dev_count_avail() }
+}
+
+pub struct Ports {
+    next: u16,
+}
+
+impl Iterator for Ports {
+    type Item = Port;
+
+    fn next(&mut self) -> Option<Port> {
+        if self.next as u32 >= RTE_MAX_ETHPORTS {
+            return None;
+        }
+        let id = unsafe { rte_eth_find_next_owned_by(self.next, RTE_ETH_DEV_NO_OWNER as u64) };
+        if id >= RTE_MAX_ETHPORTS as u64 {
+            self.next = RTE_MAX_ETHPORTS as u16;
+            return None;
+        }
+        self.next = id as u16 + 1;
+        Some(Port::new(id as u16))
+    }
+}
+
+/// Iterate over the ports matching `devargs`, such as a device name
+/// (`0000:01:00.0`, `net_ring0`) or a filter (`class=eth,mac=00:11:22:33:44:55`).
+pub fn ports_matching(devargs: &str) -> Result<PortsMatching> {
+    let devargs = CString::new(devargs).map_err(|_| Error::new(EINVAL))?;
+    let mut iter = MaybeUninit::<rte_dev_iterator>::zeroed();
+    check(unsafe { rte_eth_iterator_init(iter.as_mut_ptr(), devargs.as_ptr()) })?;
+    Ok(PortsMatching {
+        iter: unsafe { iter.assume_init() },
+        _devargs: devargs,
+        done: false,
+    })
+}
+
+pub struct PortsMatching {
+    iter: rte_dev_iterator,
+    // The iterator keeps pointers into the devargs string.
+    _devargs: CString,
+    done: bool,
+}
+
+impl Iterator for PortsMatching {
+    type Item = Port;
+
+    fn next(&mut self) -> Option<Port> {
+        if self.done {
+            return None;
+        }
+        let id = unsafe { rte_eth_iterator_next(&mut self.iter) };
+        if id as u32 >= RTE_MAX_ETHPORTS {
+            self.done = true;
+            return None;
+        }
+        Some(Port::new(id))
+    }
+}
+
+impl Drop for PortsMatching {
+    fn drop(&mut self) {
+        // A no-op if the iteration already reached its end.
+        unsafe { rte_eth_iterator_cleanup(&mut self.iter) };
+    }
+}
+
+impl Port {
+    /// Look up a port by the name of its device, e.g. a PCI address or a
+    /// vdev name.
+    pub fn by_name(name: &str) -> Result<Port> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let mut id = 0;
+        check(unsafe { rte_eth_dev_get_port_by_name(name.as_ptr(), &mut id) })?;
+        Ok(Port::new(id))
+    }
+
+    pub fn name(&self) -> Result<String> {
+        let mut buf = [0 as std::os::raw::c_char; RTE_ETH_NAME_MAX_LEN as usize];
+        check(unsafe { rte_eth_dev_get_name_by_port(self.id(), buf.as_mut_ptr()) })?;
+        let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Ok(name.to_string_lossy().into_owned())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        unsafe { rte_eth_dev_is_valid_port(self.id()) == 1 }
+    }
+
+    /// The NUMA socket the port is attached to, `None` if unknown.
+    pub fn socket_id(&self) -> Option<u32> {
+        let socket_id = unsafe { rte_eth_dev_socket_id(self.id()) };
+        if socket_id < 0 {
+            None
+        } else {
+            Some(socket_id as u32)
+        }
+    }
+}
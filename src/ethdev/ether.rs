@@ -0,0 +1,147 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::error::{Error, EINVAL};
+use crate::{rte_ether_addr, RTE_ETHER_ADDR_LEN, RTE_ETHER_GROUP_ADDR, RTE_ETHER_LOCAL_ADMIN_ADDR};
+
+/// An Ethernet MAC address, layout compatible with `rte_ether_addr`.
+///
+/// Formatting follows `RTE_ETHER_ADDR_PRT_FMT` (`%02X:%02X:...`). Parsing
+/// accepts the same formats as `rte_ether_unformat_addr`:
+/// `XX:XX:XX:XX:XX:XX`, `XX-XX-XX-XX-XX-XX` and `XXXX:XXXX:XXXX`.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct EtherAddr(rte_ether_addr);
+
+impl EtherAddr {
+    pub const ZERO: Self = Self::new([0; RTE_ETHER_ADDR_LEN as usize]);
+    pub const BROADCAST: Self = Self::new([0xff; RTE_ETHER_ADDR_LEN as usize]);
+
+    pub const fn new(bytes: [u8; RTE_ETHER_ADDR_LEN as usize]) -> Self {
+        Self(rte_ether_addr { addr_bytes: bytes })
+    }
+
+    pub const fn bytes(&self) -> [u8; RTE_ETHER_ADDR_LEN as usize] {
+        self.0.addr_bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.bytes() == Self::ZERO.bytes()
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.bytes() == Self::BROADCAST.bytes()
+    }
+
+    pub fn is_multicast(&self) -> bool {
+        self.0.addr_bytes[0] & RTE_ETHER_GROUP_ADDR as u8 != 0
+    }
+
+    pub fn is_unicast(&self) -> bool {
+        !self.is_multicast()
+    }
+
+    pub fn is_local_admin(&self) -> bool {
+        self.0.addr_bytes[0] & RTE_ETHER_LOCAL_ADMIN_ADDR as u8 != 0
+    }
+
+    /// A unicast address that is not all zeros, as `rte_is_valid_assigned_ether_addr`.
+    pub fn is_valid_assigned(&self) -> bool {
+        self.is_unicast() && !self.is_zero()
+    }
+
+    pub fn as_raw(&self) -> &rte_ether_addr {
+        &self.0
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut rte_ether_addr {
+        &mut self.0
+    }
+}
+
+impl From<rte_ether_addr> for EtherAddr {
+    fn from(raw: rte_ether_addr) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<EtherAddr> for rte_ether_addr {
+    fn from(addr: EtherAddr) -> Self {
+        addr.0
+    }
+}
+
+impl From<[u8; RTE_ETHER_ADDR_LEN as usize]> for EtherAddr {
+    fn from(bytes: [u8; RTE_ETHER_ADDR_LEN as usize]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl PartialEq for EtherAddr {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes() == other.bytes()
+    }
+}
+
+impl Eq for EtherAddr {}
+
+impl Hash for EtherAddr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes().hash(state)
+    }
+}
+
+impl fmt::Display for EtherAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b = self.bytes();
+        write!(
+            f,
+            "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+            b[0], b[1], b[2], b[3], b[4], b[5]
+        )
+    }
+}
+
+impl fmt::Debug for EtherAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EtherAddr({})", self)
+    }
+}
+
+impl FromStr for EtherAddr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::new(EINVAL);
+        let hex = |part: &str, max_len: usize| -> Result<u16, Error> {
+            if part.is_empty()
+                || part.len() > max_len
+                || !part.bytes().all(|c| c.is_ascii_hexdigit())
+            {
+                return Err(invalid());
+            }
+            u16::from_str_radix(part, 16).map_err(|_| invalid())
+        };
+
+        let mut bytes = [0u8; RTE_ETHER_ADDR_LEN as usize];
+        let sep = if s.contains('-') { '-' } else { ':' };
+        let parts: Vec<&str> = s.split(sep).collect();
+        match parts.len() {
+            6 => {
+                for (b, part) in bytes.iter_mut().zip(parts) {
+                    *b = hex(part, 2)? as u8;
+                }
+            }
+            // The short form is only defined with ':' separators.
+            3 if sep == ':' => {
+                for (b, part) in bytes.chunks_exact_mut(2).zip(parts) {
+                    b.copy_from_slice(&hex(part, 4)?.to_be_bytes());
+                }
+            }
+            _ => return Err(invalid()),
+        }
+
+        Ok(Self::new(bytes))
+    }
+}
@@ -0,0 +1,167 @@
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+use std::os::raw::c_char;
+
+use super::Port;
+use crate::error::{check, Result};
+use crate::*;
+
+unsafe fn to_string(s: *const c_char) -> Option<String> {
+    if s.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(s).to_string_lossy().into_owned())
+    }
+}
+
+/// The generic device backing a port, as exposed by the `rte_dev_*` accessors.
+///
+/// The handle is only valid while the port is attached.
+#[derive(Debug, Clone, Copy)]
+pub struct Device {
+    raw: *const rte_device,
+}
+
+impl Device {
+    /// # Safety
+    ///
+    /// `raw` must point to a device that stays attached while the handle is
+    /// used.
+    pub unsafe fn from_raw(raw: *const rte_device) -> Option<Self> {
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self { raw })
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const rte_device {
+        self.raw
+    }
+
+    /// The device name, e.g. `0000:01:00.0` for a PCI device.
+    pub fn name(&self) -> String {
+        unsafe { to_string(rte_dev_name(self.raw)) }.unwrap_or_default()
+    }
+
+    pub fn bus_name(&self) -> Option<String> {
+        unsafe {
+            let bus = rte_dev_bus(self.raw);
+            if bus.is_null() {
+                None
+            } else {
+                to_string(rte_bus_name(bus))
+            }
+        }
+    }
+
+    /// Bus specific information, e.g. vendor and device ids on PCI.
+    pub fn bus_info(&self) -> Option<String> {
+        unsafe { to_string(rte_dev_bus_info(self.raw)) }
+    }
+
+    pub fn driver_name(&self) -> Option<String> {
+        unsafe {
+            let driver = rte_dev_driver(self.raw);
+            if driver.is_null() {
+                None
+            } else {
+                to_string(rte_driver_name(driver))
+            }
+        }
+    }
+
+    pub fn numa_node(&self) -> Option<u32> {
+        let node = unsafe { rte_dev_numa_node(self.raw) };
+        if node < 0 {
+            None
+        } else {
+            Some(node as u32)
+        }
+    }
+
+    /// The PCI address (domain:bus:device.function) if this is a PCI device.
+    pub fn pci_addr(&self) -> Option<String> {
+        match self.bus_name().as_deref() {
+            Some("pci") => Some(self.name()),
+            _ => None,
+        }
+    }
+}
+
+/// A summary of a port, built from `rte_eth_dev_info` and its device.
+#[derive(Debug, Clone)]
+pub struct PortInfo {
+    pub port: Port,
+    pub name: String,
+    pub driver_name: String,
+    pub bus_name: Option<String>,
+    pub pci_addr: Option<String>,
+    pub socket_id: Option<u32>,
+    pub if_index: u32,
+    pub min_mtu: u16,
+    pub max_mtu: u16,
+    pub max_rx_queues: u16,
+    pub max_tx_queues: u16,
+    pub max_mac_addrs: u32,
+    /// `RTE_ETH_LINK_SPEED_*` flags.
+    pub speed_capa: u32,
+    /// `RTE_ETH_RX_OFFLOAD_*` flags.
+    pub rx_offload_capa: u64,
+    /// `RTE_ETH_TX_OFFLOAD_*` flags.
+    pub tx_offload_capa: u64,
+    /// `RTE_ETH_DEV_CAPA_*` flags.
+    pub dev_capa: u64,
+}
+
+impl Port {
+    pub fn dev_info(&self) -> Result<rte_eth_dev_info> {
+        let mut info = MaybeUninit::<rte_eth_dev_info>::zeroed();
+        check(unsafe { rte_eth_dev_info_get(self.id(), info.as_mut_ptr()) })?;
+        Ok(unsafe { info.assume_init() })
+    }
+
+    /// The device backing the port.
+    ///
+    /// # Safety
+    ///
+    /// The device must stay attached while the handle is used: it is not
+    /// released on detach or hot-unplug, its pointer dangles then.
+    pub unsafe fn device(&self) -> Result<Option<Device>> {
+        let info = self.dev_info()?;
+        Ok(Device::from_raw(info.device))
+    }
+
+    /// A summary of the port, read together with its device.
+    ///
+    /// Unlike [`device`](Self::device), this is safe: the device pointer is
+    /// only used during the call, right after `rte_eth_dev_info_get` returned
+    /// it for an attached port. Detaching the port concurrently would already
+    /// be a data race in DPDK, whose control path functions of a port are not
+    /// thread safe.
+    pub fn info(&self) -> Result<PortInfo> {
+        let info = self.dev_info()?;
+        // Valid until the end of the call, see above; the handle is not
+        // returned.
+        let device = unsafe { Device::from_raw(info.device) };
+
+        Ok(PortInfo {
+            port: *self,
+            name: self.name()?,
+            driver_name: unsafe { to_string(info.driver_name) }.unwrap_or_default(),
+            bus_name: device.and_then(|d| d.bus_name()),
+            pci_addr: device.and_then(|d| d.pci_addr()),
+            socket_id: self.socket_id(),
+            if_index: info.if_index,
+            min_mtu: info.min_mtu,
+            max_mtu: info.max_mtu,
+            max_rx_queues: info.max_rx_queues,
+            max_tx_queues: info.max_tx_queues,
+            max_mac_addrs: info.max_mac_addrs,
+            speed_capa: info.speed_capa,
+            rx_offload_capa: info.rx_offload_capa,
+            tx_offload_capa: info.tx_offload_capa,
+            dev_capa: info.dev_capa,
+        })
+    }
+}
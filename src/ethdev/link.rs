@@ -0,0 +1,344 @@
+use std::cell::RefCell;
+use std::ffi::{c_void, CStr};
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use super::Port;
+use crate::error::{check, Error, Result, EAGAIN, EINVAL, ETIMEDOUT};
+use crate::*;
+
+/// Link speed in Mbps, as reported in `rte_eth_link.link_speed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LinkSpeed(u32);
+
+impl LinkSpeed {
+    pub const NONE: Self = Self(RTE_ETH_SPEED_NUM_NONE);
+    pub const SPEED_10M: Self = Self(RTE_ETH_SPEED_NUM_10M);
+    pub const SPEED_100M: Self = Self(RTE_ETH_SPEED_NUM_100M);
+    pub const SPEED_1G: Self = Self(RTE_ETH_SPEED_NUM_1G);
+    pub const SPEED_2_5G: Self = Self(RTE_ETH_SPEED_NUM_2_5G);
+    pub const SPEED_5G: Self = Self(RTE_ETH_SPEED_NUM_5G);
+    pub const SPEED_10G: Self = Self(RTE_ETH_SPEED_NUM_10G);
+    pub const SPEED_20G: Self = Self(RTE_ETH_SPEED_NUM_20G);
+    pub const SPEED_25G: Self = Self(RTE_ETH_SPEED_NUM_25G);
+    pub const SPEED_40G: Self = Self(RTE_ETH_SPEED_NUM_40G);
+    pub const SPEED_50G: Self = Self(RTE_ETH_SPEED_NUM_50G);
+    pub const SPEED_56G: Self = Self(RTE_ETH_SPEED_NUM_56G);
+    pub const SPEED_100G: Self = Self(RTE_ETH_SPEED_NUM_100G);
+    pub const SPEED_200G: Self = Self(RTE_ETH_SPEED_NUM_200G);
+    pub const SPEED_400G: Self = Self(RTE_ETH_SPEED_NUM_400G);
+    pub const UNKNOWN: Self = Self(RTE_ETH_SPEED_NUM_UNKNOWN);
+
+    pub const fn new(mbps: u32) -> Self {
+        Self(mbps)
+    }
+
+    /// The speed in Mbps, or `None` if the driver does not know it.
+    pub const fn mbps(&self) -> Option<u32> {
+        match *self {
+            Self::UNKNOWN => None,
+            Self(mbps) => Some(mbps),
+        }
+    }
+}
+
+impl fmt::Display for LinkSpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = unsafe { CStr::from_ptr(rte_eth_link_speed_to_str(self.0)) };
+        f.write_str(&s.to_string_lossy())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Duplex {
+    Half,
+    Full,
+}
+
+/// The link state of a port, decoded from `rte_eth_link`.
+#[derive(Clone, Copy)]
+pub struct LinkStatus {
+    raw: rte_eth_link,
+}
+
+impl LinkStatus {
+    pub fn speed(&self) -> LinkSpeed {
+        LinkSpeed(self.raw.link_speed)
+    }
+
+    pub fn duplex(&self) -> Duplex {
+        if self.raw.link_duplex() as u32 == RTE_ETH_LINK_FULL_DUPLEX {
+            Duplex::Full
+        } else {
+            Duplex::Half
+        }
+    }
+
+    pub fn autoneg(&self) -> bool {
+        self.raw.link_autoneg() as u32 == RTE_ETH_LINK_AUTONEG
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.raw.link_status() as u32 == RTE_ETH_LINK_UP
+    }
+
+    pub fn as_raw(&self) -> &rte_eth_link {
+        &self.raw
+    }
+}
+
+impl From<rte_eth_link> for LinkStatus {
+    fn from(raw: rte_eth_link) -> Self {
+        Self { raw }
+    }
+}
+
+impl fmt::Debug for LinkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkStatus")
+            .field("speed", &self.speed())
+            .field("duplex", &self.duplex())
+            .field("autoneg", &self.autoneg())
+            .field("up", &self.is_up())
+            .finish()
+    }
+}
+
+impl fmt::Display for LinkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = [0 as std::os::raw::c_char; RTE_ETH_LINK_MAX_STR_LEN as usize];
+        let ret = unsafe { rte_eth_link_to_str(buf.as_mut_ptr(), buf.len(), &self.raw) };
+        if ret < 0 {
+            return Err(fmt::Error);
+        }
+        let s = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        f.write_str(&s.to_string_lossy())
+    }
+}
+
+// Interval between two link polls in `Port::wait_link_up`, the same as the
+// one used by the DPDK examples.
+const LINK_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+impl Port {
+    /// Get the link status, waiting for the driver to complete the query.
+    pub fn link(&self) -> Result<LinkStatus> {
+        let mut link = MaybeUninit::<rte_eth_link>::zeroed();
+        check(unsafe { rte_eth_link_get(self.id(), link.as_mut_ptr()) })?;
+        Ok(LinkStatus::from(unsafe { link.assume_init() }))
+    }
+
+    /// Get the link status without waiting.
+    pub fn link_nowait(&self) -> Result<LinkStatus> {
+        let mut link = MaybeUninit::<rte_eth_link>::zeroed();
+        check(unsafe { rte_eth_link_get_nowait(self.id(), link.as_mut_ptr()) })?;
+        Ok(LinkStatus::from(unsafe { link.assume_init() }))
+    }
+
+    /// Poll the link until it is up, failing with `ETIMEDOUT` once `timeout`
+    /// has passed.
+    pub fn wait_link_up(&self, timeout: Duration) -> Result<LinkStatus> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let link = self.link_nowait()?;
+            if link.is_up() {
+                return Ok(link);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::new(ETIMEDOUT));
+            }
+            std::thread::sleep(LINK_CHECK_INTERVAL.min(deadline - now));
+        }
+    }
+
+    /// Register `f` to be called when `event` occurs on this port.
+    ///
+    /// The callback stays registered until the returned handle is dropped.
+    pub fn on_event<F>(&self, event: EthEvent, f: F) -> Result<EventCallback>
+    where
+        F: Fn(Port, EthEvent) + Send + Sync + 'static,
+    {
+        EventCallback::register(self.id(), event, Box::new(f))
+    }
+}
+
+/// Register `f` to be called when `event` occurs on any port, including
+/// ports probed after the registration.
+pub fn on_event_all<F>(event: EthEvent, f: F) -> Result<EventCallback>
+where
+    F: Fn(Port, EthEvent) + Send + Sync + 'static,
+{
+    EventCallback::register(RTE_ETH_ALL as u16, event, Box::new(f))
+}
+
+/// Ethdev events that a callback can be registered for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EthEvent {
+    /// Link status change.
+    Lsc,
+    QueueState,
+    Reset,
+    VfMbox,
+    Macsec,
+    /// Device removal.
+    Rmv,
+    New,
+    Destroy,
+    Ipsec,
+    FlowAged,
+    RxAvailThresh,
+    ErrRecovering,
+    RecoverySuccess,
+    RecoveryFailed,
+}
+
+impl EthEvent {
+    pub fn as_raw(&self) -> rte_eth_event_type {
+        match self {
+            Self::Lsc => rte_eth_event_type_RTE_ETH_EVENT_INTR_LSC,
+            Self::QueueState => rte_eth_event_type_RTE_ETH_EVENT_QUEUE_STATE,
+            Self::Reset => rte_eth_event_type_RTE_ETH_EVENT_INTR_RESET,
+            Self::VfMbox => rte_eth_event_type_RTE_ETH_EVENT_VF_MBOX,
+            Self::Macsec => rte_eth_event_type_RTE_ETH_EVENT_MACSEC,
+            Self::Rmv => rte_eth_event_type_RTE_ETH_EVENT_INTR_RMV,
+            Self::New => rte_eth_event_type_RTE_ETH_EVENT_NEW,
+            Self::Destroy => rte_eth_event_type_RTE_ETH_EVENT_DESTROY,
+            Self::Ipsec => rte_eth_event_type_RTE_ETH_EVENT_IPSEC,
+            Self::FlowAged => rte_eth_event_type_RTE_ETH_EVENT_FLOW_AGED,
+            Self::RxAvailThresh => rte_eth_event_type_RTE_ETH_EVENT_RX_AVAIL_THRESH,
+            Self::ErrRecovering => rte_eth_event_type_RTE_ETH_EVENT_ERR_RECOVERING,
+            Self::RecoverySuccess => rte_eth_event_type_RTE_ETH_EVENT_RECOVERY_SUCCESS,
+            Self::RecoveryFailed => rte_eth_event_type_RTE_ETH_EVENT_RECOVERY_FAILED,
+        }
+    }
+}
+
+impl TryFrom<rte_eth_event_type> for EthEvent {
+    type Error = Error;
+
+    fn try_from(raw: rte_eth_event_type) -> Result<Self> {
+        let event = match raw {
+            rte_eth_event_type_RTE_ETH_EVENT_INTR_LSC => Self::Lsc,
+            rte_eth_event_type_RTE_ETH_EVENT_QUEUE_STATE => Self::QueueState,
+            rte_eth_event_type_RTE_ETH_EVENT_INTR_RESET => Self::Reset,
+            rte_eth_event_type_RTE_ETH_EVENT_VF_MBOX => Self::VfMbox,
+            rte_eth_event_type_RTE_ETH_EVENT_MACSEC => Self::Macsec,
+            rte_eth_event_type_RTE_ETH_EVENT_INTR_RMV => Self::Rmv,
+            rte_eth_event_type_RTE_ETH_EVENT_NEW => Self::New,
+            rte_eth_event_type_RTE_ETH_EVENT_DESTROY => Self::Destroy,
+            rte_eth_event_type_RTE_ETH_EVENT_IPSEC => Self::Ipsec,
+            rte_eth_event_type_RTE_ETH_EVENT_FLOW_AGED => Self::FlowAged,
+            rte_eth_event_type_RTE_ETH_EVENT_RX_AVAIL_THRESH => Self::RxAvailThresh,
+            rte_eth_event_type_RTE_ETH_EVENT_ERR_RECOVERING => Self::ErrRecovering,
+            rte_eth_event_type_RTE_ETH_EVENT_RECOVERY_SUCCESS => Self::RecoverySuccess,
+            rte_eth_event_type_RTE_ETH_EVENT_RECOVERY_FAILED => Self::RecoveryFailed,
+            _ => return Err(Error::new(EINVAL)),
+        };
+        Ok(event)
+    }
+}
+
+type EventFn = Box<dyn Fn(Port, EthEvent) + Send + Sync>;
+
+/// A registered ethdev event callback, unregistered on drop.
+///
+/// DPDK cannot unregister a callback while it runs, so dropping the handle
+/// from its own callback hands the unregistration over to another thread,
+/// which waits for the callback to return.
+pub struct EventCallback {
+    port_id: u16,
+    event: EthEvent,
+    arg: *mut EventFn,
+}
+
+// The closure is `Send + Sync`, so the handle can be dropped on any thread.
+unsafe impl Send for EventCallback {}
+unsafe impl Sync for EventCallback {}
+
+thread_local! {
+    /// The closures of the callbacks running on this thread.
+    static RUNNING: RefCell<Vec<*mut c_void>> = const { RefCell::new(Vec::new()) };
+}
+
+impl EventCallback {
+    fn register(port_id: u16, event: EthEvent, f: EventFn) -> Result<Self> {
+        let arg = Box::into_raw(Box::new(f));
+        let ret = unsafe {
+            rte_eth_dev_callback_register(
+                port_id,
+                event.as_raw(),
+                Some(event_trampoline),
+                arg as *mut c_void,
+            )
+        };
+        if let Err(e) = check(ret) {
+            drop(unsafe { Box::from_raw(arg) });
+            return Err(e);
+        }
+
+        Ok(Self {
+            port_id,
+            event,
+            arg,
+        })
+    }
+
+    pub fn event(&self) -> EthEvent {
+        self.event
+    }
+}
+
+impl Drop for EventCallback {
+    fn drop(&mut self) {
+        let arg = self.arg as *mut c_void;
+        if RUNNING.with(|running| running.borrow().contains(&arg)) {
+            let deferred = EventCallback {
+                port_id: self.port_id,
+                event: self.event,
+                arg: self.arg,
+            };
+            std::thread::spawn(move || drop(deferred));
+            return;
+        }
+        let ret = loop {
+            let ret = unsafe {
+                rte_eth_dev_callback_unregister(
+                    self.port_id,
+                    self.event.as_raw(),
+                    Some(event_trampoline),
+                    arg,
+                )
+            };
+            // The callback is currently running on another thread.
+            if ret == -EAGAIN {
+                std::thread::yield_now();
+                continue;
+            }
+            break ret;
+        };
+        // If unregistering failed, the callback may still be called, so its
+        // closure is leaked rather than freed under it.
+        if ret == 0 {
+            drop(unsafe { Box::from_raw(self.arg) });
+        }
+    }
+}
+
+unsafe extern "C" fn event_trampoline(
+    port_id: u16,
+    event: rte_eth_event_type,
+    cb_arg: *mut c_void,
+    _ret_param: *mut c_void,
+) -> std::os::raw::c_int {
+    let f = &*(cb_arg as *const EventFn);
+    if let Ok(event) = EthEvent::try_from(event) {
+        RUNNING.with(|running| running.borrow_mut().push(cb_arg));
+        // Unwinding into the C interrupt thread is undefined behavior.
+        let _ = catch_unwind(AssertUnwindSafe(|| f(Port::new(port_id), event)));
+        RUNNING.with(|running| running.borrow_mut().pop());
+    }
+    0
+}
@@ -0,0 +1,138 @@
+use std::ffi::CString;
+use std::sync::Arc;
+
+use super::Port;
+use crate::error::{check_errno, Error, Result, EINVAL, SOCKET_ID_ANY};
+use crate::mbuf::{Mbuf, Mempool};
+use crate::ring::{Ring, RingBuilder};
+use crate::{rte_eth_from_ring, rte_eth_from_rings, rte_ring};
+
+impl Port {
+    /// Create a `net_ring` port named `name`, receiving from the `rx` rings
+    /// and sending to the `tx` rings, one ring per queue.
+    ///
+    /// # Safety
+    ///
+    /// The rings must outlive the port, until it is closed.
+    pub unsafe fn from_rings(
+        name: &str,
+        rx: &[&Ring<Mbuf>],
+        tx: &[&Ring<Mbuf>],
+        socket_id: Option<u32>,
+    ) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let rx: Vec<*mut rte_ring> = rx.iter().map(|ring| ring.as_ptr()).collect();
+        let tx: Vec<*mut rte_ring> = tx.iter().map(|ring| ring.as_ptr()).collect();
+        let id = check_errno(rte_eth_from_rings(
+            name.as_ptr(),
+            rx.as_ptr(),
+            rx.len() as u32,
+            tx.as_ptr(),
+            tx.len() as u32,
+            socket_id.map_or(SOCKET_ID_ANY, |id| id as i32) as u32,
+        ))?;
+        Ok(Self::new(id as u16))
+    }
+
+    /// Create a `net_ring` port with a single queue sending to the ring it
+    /// receives from.
+    ///
+    /// # Safety
+    ///
+    /// The ring must outlive the port, until it is closed.
+    pub unsafe fn from_ring(ring: &Ring<Mbuf>) -> Result<Self> {
+        let id = check_errno(rte_eth_from_ring(ring.as_ptr()))?;
+        Ok(Self::new(id as u16))
+    }
+}
+
+/// One end of a pair of `net_ring` ports connected back to back: what one
+/// sends on a queue, the other receives on the same queue.
+///
+/// The ports are configured and started when created, and stopped and
+/// closed on drop. They need no NIC nor hugepages, which makes them handy to
+/// test burst code.
+pub struct LoopbackPort {
+    port: Port,
+    _rings: Arc<Vec<Ring<Mbuf>>>,
+}
+
+impl LoopbackPort {
+    /// Create the ports `<name>_0` and `<name>_1` with `nb_queues` queues
+    /// each, backed by rings of `ring_size` mbufs.
+    ///
+    /// `pool` is only checked by the receive queue setup, the ports receive
+    /// the mbufs sent by their peer.
+    pub fn pair(
+        name: &str,
+        nb_queues: u16,
+        ring_size: u32,
+        pool: &Mempool,
+    ) -> Result<(Self, Self)> {
+        let mut rings = Vec::with_capacity(2 * nb_queues as usize);
+        for end in 0..2 {
+            for queue in 0..nb_queues {
+                let ring = RingBuilder::new(format!("{}_{}q{}", name, end, queue), ring_size)
+                    .exact_size()
+                    .create::<Mbuf>()?;
+                rings.push(ring);
+            }
+        }
+        let (a, b) = rings.split_at(nb_queues as usize);
+        let a: Vec<&Ring<Mbuf>> = a.iter().collect();
+        let b: Vec<&Ring<Mbuf>> = b.iter().collect();
+
+        let port_a = unsafe { Port::from_rings(&format!("{}_0", name), &a, &b, None)? };
+        let port_b = match unsafe { Port::from_rings(&format!("{}_1", name), &b, &a, None) } {
+            Ok(port) => port,
+            Err(err) => {
+                let _ = port_a.close();
+                return Err(err);
+            }
+        };
+
+        let rings = Arc::new(rings);
+        let a = Self {
+            port: port_a,
+            _rings: rings.clone(),
+        };
+        let b = Self {
+            port: port_b,
+            _rings: rings,
+        };
+        a.setup(nb_queues, pool)?;
+        b.setup(nb_queues, pool)?;
+        Ok((a, b))
+    }
+
+    fn setup(&self, nb_queues: u16, pool: &Mempool) -> Result<()> {
+        self.port.configure(nb_queues, nb_queues)?;
+        for queue in 0..nb_queues {
+            self.port.rx_queue_setup(queue, 0, pool)?;
+            self.port.tx_queue_setup(queue, 0)?;
+        }
+        self.port.start()
+    }
+
+    pub fn port(&self) -> Port {
+        self.port
+    }
+
+    /// Receive up to `max` packets sent by the peer on `queue_id`, as
+    /// [`Port::rx_burst`].
+    pub fn rx_burst(&self, queue_id: u16, out: &mut Vec<Mbuf>, max: usize) -> usize {
+        self.port.rx_burst(queue_id, out, max)
+    }
+
+    /// Send packets to the peer on `queue_id`, as [`Port::tx_burst`].
+    pub fn tx_burst(&self, queue_id: u16, pkts: &mut Vec<Mbuf>) -> usize {
+        self.port.tx_burst(queue_id, pkts)
+    }
+}
+
+impl Drop for LoopbackPort {
+    fn drop(&mut self) {
+        let _ = self.port.stop();
+        let _ = self.port.close();
+    }
+}
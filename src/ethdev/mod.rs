@@ -0,0 +1,41 @@
+mod config;
+mod discover;
+mod ether;
+mod info;
+mod link;
+mod loopback;
+mod queue;
+mod stats;
+
+pub use config::*;
+pub use discover::*;
+pub use ether::*;
+pub use info::*;
+pub use link::*;
+pub use loopback::*;
+pub use stats::*;
+
+/// An ethdev port, identified by its port id.
+///
+/// This is a plain handle: it does not own the underlying device, and every
+/// method forwards to the `rte_eth_*` function taking the same port id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Port {
+    id: u16,
+}
+
+impl Port {
+    pub const fn new(id: u16) -> Self {
+        Self { id }
+    }
+
+    pub const fn id(&self) -> u16 {
+        self.id
+    }
+}
+
+impl From<u16> for Port {
+    fn from(id: u16) -> Self {
+        Self::new(id)
+    }
+}
@@ -0,0 +1,120 @@
+use std::mem;
+use std::ptr;
+
+use super::Port;
+use crate::error::{check, Result, SOCKET_ID_ANY};
+use crate::mbuf::{Mbuf, Mempool};
+use crate::{
+    rte_eth_conf, rte_eth_dev_close, rte_eth_dev_configure, rte_eth_dev_start, rte_eth_dev_stop,
+    rte_eth_rx_burst_, rte_eth_rx_queue_setup, rte_eth_tx_burst_, rte_eth_tx_queue_setup, rte_mbuf,
+};
+
+impl Port {
+    /// Configure the port with `nb_rx_queues` and `nb_tx_queues` queues and
+    /// the default settings of the driver.
+    pub fn configure(&self, nb_rx_queues: u16, nb_tx_queues: u16) -> Result<()> {
+        let conf: rte_eth_conf = unsafe { mem::zeroed() };
+        self.configure_with(nb_rx_queues, nb_tx_queues, &conf)
+    }
+
+    pub fn configure_with(
+        &self,
+        nb_rx_queues: u16,
+        nb_tx_queues: u16,
+        conf: &rte_eth_conf,
+    ) -> Result<()> {
+        check(unsafe { rte_eth_dev_configure(self.id(), nb_rx_queues, nb_tx_queues, conf) })
+            .map(|_| ())
+    }
+
+    fn queue_socket_id(&self) -> u32 {
+        self.socket_id().map_or(SOCKET_ID_ANY as u32, |id| id)
+    }
+
+    /// Set up receive queue `queue_id` with `nb_desc` descriptors, 0 for the
+    /// driver default, filled with mbufs from `pool`.
+    ///
+    /// The pool must outlive the port, or at least its next
+    /// [`close`](Self::close).
+    pub fn rx_queue_setup(&self, queue_id: u16, nb_desc: u16, pool: &Mempool) -> Result<()> {
+        check(unsafe {
+            rte_eth_rx_queue_setup(
+                self.id(),
+                queue_id,
+                nb_desc,
+                self.queue_socket_id(),
+                ptr::null(),
+                pool.as_ptr(),
+            )
+        })
+        .map(|_| ())
+    }
+
+    /// Set up transmit queue `queue_id` with `nb_desc` descriptors, 0 for
+    /// the driver default.
+    pub fn tx_queue_setup(&self, queue_id: u16, nb_desc: u16) -> Result<()> {
+        check(unsafe {
+            rte_eth_tx_queue_setup(
+                self.id(),
+                queue_id,
+                nb_desc,
+                self.queue_socket_id(),
+                ptr::null(),
+            )
+        })
+        .map(|_| ())
+    }
+
+    pub fn start(&self) -> Result<()> {
+        check(unsafe { rte_eth_dev_start(self.id()) }).map(|_| ())
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        check(unsafe { rte_eth_dev_stop(self.id()) }).map(|_| ())
+    }
+
+    /// Release the queues and resources of a stopped port. The port id may
+    /// be reused afterwards.
+    pub fn close(&self) -> Result<()> {
+        check(unsafe { rte_eth_dev_close(self.id()) }).map(|_| ())
+    }
+
+    /// Receive up to `max` packets from `queue_id`, appended to `out`, and
+    /// return how many were received.
+    pub fn rx_burst(&self, queue_id: u16, out: &mut Vec<Mbuf>, max: usize) -> usize {
+        let max = max.min(u16::MAX as usize);
+        out.reserve(max);
+        let len = out.len();
+        let n = unsafe {
+            rte_eth_rx_burst_(
+                self.id(),
+                queue_id,
+                out.as_mut_ptr().add(len) as *mut *mut rte_mbuf,
+                max as u16,
+            )
+        } as usize;
+        unsafe { out.set_len(len + n) };
+        n
+    }
+
+    /// Send as many packets as the queue takes from the front of `pkts`,
+    /// which are removed from it, and return how many were sent.
+    pub fn tx_burst(&self, queue_id: u16, pkts: &mut Vec<Mbuf>) -> usize {
+        let len = pkts.len().min(u16::MAX as usize);
+        let n = unsafe {
+            rte_eth_tx_burst_(
+                self.id(),
+                queue_id,
+                pkts.as_mut_ptr() as *mut *mut rte_mbuf,
+                len as u16,
+            )
+        } as usize;
+        // The driver owns the first `n` packets now.
+        unsafe {
+            let p = pkts.as_mut_ptr();
+            ptr::copy(p.add(n), p, pkts.len() - n);
+            pkts.set_len(pkts.len() - n);
+        }
+        n
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::Port;
+use crate::error::{check, Error, Result, EINVAL};
+use crate::{
+    rte_eth_dev_set_rx_queue_stats_mapping, rte_eth_dev_set_tx_queue_stats_mapping, rte_eth_stats,
+    rte_eth_stats_get, rte_eth_stats_reset, rte_eth_xstat, rte_eth_xstat_name, rte_eth_xstats_get,
+    rte_eth_xstats_get_by_id, rte_eth_xstats_get_id_by_name, rte_eth_xstats_get_names,
+    rte_eth_xstats_reset, RTE_ETHDEV_QUEUE_STAT_CNTRS,
+};
+
+impl Port {
+    /// Basic statistics of the port.
+    ///
+    /// The per-queue arrays hold `RTE_ETHDEV_QUEUE_STAT_CNTRS` counters, which
+    /// queues map to which counter is set with the queue stats mapping.
+    pub fn stats(&self) -> Result<rte_eth_stats> {
+        let mut stats = MaybeUninit::<rte_eth_stats>::zeroed();
+        check(unsafe { rte_eth_stats_get(self.id(), stats.as_mut_ptr()) })?;
+        Ok(unsafe { stats.assume_init() })
+    }
+
+    pub fn reset_stats(&self) -> Result<()> {
+        check(unsafe { rte_eth_stats_reset(self.id()) }).map(|_| ())
+    }
+
+    pub fn reset_xstats(&self) -> Result<()> {
+        check(unsafe { rte_eth_xstats_reset(self.id()) }).map(|_| ())
+    }
+
+    /// Names of all extended statistics, indexed by xstat id.
+    pub fn xstat_names(&self) -> Result<Vec<String>> {
+        loop {
+            let n = check(unsafe { rte_eth_xstats_get_names(self.id(), std::ptr::null_mut(), 0) })?
+                as usize;
+            let mut names: Vec<rte_eth_xstat_name> = vec![unsafe { std::mem::zeroed() }; n];
+            let ret = check(unsafe {
+                rte_eth_xstats_get_names(self.id(), names.as_mut_ptr(), n as u32)
+            })? as usize;
+            // The number of xstats may change between the two calls, e.g. when
+            // queues are reconfigured.
+            if ret > n {
+                continue;
+            }
+            names.truncate(ret);
+            return Ok(names
+                .iter()
+                .map(|n| {
+                    unsafe { CStr::from_ptr(n.name.as_ptr()) }
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect());
+        }
+    }
+
+    /// Values of all extended statistics, indexed by xstat id.
+    pub fn xstats(&self) -> Result<Vec<u64>> {
+        loop {
+            let n =
+                check(unsafe { rte_eth_xstats_get(self.id(), std::ptr::null_mut(), 0) })? as usize;
+            let mut xstats: Vec<rte_eth_xstat> = vec![unsafe { std::mem::zeroed() }; n];
+            let ret =
+                check(unsafe { rte_eth_xstats_get(self.id(), xstats.as_mut_ptr(), n as u32) })?
+                    as usize;
+            if ret > n {
+                continue;
+            }
+            let mut values = vec![0; ret];
+            for xstat in xstats[..ret].iter() {
+                if let Some(v) = values.get_mut(xstat.id as usize) {
+                    *v = xstat.value;
+                }
+            }
+            return Ok(values);
+        }
+    }
+
+    /// Look up the id of the extended statistic called `name`.
+    pub fn xstat_id(&self, name: &str) -> Result<u64> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let mut id = 0;
+        check(unsafe { rte_eth_xstats_get_id_by_name(self.id(), name.as_ptr(), &mut id) })?;
+        Ok(id)
+    }
+
+    /// Read the extended statistics selected by `ids` into `values`.
+    pub fn xstats_by_id(&self, ids: &[u64], values: &mut [u64]) -> Result<()> {
+        if ids.len() != values.len() {
+            return Err(Error::new(EINVAL));
+        }
+        check(unsafe {
+            rte_eth_xstats_get_by_id(
+                self.id(),
+                ids.as_ptr(),
+                values.as_mut_ptr(),
+                ids.len() as u32,
+            )
+        })
+        .map(|_| ())
+    }
+
+    /// Map Rx queue `queue_id` to the per-queue counter `stat_idx`.
+    pub fn set_rx_queue_stats_mapping(&self, queue_id: u16, stat_idx: u8) -> Result<()> {
+        if stat_idx as u32 >= RTE_ETHDEV_QUEUE_STAT_CNTRS {
+            return Err(Error::new(EINVAL));
+        }
+        check(unsafe { rte_eth_dev_set_rx_queue_stats_mapping(self.id(), queue_id, stat_idx) })
+            .map(|_| ())
+    }
+
+    /// Map Tx queue `queue_id` to the per-queue counter `stat_idx`.
+    pub fn set_tx_queue_stats_mapping(&self, queue_id: u16, stat_idx: u8) -> Result<()> {
+        if stat_idx as u32 >= RTE_ETHDEV_QUEUE_STAT_CNTRS {
+            return Err(Error::new(EINVAL));
+        }
+        check(unsafe { rte_eth_dev_set_tx_queue_stats_mapping(self.id(), queue_id, stat_idx) })
+            .map(|_| ())
+    }
+
+    pub fn snapshot(&self) -> Result<PortStats> {
+        PortStats::collect(*self)
+    }
+}
+
+/// Counters of one queue, taken from the per-queue arrays of `rte_eth_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    pub ipackets: u64,
+    pub opackets: u64,
+    pub ibytes: u64,
+    pub obytes: u64,
+    pub errors: u64,
+}
+
+/// A snapshot of the basic and extended statistics of a port.
+///
+/// Xstat names are fetched once and shared by every snapshot produced through
+/// [`PortStats::refresh`], so taking periodic snapshots only reads values.
+#[derive(Debug, Clone)]
+pub struct PortStats {
+    port: Port,
+    names: Arc<[String]>,
+    values: Vec<u64>,
+    basic: rte_eth_stats,
+    taken: Instant,
+}
+
+impl PortStats {
+    pub fn collect(port: Port) -> Result<Self> {
+        let names: Arc<[String]> = port.xstat_names()?.into();
+        Self::collect_with(port, names)
+    }
+
+    /// Take a new snapshot of the same port, reusing the cached xstat names.
+    pub fn refresh(&self) -> Result<Self> {
+        Self::collect_with(self.port, self.names.clone())
+    }
+
+    fn collect_with(port: Port, names: Arc<[String]>) -> Result<Self> {
+        let basic = port.stats()?;
+        let values = port.xstats()?;
+        let taken = Instant::now();
+        // The set of xstats changed, so the cached names are stale.
+        let names = if values.len() != names.len() {
+            port.xstat_names()?.into()
+        } else {
+            names
+        };
+        if values.len() != names.len() {
+            return Err(Error::new(EINVAL));
+        }
+
+        Ok(Self {
+            port,
+            names,
+            values,
+            basic,
+            taken,
+        })
+    }
+
+    pub fn port(&self) -> Port {
+        self.port
+    }
+
+    pub fn taken(&self) -> Instant {
+        self.taken
+    }
+
+    pub fn basic(&self) -> &rte_eth_stats {
+        &self.basic
+    }
+
+    /// Counters of the per-queue stats slot `stat_idx`.
+    pub fn queue(&self, stat_idx: usize) -> Option<QueueStats> {
+        if stat_idx >= RTE_ETHDEV_QUEUE_STAT_CNTRS as usize {
+            return None;
+        }
+        Some(QueueStats {
+            ipackets: self.basic.q_ipackets[stat_idx],
+            opackets: self.basic.q_opackets[stat_idx],
+            ibytes: self.basic.q_ibytes[stat_idx],
+            obytes: self.basic.q_obytes[stat_idx],
+            errors: self.basic.q_errors[stat_idx],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        let idx = self.names.iter().position(|n| n == name)?;
+        Some(self.values[idx])
+    }
+
+    /// Iterate over `(name, value)` pairs of the extended statistics.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> + '_ {
+        self.names
+            .iter()
+            .map(|n| n.as_str())
+            .zip(self.values.iter().copied())
+    }
+
+    /// Difference between this snapshot and an `earlier` one.
+    ///
+    /// Counters that went backwards, e.g. because the stats were reset in
+    /// between, yield zero. Xstats missing from `earlier` are reported as is.
+    pub fn delta(&self, earlier: &PortStats) -> StatsDelta {
+        let values = if Arc::ptr_eq(&self.names, &earlier.names) || self.names == earlier.names {
+            self.values
+                .iter()
+                .zip(earlier.values.iter())
+                .map(|(now, then)| now.saturating_sub(*then))
+                .collect()
+        } else {
+            let then: HashMap<&str, u64> = earlier.iter().collect();
+            self.iter()
+                .map(|(name, now)| now.saturating_sub(then.get(name).copied().unwrap_or(0)))
+                .collect()
+        };
+
+        StatsDelta {
+            names: self.names.clone(),
+            values,
+            elapsed: self.taken.saturating_duration_since(earlier.taken),
+        }
+    }
+}
+
+/// The change of extended statistics between two [`PortStats`] snapshots.
+#[derive(Debug, Clone)]
+pub struct StatsDelta {
+    names: Arc<[String]>,
+    values: Vec<u64>,
+    elapsed: Duration,
+}
+
+impl StatsDelta {
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        let idx = self.names.iter().position(|n| n == name)?;
+        Some(self.values[idx])
+    }
+
+    /// Per-second rate of the xstat called `name`.
+    pub fn rate(&self, name: &str) -> Option<f64> {
+        self.get(name).map(|v| self.per_second(v))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> + '_ {
+        self.names
+            .iter()
+            .map(|n| n.as_str())
+            .zip(self.values.iter().copied())
+    }
+
+    /// Iterate over `(name, per-second rate)` pairs.
+    pub fn rates(&self) -> impl Iterator<Item = (&str, f64)> + '_ {
+        self.iter().map(|(n, v)| (n, self.per_second(v)))
+    }
+
+    fn per_second(&self, value: u64) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            0.0
+        } else {
+            value as f64 / secs
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+use std::time::Duration;
+
+use crate::error::{check, Error, Result, SOCKET_ID_ANY};
+use crate::mbuf::{Mbuf, Mempool};
+use crate::net::IPPROTO_FRAGMENT;
+use crate::*;
+
+/// Split the IPv4 packet `pkt` in fragments of at most `mtu` bytes.
+///
+/// `pkt` must start at the IPv4 header, without L2 header. The fragments
+/// get their headers from `direct` and refer to the payload of `pkt` through
+/// indirect mbufs from `indirect`, so the payload is not copied.
+pub fn fragment_ipv4(
+    pkt: Mbuf,
+    mtu: u16,
+    direct: &Mempool,
+    indirect: &Mempool,
+) -> Result<Vec<Mbuf>> {
+    fragment(pkt, mtu, direct, indirect, rte_ipv4_fragment_packet)
+}
+
+/// Split the IPv6 packet `pkt` in fragments of at most `mtu` bytes, as
+/// [`fragment_ipv4`].
+pub fn fragment_ipv6(
+    pkt: Mbuf,
+    mtu: u16,
+    direct: &Mempool,
+    indirect: &Mempool,
+) -> Result<Vec<Mbuf>> {
+    fragment(pkt, mtu, direct, indirect, rte_ipv6_fragment_packet)
+}
+
+type FragmentFn = unsafe extern "C" fn(
+    *mut rte_mbuf,
+    *mut *mut rte_mbuf,
+    u16,
+    u16,
+    *mut rte_mempool,
+    *mut rte_mempool,
+) -> i32;
+
+fn fragment(
+    mut pkt: Mbuf,
+    mtu: u16,
+    direct: &Mempool,
+    indirect: &Mempool,
+    f: FragmentFn,
+) -> Result<Vec<Mbuf>> {
+    // Every fragment but the last carries a multiple of 8 payload bytes.
+    let max = (pkt.pkt_len() as usize / 8 + 1).min(u16::MAX as usize);
+    let mut out: Vec<Mbuf> = Vec::with_capacity(max);
+    let n = check(unsafe {
+        f(
+            pkt.as_mut_ptr(),
+            out.as_mut_ptr() as *mut *mut rte_mbuf,
+            max as u16,
+            mtu,
+            direct.as_ptr(),
+            indirect.as_ptr(),
+        )
+    })?;
+    unsafe { out.set_len(n as usize) };
+    // The fragments hold their own references to the payload of `pkt`,
+    // which is released on drop.
+    Ok(out)
+}
+
+/// The sizing of a [`FragTable`].
+#[derive(Debug, Clone, Copy)]
+pub struct FragTableConfig {
+    pub bucket_num: u32,
+    /// The entries per bucket, a power of two.
+    pub bucket_entries: u32,
+    /// The number of packets being reassembled at once, at most
+    /// `bucket_num * bucket_entries`.
+    pub max_entries: u32,
+    /// How long the fragments of a packet are kept before giving up.
+    pub max_age: Duration,
+    pub socket_id: Option<u32>,
+}
+
+impl Default for FragTableConfig {
+    fn default() -> Self {
+        Self {
+            bucket_num: 4096,
+            bucket_entries: 16,
+            max_entries: 4096,
+            max_age: Duration::from_secs(1),
+            socket_id: None,
+        }
+    }
+}
+
+/// An IPv4 and IPv6 reassembly table, `rte_ip_frag_tbl`, with its death
+/// row.
+///
+/// The table is not thread safe and meant to be used by a single lcore, so
+/// it is neither `Send` nor `Sync`: create one on each lcore reassembling
+/// packets.
+pub struct FragTable {
+    raw: NonNull<rte_ip_frag_tbl>,
+    death_row: Box<rte_ip_frag_death_row>,
+    _lcore: PhantomData<*const ()>,
+}
+
+impl FragTable {
+    pub fn new(config: &FragTableConfig) -> Result<Self> {
+        let hz = unsafe { rte_get_tsc_hz() };
+        let max_cycles = (hz as u128 * config.max_age.as_nanos() / 1_000_000_000) as u64;
+        let raw = unsafe {
+            rte_ip_frag_table_create(
+                config.bucket_num,
+                config.bucket_entries,
+                config.max_entries,
+                max_cycles,
+                config.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32),
+            )
+        };
+        let raw = NonNull::new(raw).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            death_row: Box::new(unsafe { mem::zeroed() }),
+            _lcore: PhantomData,
+        })
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_ip_frag_tbl {
+        self.raw.as_ptr()
+    }
+
+    /// Add the fragment `mbuf` to the table.
+    ///
+    /// Returns the reassembled packet once all its fragments arrived, and
+    /// `None` until then. Packets that are not IP fragments are returned as
+    /// is. Fragments that cannot be reassembled, e.g. duplicates or the
+    /// fragments of a table overflow, are moved to the death row.
+    ///
+    /// `l2_len` and `l3_len` must be set in the mbuf, e.g. by
+    /// [`Mbuf::parse_header_lens`], and the headers must be in the first
+    /// segment.
+    pub fn reassemble(&mut self, mut mbuf: Mbuf) -> Option<Mbuf> {
+        let l2 = mbuf.header_lens().l2_len as usize;
+        let data = mbuf.data_mut();
+        let Some(ip) = data.get_mut(l2..) else {
+            return Some(mbuf);
+        };
+        let hdr = ip.as_mut_ptr();
+        let raw = match ip.first().map(|b| b >> 4) {
+            Some(4) if ip.len() >= mem::size_of::<rte_ipv4_hdr>() => {
+                let hdr = hdr as *mut rte_ipv4_hdr;
+                let frag = u16::from_be(unsafe { (*hdr).fragment_offset }) as u32;
+                if frag & (RTE_IPV4_HDR_MF_FLAG | RTE_IPV4_HDR_OFFSET_MASK) == 0 {
+                    return Some(mbuf);
+                }
+                self.reap();
+                unsafe {
+                    rte_ipv4_frag_reassemble_packet(
+                        self.as_ptr(),
+                        &mut *self.death_row,
+                        mbuf.into_raw(),
+                        rte_rdtsc_(),
+                        hdr,
+                    )
+                }
+            }
+            Some(6)
+                if ip.len()
+                    >= mem::size_of::<rte_ipv6_hdr>() + mem::size_of::<rte_ipv6_fragment_ext>() =>
+            {
+                let hdr = hdr as *mut rte_ipv6_hdr;
+                // As rte_ipv6_frag_get_ipv6_fragment_header(), only a fragment
+                // header right after the IPv6 header is handled.
+                if unsafe { (*hdr).proto } != IPPROTO_FRAGMENT {
+                    return Some(mbuf);
+                }
+                let frag_hdr = unsafe { hdr.add(1) } as *mut rte_ipv6_fragment_ext;
+                self.reap();
+                unsafe {
+                    rte_ipv6_frag_reassemble_packet(
+                        self.as_ptr(),
+                        &mut *self.death_row,
+                        mbuf.into_raw(),
+                        rte_rdtsc_(),
+                        hdr,
+                        frag_hdr,
+                    )
+                }
+            }
+            _ => return Some(mbuf),
+        };
+        unsafe { Mbuf::from_raw(raw) }
+    }
+
+    /// Drop the packets whose fragments are older than the maximum age.
+    pub fn del_expired(&mut self) {
+        self.free_death_row();
+        unsafe {
+            rte_ip_frag_table_del_expired_entries(self.as_ptr(), &mut *self.death_row, rte_rdtsc_())
+        };
+        self.free_death_row();
+    }
+
+    /// Free the mbufs on the death row.
+    pub fn free_death_row(&mut self) {
+        unsafe { rte_ip_frag_free_death_row(&mut *self.death_row, DEATH_ROW_PREFETCH) };
+    }
+
+    /// The number of mbufs waiting on the death row.
+    pub fn death_row_len(&self) -> usize {
+        self.death_row.cnt as usize
+    }
+
+    /// Make room on the death row for all the fragments of a packet.
+    fn reap(&mut self) {
+        let room = self.death_row.row.len() - self.death_row.cnt as usize;
+        if room <= RTE_LIBRTE_IP_FRAG_MAX_FRAG as usize {
+            self.free_death_row();
+        }
+    }
+}
+
+/// How many mbufs ahead `rte_ip_frag_free_death_row` prefetches.
+const DEATH_ROW_PREFETCH: u32 = 3;
+
+impl Drop for FragTable {
+    fn drop(&mut self) {
+        self.free_death_row();
+        unsafe { rte_ip_frag_table_destroy(self.raw.as_ptr()) };
+    }
+}
@@ -0,0 +1,210 @@
+use std::ffi::c_void;
+use std::ops::{BitOr, BitOrAssign};
+use std::ptr::NonNull;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::mbuf::Mbuf;
+use crate::*;
+
+/// The kinds of packets GRO merges, `RTE_GRO_*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GroTypes(u64);
+
+impl GroTypes {
+    pub const TCP_IPV4: Self = Self(RTE_GRO_TCP_IPV4 as u64);
+    pub const TCP_IPV6: Self = Self(RTE_GRO_TCP_IPV6 as u64);
+    pub const UDP_IPV4: Self = Self(RTE_GRO_UDP_IPV4 as u64);
+    /// TCP/IPv4 in VXLAN over IPv4.
+    pub const VXLAN_TCP_IPV4: Self = Self(RTE_GRO_IPV4_VXLAN_TCP_IPV4 as u64);
+    /// UDP/IPv4 in VXLAN over IPv4.
+    pub const VXLAN_UDP_IPV4: Self = Self(RTE_GRO_IPV4_VXLAN_UDP_IPV4 as u64);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self(
+            Self::TCP_IPV4.0
+                | Self::TCP_IPV6.0
+                | Self::UDP_IPV4.0
+                | Self::VXLAN_TCP_IPV4.0
+                | Self::VXLAN_UDP_IPV4.0,
+        )
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for GroTypes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for GroTypes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// The parameters of GRO, `rte_gro_param`.
+#[derive(Debug, Clone, Copy)]
+pub struct GroConfig {
+    pub types: GroTypes,
+    /// The number of flows merged at once.
+    pub max_flow_num: u16,
+    /// The number of packets per flow kept for merging.
+    pub max_item_per_flow: u16,
+    pub socket_id: u16,
+}
+
+impl Default for GroConfig {
+    fn default() -> Self {
+        Self {
+            types: GroTypes::TCP_IPV4 | GroTypes::TCP_IPV6,
+            max_flow_num: 64,
+            max_item_per_flow: 32,
+            socket_id: 0,
+        }
+    }
+}
+
+impl GroConfig {
+    fn as_raw(&self) -> rte_gro_param {
+        rte_gro_param {
+            gro_types: self.types.bits(),
+            max_flow_num: self.max_flow_num,
+            max_item_per_flow: self.max_item_per_flow,
+            socket_id: self.socket_id,
+        }
+    }
+}
+
+/// Merge the packets of `pkts` in place, within the burst only.
+///
+/// Merged packets are chained to the first packet of their flow, and
+/// `pkts` is left with the merged and unmerged packets.
+///
+/// The packet type and the header lengths of each mbuf must be set, e.g.
+/// by [`Mbuf::fill_packet_type`] and [`Mbuf::parse_header_lens`].
+pub fn reassemble_burst(pkts: &mut Vec<Mbuf>, config: &GroConfig) {
+    let param = config.as_raw();
+    let n = unsafe {
+        rte_gro_reassemble_burst(
+            pkts.as_mut_ptr() as *mut *mut rte_mbuf,
+            pkts.len().min(u16::MAX as usize) as u16,
+            &param,
+        )
+    };
+    // The mbufs past `n` are now segments of the first ones.
+    unsafe { pkts.set_len(n as usize) };
+}
+
+/// A GRO context, keeping packets across bursts to merge them with later
+/// ones.
+///
+/// The context is not thread safe, so it is `Send` but not `Sync`.
+pub struct GroContext {
+    raw: NonNull<c_void>,
+    types: GroTypes,
+}
+
+unsafe impl Send for GroContext {}
+
+impl GroContext {
+    pub fn new(config: &GroConfig) -> Result<Self> {
+        let param = config.as_raw();
+        let raw = NonNull::new(unsafe { rte_gro_ctx_create(&param) }).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            types: config.types,
+        })
+    }
+
+    pub fn as_ptr(&self) -> *mut c_void {
+        self.raw.as_ptr()
+    }
+
+    /// Move the packets of `pkts` that can be merged into the context,
+    /// leaving the others in `pkts`.
+    ///
+    /// The packet type and the header lengths of each mbuf must be set, e.g.
+    /// by [`Mbuf::fill_packet_type`] and [`Mbuf::parse_header_lens`].
+    pub fn reassemble(&mut self, pkts: &mut Vec<Mbuf>) {
+        let n = unsafe {
+            rte_gro_reassemble(
+                pkts.as_mut_ptr() as *mut *mut rte_mbuf,
+                pkts.len().min(u16::MAX as usize) as u16,
+                self.as_ptr(),
+            )
+        };
+        // The mbufs past `n` are owned by the context now.
+        unsafe { pkts.set_len(n as usize) };
+    }
+
+    /// The number of packets in the context.
+    pub fn len(&self) -> usize {
+        unsafe { rte_gro_get_pkt_count(self.as_ptr()) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Move up to `max` packets of `types` that have been in the context for
+    /// at least `timeout` to `out`, and return how many were moved.
+    pub fn timeout_flush(
+        &mut self,
+        timeout: Duration,
+        types: GroTypes,
+        out: &mut Vec<Mbuf>,
+        max: usize,
+    ) -> usize {
+        let hz = unsafe { rte_get_tsc_hz() };
+        let cycles = (hz as u128 * timeout.as_nanos() / 1_000_000_000) as u64;
+        let max = max.min(u16::MAX as usize);
+        out.reserve(max);
+        let len = out.len();
+        let n = unsafe {
+            rte_gro_timeout_flush(
+                self.as_ptr(),
+                cycles,
+                types.bits(),
+                out.as_mut_ptr().add(len) as *mut *mut rte_mbuf,
+                max as u16,
+            )
+        } as usize;
+        unsafe { out.set_len(len + n) };
+        n
+    }
+
+    /// Move all packets out of the context.
+    pub fn flush(&mut self) -> Vec<Mbuf> {
+        let mut out = Vec::new();
+        while !self.is_empty() {
+            let n = self.timeout_flush(Duration::ZERO, self.types, &mut out, self.len());
+            if n == 0 {
+                break;
+            }
+        }
+        out
+    }
+}
+
+impl Drop for GroContext {
+    fn drop(&mut self) {
+        // rte_gro_ctx_destroy() frees the tables, not the packets in them.
+        drop(self.flush());
+        unsafe { rte_gro_ctx_destroy(self.as_ptr()) };
+    }
+}
@@ -0,0 +1,138 @@
+use std::marker::PhantomData;
+use std::ops::{BitOr, BitOrAssign};
+
+use crate::error::{check, Error, Result, EINVAL};
+use crate::mbuf::{Mbuf, Mempool};
+use crate::*;
+
+/// The kinds of packets GSO segments, as `RTE_ETH_TX_OFFLOAD_*_TSO` flags.
+///
+/// The `RTE_BIT64` based offload flags are not part of the bindings, so
+/// their values are repeated here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GsoTypes(u32);
+
+impl GsoTypes {
+    /// `RTE_ETH_TX_OFFLOAD_TCP_TSO`, TCP over IPv4 or IPv6.
+    pub const TCP: Self = Self(1 << 5);
+    /// `RTE_ETH_TX_OFFLOAD_UDP_TSO`, UDP over IPv4.
+    pub const UDP: Self = Self(1 << 6);
+    /// `RTE_ETH_TX_OFFLOAD_VXLAN_TNL_TSO`, TCP or UDP in VXLAN over IPv4.
+    pub const VXLAN: Self = Self(1 << 9);
+    /// `RTE_ETH_TX_OFFLOAD_GRE_TNL_TSO`, TCP in GRE over IPv4.
+    pub const GRE: Self = Self(1 << 10);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self(Self::TCP.0 | Self::UDP.0 | Self::VXLAN.0 | Self::GRE.0)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for GsoTypes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for GsoTypes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// A GSO context, `rte_gso_ctx`, segmenting packets in software.
+///
+/// Segments get their headers from the direct pool and refer to the payload
+/// of the input packet through mbufs of the indirect pool, so the payload is
+/// not copied.
+#[derive(Clone, Copy)]
+pub struct GsoContext<'a> {
+    raw: rte_gso_ctx,
+    _pools: PhantomData<&'a Mempool>,
+}
+
+unsafe impl Send for GsoContext<'_> {}
+unsafe impl Sync for GsoContext<'_> {}
+
+impl<'a> GsoContext<'a> {
+    /// A context for all supported types, making segments of at most
+    /// `gso_size` bytes, headers included.
+    pub fn new(direct: &'a Mempool, indirect: &'a Mempool, gso_size: u16) -> Self {
+        Self {
+            raw: rte_gso_ctx {
+                direct_pool: direct.as_ptr(),
+                indirect_pool: indirect.as_ptr(),
+                flag: 0,
+                gso_types: GsoTypes::all().bits(),
+                gso_size,
+            },
+            _pools: PhantomData,
+        }
+    }
+
+    pub fn types(mut self, types: GsoTypes) -> Self {
+        self.raw.gso_types = types.bits();
+        self
+    }
+
+    /// Give all segments the IPv4 id of the input packet instead of
+    /// incrementing it.
+    pub fn fixed_ip_id(mut self) -> Self {
+        self.raw.flag |= RTE_GSO_FLAG_IPID_FIXED as u64;
+        self
+    }
+
+    pub fn gso_size(&self) -> u16 {
+        self.raw.gso_size
+    }
+
+    /// Segment `pkt`, which must request segmentation offload with its header
+    /// lengths and `RTE_MBUF_F_TX_*_SEG` flags, e.g. through
+    /// [`Mbuf::set_tx_offload`].
+    ///
+    /// Returns the segments, or `pkt` alone if it needs no segmentation. The
+    /// packet is dropped on failure.
+    pub fn segment(&self, mut pkt: Mbuf) -> Result<Vec<Mbuf>> {
+        let lens = pkt.header_lens();
+        let hdr_len =
+            lens.outer_l2_len + lens.outer_l3_len + lens.l2_len + lens.l3_len + lens.l4_len;
+        let seg_payload = self.raw.gso_size.saturating_sub(hdr_len);
+        if seg_payload == 0 {
+            return Err(Error::new(EINVAL));
+        }
+        let max = (pkt.pkt_len() as usize)
+            .div_ceil(seg_payload as usize)
+            .clamp(1, u16::MAX as usize);
+
+        let mut out: Vec<Mbuf> = Vec::with_capacity(max);
+        let n = check(unsafe {
+            rte_gso_segment(
+                pkt.as_mut_ptr(),
+                &self.raw,
+                out.as_mut_ptr() as *mut *mut rte_mbuf,
+                max as u16,
+            )
+        })?;
+        if n == 0 {
+            out.push(pkt);
+        } else {
+            // The segments hold their own references to the payload, `pkt`
+            // is released on drop.
+            unsafe { out.set_len(n as usize) };
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,484 @@
+use std::ffi::{c_void, CString};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::{self, NonNull};
+
+use crate::error::{check, Error, Result, EEXIST, EINVAL, SOCKET_ID_ANY};
+use crate::*;
+
+/// Plain old data usable as a hash key: the key is hashed and compared as
+/// its raw bytes.
+///
+/// # Safety
+///
+/// The type must have no padding, and values comparing equal must have the
+/// same bytes.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// The hash function of a table.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum HashFunction {
+    /// `rte_jhash`, the default.
+    #[default]
+    Jhash,
+    /// `rte_hash_crc`, using the CPU CRC32 instructions where available.
+    Crc,
+    /// A custom function, `None` leaving the choice to `rte_hash_create`.
+    Custom(rte_hash_function),
+}
+
+impl HashFunction {
+    fn as_raw(&self) -> rte_hash_function {
+        match self {
+            HashFunction::Jhash => Some(rte_jhash_),
+            HashFunction::Crc => Some(rte_hash_crc_),
+            HashFunction::Custom(f) => *f,
+        }
+    }
+}
+
+pub fn jhash(data: &[u8], init_val: u32) -> u32 {
+    unsafe { rte_jhash_(data.as_ptr() as *const c_void, data.len() as u32, init_val) }
+}
+
+pub fn hash_crc(data: &[u8], init_val: u32) -> u32 {
+    unsafe { rte_hash_crc_(data.as_ptr() as *const c_void, data.len() as u32, init_val) }
+}
+
+/// Builder for a [`DpdkHash`].
+#[derive(Debug, Clone)]
+pub struct HashBuilder {
+    name: String,
+    entries: u32,
+    hash_func: HashFunction,
+    init_val: u32,
+    socket_id: Option<u32>,
+    flags: u8,
+}
+
+impl HashBuilder {
+    pub fn new<S: Into<String>>(name: S, entries: u32) -> Self {
+        Self {
+            name: name.into(),
+            entries,
+            hash_func: HashFunction::default(),
+            init_val: 0,
+            socket_id: None,
+            flags: 0,
+        }
+    }
+
+    pub fn hash_func(mut self, hash_func: HashFunction) -> Self {
+        self.hash_func = hash_func;
+        self
+    }
+
+    pub fn init_val(mut self, init_val: u32) -> Self {
+        self.init_val = init_val;
+        self
+    }
+
+    pub fn socket_id(mut self, socket_id: u32) -> Self {
+        self.socket_id = Some(socket_id);
+        self
+    }
+
+    /// Chain colliding keys in an extendable bucket table, so inserts only
+    /// fail once `entries` keys are stored.
+    pub fn ext_table(mut self) -> Self {
+        self.flags |= RTE_HASH_EXTRA_FLAGS_EXT_TABLE as u8;
+        self
+    }
+
+    /// Allow concurrent inserts and deletes from several threads.
+    pub fn multi_writer(mut self) -> Self {
+        self.flags |= RTE_HASH_EXTRA_FLAGS_MULTI_WRITER_ADD as u8;
+        self
+    }
+
+    /// Allow lookups concurrent with writes, using a reader-writer lock.
+    pub fn rw_concurrency(mut self) -> Self {
+        self.flags |= RTE_HASH_EXTRA_FLAGS_RW_CONCURRENCY as u8;
+        self
+    }
+
+    /// Allow lookups concurrent with writes without locking readers.
+    ///
+    /// Deleted keys are not recycled until reclaimed, see
+    /// [`DpdkHash::attach_rcu`].
+    pub fn lock_free(mut self) -> Self {
+        self.flags |= RTE_HASH_EXTRA_FLAGS_RW_CONCURRENCY_LF as u8;
+        self
+    }
+
+    /// Use hardware transactional memory where available.
+    pub fn transactional_memory(mut self) -> Self {
+        self.flags |= RTE_HASH_EXTRA_FLAGS_TRANS_MEM_SUPPORT as u8;
+        self
+    }
+
+    pub fn create<K: Pod, V: Send + Sync>(self) -> Result<DpdkHash<K, V>> {
+        let name = CString::new(self.name).map_err(|_| Error::new(EINVAL))?;
+        let params = rte_hash_parameters {
+            name: name.as_ptr(),
+            entries: self.entries,
+            reserved: 0,
+            key_len: mem::size_of::<K>() as u32,
+            hash_func: self.hash_func.as_raw(),
+            hash_func_init_val: self.init_val,
+            socket_id: self.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32),
+            extra_flag: self.flags,
+        };
+        let raw = NonNull::new(unsafe { rte_hash_create(&params) }).ok_or_else(Error::last)?;
+        Ok(DpdkHash {
+            raw,
+            flags: self.flags,
+            rcu: false,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// How deleted entries are reclaimed once an RCU variable is attached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RcuMode {
+    /// Queue deleted entries and reclaim them once readers went through a
+    /// quiescent state. `dq_size` is the queue size, 0 for the table size.
+    Defer { dq_size: u32 },
+    /// Block the deleting thread until readers went through a quiescent
+    /// state.
+    Sync,
+}
+
+/// A typed `rte_hash`, mapping keys of `K` to boxed values of `V`.
+pub struct DpdkHash<K, V> {
+    raw: NonNull<rte_hash>,
+    flags: u8,
+    rcu: bool,
+    _marker: PhantomData<(K, Box<V>)>,
+}
+
+unsafe impl<K: Send, V: Send> Send for DpdkHash<K, V> {}
+unsafe impl<K: Sync, V: Send + Sync> Sync for DpdkHash<K, V> {}
+
+unsafe extern "C" fn free_value<V>(_ctx: *mut c_void, data: *mut c_void) {
+    // Values taken out by `remove` are replaced by NULL before deletion.
+    if !data.is_null() {
+        drop(Box::from_raw(data as *mut V));
+    }
+}
+
+impl<K: Pod, V: Send + Sync> DpdkHash<K, V> {
+    pub fn new(name: &str, entries: u32) -> Result<Self> {
+        HashBuilder::new(name, entries).create()
+    }
+
+    pub fn builder<S: Into<String>>(name: S, entries: u32) -> HashBuilder {
+        HashBuilder::new(name, entries)
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_hash {
+        self.raw.as_ptr()
+    }
+
+    fn key_ptr(key: &K) -> *const c_void {
+        key as *const K as *const c_void
+    }
+
+    /// Reclaim deleted entries through the RCU variable `v`, which makes
+    /// [`insert_shared`](Self::insert_shared) and
+    /// [`remove_shared`](Self::remove_shared) available on lock-free tables.
+    ///
+    /// # Safety
+    ///
+    /// `v` must outlive the table. Reader threads must be registered with
+    /// `v` and only report a quiescent state while they hold no reference
+    /// returned by this table.
+    pub unsafe fn attach_rcu(&mut self, v: *mut rte_rcu_qsbr, mode: RcuMode) -> Result<()> {
+        let (mode, dq_size) = match mode {
+            RcuMode::Defer { dq_size } => (rte_hash_qsbr_mode_RTE_HASH_QSBR_MODE_DQ, dq_size),
+            RcuMode::Sync => (rte_hash_qsbr_mode_RTE_HASH_QSBR_MODE_SYNC, 0),
+        };
+        let mut cfg: rte_hash_rcu_config = mem::zeroed();
+        cfg.v = v;
+        cfg.mode = mode;
+        cfg.dq_size = dq_size;
+        cfg.free_key_data_func = Some(free_value::<V>);
+        // Unlike most calls, failures are reported as 1.
+        if rte_hash_rcu_qsbr_add(self.as_ptr(), &mut cfg) != 0 {
+            return Err(Error::last());
+        }
+        self.rcu = true;
+        Ok(())
+    }
+
+    /// The number of keys in the table.
+    pub fn len(&self) -> usize {
+        unsafe { rte_hash_count(self.as_ptr()) }.max(0) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The signature of `key`, for the `*_with_hash` variants.
+    pub fn hash(&self, key: &K) -> u32 {
+        unsafe { rte_hash_hash(self.as_ptr(), Self::key_ptr(key)) }
+    }
+
+    fn lookup_raw(&self, key: &K, sig: Option<u32>) -> Option<*mut V> {
+        let mut data = ptr::null_mut();
+        let ret = unsafe {
+            match sig {
+                Some(sig) => rte_hash_lookup_with_hash_data(
+                    self.as_ptr(),
+                    Self::key_ptr(key),
+                    sig,
+                    &mut data,
+                ),
+                None => rte_hash_lookup_data(self.as_ptr(), Self::key_ptr(key), &mut data),
+            }
+        };
+        if ret < 0 || data.is_null() {
+            None
+        } else {
+            Some(data as *mut V)
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.lookup_raw(key, None).map(|v| unsafe { &*v })
+    }
+
+    /// Like [`get`](Self::get), with the signature computed by
+    /// [`hash`](Self::hash).
+    pub fn get_with_hash(&self, key: &K, sig: u32) -> Option<&V> {
+        self.lookup_raw(key, Some(sig)).map(|v| unsafe { &*v })
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.lookup_raw(key, None).map(|v| unsafe { &mut *v })
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Look up all of `keys`, `RTE_HASH_LOOKUP_BULK_MAX` at a time.
+    pub fn get_bulk(&self, keys: &[K]) -> Vec<Option<&V>> {
+        let mut ret = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(RTE_HASH_LOOKUP_BULK_MAX as usize) {
+            let mut key_ptrs: Vec<*const c_void> = chunk.iter().map(Self::key_ptr).collect();
+            let mut data = vec![ptr::null_mut(); chunk.len()];
+            let mut hit_mask = 0u64;
+            unsafe {
+                rte_hash_lookup_bulk_data(
+                    self.as_ptr(),
+                    key_ptrs.as_mut_ptr(),
+                    chunk.len() as u32,
+                    &mut hit_mask,
+                    data.as_mut_ptr(),
+                )
+            };
+            for (i, data) in data.into_iter().enumerate() {
+                if hit_mask & (1u64 << i) != 0 && !data.is_null() {
+                    ret.push(Some(unsafe { &*(data as *const V) }));
+                } else {
+                    ret.push(None);
+                }
+            }
+        }
+        ret
+    }
+
+    fn add_raw(&self, key: &K, sig: Option<u32>, data: *mut V) -> Result<()> {
+        let ret = unsafe {
+            match sig {
+                Some(sig) => rte_hash_add_key_with_hash_data(
+                    self.as_ptr(),
+                    Self::key_ptr(key),
+                    sig,
+                    data as *mut c_void,
+                ),
+                None => {
+                    rte_hash_add_key_data(self.as_ptr(), Self::key_ptr(key), data as *mut c_void)
+                }
+            }
+        };
+        check(ret).map(|_| ())
+    }
+
+    /// Insert `value`, returning the value previously stored for `key`.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>> {
+        let old = self.lookup_raw(&key, None);
+        let new = Box::into_raw(Box::new(value));
+        if let Err(e) = self.add_raw(&key, None, new) {
+            drop(unsafe { Box::from_raw(new) });
+            return Err(e);
+        }
+        Ok(old.map(|old| *unsafe { Box::from_raw(old) }))
+    }
+
+    /// Like [`insert`](Self::insert), with the signature computed by
+    /// [`hash`](Self::hash).
+    pub fn insert_with_hash(&mut self, key: K, sig: u32, value: V) -> Result<Option<V>> {
+        let old = self.lookup_raw(&key, Some(sig));
+        let new = Box::into_raw(Box::new(value));
+        if let Err(e) = self.add_raw(&key, Some(sig), new) {
+            drop(unsafe { Box::from_raw(new) });
+            return Err(e);
+        }
+        Ok(old.map(|old| *unsafe { Box::from_raw(old) }))
+    }
+
+    fn del_raw(&mut self, key: &K, sig: Option<u32>) -> Option<V> {
+        let old = self.lookup_raw(key, sig)?;
+        if self.rcu {
+            // The entry is reclaimed through free_value(), which must not
+            // free the value handed back to the caller.
+            self.add_raw(key, sig, ptr::null_mut()).ok()?;
+        }
+        let pos = unsafe {
+            match sig {
+                Some(sig) => rte_hash_del_key_with_hash(self.as_ptr(), Self::key_ptr(key), sig),
+                None => rte_hash_del_key(self.as_ptr(), Self::key_ptr(key)),
+            }
+        };
+        if pos < 0 {
+            return None;
+        }
+        if !self.rcu && self.keeps_deleted_keys() {
+            // No reader can hold the entry while the table is borrowed
+            // mutably.
+            unsafe { rte_hash_free_key_with_position(self.as_ptr(), pos) };
+        }
+        Some(*unsafe { Box::from_raw(old) })
+    }
+
+    fn keeps_deleted_keys(&self) -> bool {
+        self.flags & RTE_HASH_EXTRA_FLAGS_RW_CONCURRENCY_LF as u8 != 0
+            || self.flags & RTE_HASH_EXTRA_FLAGS_NO_FREE_ON_DEL as u8 != 0
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.del_raw(key, None)
+    }
+
+    /// Like [`remove`](Self::remove), with the signature computed by
+    /// [`hash`](Self::hash).
+    pub fn remove_with_hash(&mut self, key: &K, sig: u32) -> Option<V> {
+        self.del_raw(key, Some(sig))
+    }
+
+    fn check_shared(&self) -> Result<()> {
+        let shared = RTE_HASH_EXTRA_FLAGS_RW_CONCURRENCY_LF | RTE_HASH_EXTRA_FLAGS_MULTI_WRITER_ADD;
+        if self.rcu && self.flags as u32 & shared == shared {
+            Ok(())
+        } else {
+            Err(Error::new(EINVAL))
+        }
+    }
+
+    /// Insert `value` while other threads may read or write the table.
+    ///
+    /// Requires a lock-free, multi-writer table with RCU attached. Fails
+    /// with `EEXIST` if `key` is present. If two threads insert the same key
+    /// at once, one of the values may be leaked.
+    pub fn insert_shared(&self, key: K, value: V) -> Result<()> {
+        self.check_shared()?;
+        if self.lookup_raw(&key, None).is_some() {
+            return Err(Error::new(EEXIST));
+        }
+        let new = Box::into_raw(Box::new(value));
+        if let Err(e) = self.add_raw(&key, None, new) {
+            drop(unsafe { Box::from_raw(new) });
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Remove `key` while other threads may read or write the table. The
+    /// value is dropped once the readers went through a quiescent state.
+    ///
+    /// Requires a lock-free, multi-writer table with RCU attached.
+    pub fn remove_shared(&self, key: &K) -> Result<()> {
+        self.check_shared()?;
+        let ret = unsafe { rte_hash_del_key(self.as_ptr(), Self::key_ptr(key)) };
+        check(ret).map(|_| ())
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        unsafe {
+            drop_values::<V>(self.as_ptr());
+            rte_hash_reset(self.as_ptr());
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            hash: self,
+            next: 0,
+        }
+    }
+}
+
+unsafe fn drop_values<V>(raw: *mut rte_hash) {
+    let mut next = 0;
+    let mut key = ptr::null();
+    let mut data = ptr::null_mut();
+    while rte_hash_iterate(raw, &mut key, &mut data, &mut next) >= 0 {
+        if !data.is_null() {
+            drop(Box::from_raw(data as *mut V));
+        }
+    }
+}
+
+impl<K, V> Drop for DpdkHash<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            drop_values::<V>(self.raw.as_ptr());
+            // Values of deleted entries still queued for reclamation are
+            // dropped here through free_value().
+            rte_hash_free(self.raw.as_ptr());
+        }
+    }
+}
+
+/// Iterator over the entries of a [`DpdkHash`], in no particular order.
+pub struct Iter<'a, K, V> {
+    hash: &'a DpdkHash<K, V>,
+    next: u32,
+}
+
+impl<'a, K: Pod, V: 'a> Iterator for Iter<'a, K, V> {
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut key = ptr::null();
+            let mut data = ptr::null_mut();
+            let ret = unsafe {
+                rte_hash_iterate(self.hash.raw.as_ptr(), &mut key, &mut data, &mut self.next)
+            };
+            if ret < 0 {
+                return None;
+            }
+            if data.is_null() {
+                continue;
+            }
+            let key = unsafe { ptr::read_unaligned(key as *const K) };
+            return Some((key, unsafe { &*(data as *const V) }));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::ffi::{CStr, CString, OsStr};
+use std::os::raw::c_char;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::dev::Devargs;
+use crate::error::{check, check_errno, Error, Result, EINVAL};
+use crate::process::ProcessType;
+use crate::*;
+
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Command line arguments for `rte_eal_init`.
+///
+/// The EAL can only be initialized once per process; later attempts fail
+/// with `EALREADY`.
+#[derive(Debug, Clone)]
+pub struct EalArgs {
+    args: Vec<String>,
+}
+
+impl EalArgs {
+    /// Arguments starting with the program name `argv[0]`.
+    pub fn new<S: Into<String>>(program: S) -> Self {
+        Self {
+            args: vec![program.into()],
+        }
+    }
+
+    pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// The lcores to run on, e.g. `0-3` or `0,2,4`.
+    pub fn lcores(self, lcores: &str) -> Self {
+        self.arg("-l").arg(lcores)
+    }
+
+    /// Use anonymous memory instead of hugepages.
+    pub fn no_huge(self) -> Self {
+        self.arg("--no-huge")
+    }
+
+    /// Do not create any shared file, so several processes can run without
+    /// a distinct file prefix.
+    pub fn in_memory(self) -> Self {
+        self.arg("--in-memory")
+    }
+
+    pub fn no_pci(self) -> Self {
+        self.arg("--no-pci")
+    }
+
+    pub fn file_prefix(self, prefix: &str) -> Self {
+        self.arg(format!("--file-prefix={}", prefix))
+    }
+
+    /// Run as `proc_type`, which must match the running processes of the
+    /// file prefix.
+    pub fn proc_type(self, proc_type: ProcessType) -> Self {
+        self.arg(format!("--proc-type={}", proc_type.as_arg()))
+    }
+
+    /// Run as a secondary process if a primary is running, as the primary
+    /// otherwise.
+    pub fn proc_type_auto(self) -> Self {
+        self.arg("--proc-type=auto")
+    }
+
+    pub fn vdev(self, devargs: &Devargs) -> Self {
+        self.arg(format!("--vdev={}", devargs))
+    }
+
+    pub fn log_level(self, level: &str) -> Self {
+        self.arg(format!("--log-level={}", level))
+    }
+
+    pub fn as_slice(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Initialize the EAL and return the number of arguments it parsed.
+    pub fn init(&self) -> Result<usize> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| CString::new(arg.as_str()))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| Error::new(EINVAL))?;
+        // rte_eal_init() may permute argv, the strings themselves stay ours.
+        let mut argv: Vec<*mut c_char> = args.iter().map(|a| a.as_ptr() as *mut c_char).collect();
+        let ret = check_errno(unsafe { rte_eal_init(argv.len() as i32, argv.as_mut_ptr()) })?;
+        INITIALIZED.store(true, Ordering::Release);
+        Ok(ret as usize)
+    }
+}
+
+/// Whether the EAL was initialized through [`EalArgs::init`].
+pub fn is_initialized() -> bool {
+    INITIALIZED.load(Ordering::Acquire)
+}
+
+/// The runtime directory of the EAL, holding its sockets, e.g. the
+/// telemetry one.
+pub fn runtime_dir() -> PathBuf {
+    let dir = unsafe { CStr::from_ptr(rte_eal_get_runtime_dir()) };
+    PathBuf::from(OsStr::from_bytes(dir.to_bytes()))
+}
+
+/// Release the EAL resources.
+///
+/// # Safety
+///
+/// No DPDK object may be used after this call, including the ones owned by
+/// the wrappers of this crate.
+pub unsafe fn cleanup() -> Result<()> {
+    check(rte_eal_cleanup()).map(|_| ())
+}
@@ -0,0 +1,138 @@
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+use super::{workers, Lcore, Role};
+use crate::error::{check, Error, Result, EINVAL, EPERM};
+use crate::*;
+
+type Packet<T> = Arc<Mutex<Option<std::thread::Result<T>>>>;
+
+struct Task<F, T> {
+    f: F,
+    packet: Packet<T>,
+}
+
+unsafe extern "C" fn task_trampoline<F, T>(arg: *mut c_void) -> c_int
+where
+    F: FnOnce() -> T,
+{
+    let task = Box::from_raw(arg as *mut Task<F, T>);
+    let Task { f, packet } = *task;
+    // Unwinding into the EAL worker loop is undefined behavior, the panic is
+    // handed over to the joining lcore instead.
+    let result = catch_unwind(AssertUnwindSafe(f));
+    let ret = if result.is_ok() { 0 } else { -1 };
+    *packet.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+    ret
+}
+
+/// Run `f` on the worker `lcore`, which must be in the WAIT state.
+///
+/// Fails with `EPERM` if not called from the main lcore, with `EINVAL` if
+/// `lcore` is not a worker lcore and with `EBUSY` if it is still running a
+/// function.
+pub fn launch<F, T>(lcore: Lcore, f: F) -> Result<JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    if Lcore::current() != Some(Lcore::main()) {
+        return Err(Error::new(EPERM));
+    }
+    // Only EAL workers wait for a function, launching on any other lcore
+    // would block on a thread that never answers.
+    if lcore.is_main() || lcore.role() != Role::Rte {
+        return Err(Error::new(EINVAL));
+    }
+    let packet: Packet<T> = Arc::new(Mutex::new(None));
+    let task = Box::into_raw(Box::new(Task {
+        f,
+        packet: packet.clone(),
+    }));
+
+    let ret = unsafe {
+        rte_eal_remote_launch(
+            Some(task_trampoline::<F, T>),
+            task as *mut c_void,
+            lcore.id(),
+        )
+    };
+    if let Err(e) = check(ret) {
+        // The task was not handed over to the worker.
+        drop(unsafe { Box::from_raw(task) });
+        return Err(e);
+    }
+
+    Ok(JoinHandle {
+        lcore,
+        packet,
+        _main: PhantomData,
+    })
+}
+
+/// Run a clone of `f` on every worker lcore.
+///
+/// If launching fails on one worker, the lcores already launched keep
+/// running and can be waited for with [`wait_all`].
+pub fn launch_workers<F, T>(f: F) -> Result<Vec<JoinHandle<T>>>
+where
+    F: Fn() -> T + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    let f = Arc::new(f);
+    let mut handles = Vec::new();
+    for lcore in workers() {
+        let f = f.clone();
+        handles.push(launch(lcore, move || f())?);
+    }
+    Ok(handles)
+}
+
+/// Wait until all worker lcores are back in the WAIT state.
+pub fn wait_all() {
+    unsafe { rte_eal_mp_wait_lcore() }
+}
+
+/// Whether `lcore` is running a function.
+pub fn is_running(lcore: Lcore) -> bool {
+    unsafe { rte_eal_get_lcore_state(lcore.id()) == rte_lcore_state_t_RUNNING }
+}
+
+/// A handle to a function launched on a worker lcore.
+///
+/// Like the underlying `rte_eal_wait_lcore`, it can only be joined from the
+/// main lcore, so it is neither `Send` nor `Sync`.
+pub struct JoinHandle<T> {
+    lcore: Lcore,
+    packet: Packet<T>,
+    _main: PhantomData<*const ()>,
+}
+
+impl<T> JoinHandle<T> {
+    pub fn lcore(&self) -> Lcore {
+        self.lcore
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.packet
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+
+    /// Wait for the function to return.
+    ///
+    /// Returns the panic payload if the function panicked, so the caller can
+    /// propagate it with `std::panic::resume_unwind`.
+    pub fn join(self) -> std::thread::Result<T> {
+        unsafe { rte_eal_wait_lcore(self.lcore.id()) };
+        self.packet
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .expect("lcore returned without running the launched function")
+    }
+}
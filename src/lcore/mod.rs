@@ -0,0 +1,109 @@
+mod launch;
+mod register;
+mod topology;
+
+pub use launch::*;
+pub use register::*;
+pub use topology::*;
+
+use crate::{
+    rte_get_main_lcore, rte_get_next_lcore, rte_lcore_count, rte_lcore_id_, RTE_MAX_LCORE,
+};
+
+/// A logical core, identified by its lcore id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lcore {
+    id: u32,
+}
+
+impl Lcore {
+    pub const fn new(id: u32) -> Self {
+        Self { id }
+    }
+
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The lcore of the calling thread, `None` for threads that are not
+    /// EAL threads and were not registered.
+    pub fn current() -> Option<Self> {
+        let id = unsafe { rte_lcore_id_() };
+        if id >= RTE_MAX_LCORE {
+            None
+        } else {
+            Some(Self::new(id))
+        }
+    }
+
+    pub fn main() -> Self {
+        Self::new(unsafe { rte_get_main_lcore() })
+    }
+
+    pub fn is_main(&self) -> bool {
+        *self == Self::main()
+    }
+
+    /// The number of enabled lcores.
+    pub fn count() -> u32 {
+        unsafe { rte_lcore_count() }
+    }
+}
+
+/// Iterator over the enabled lcores, built on `rte_get_next_lcore`.
+///
+/// With `wrap`, the iteration restarts from the first lcore after the last
+/// one and never ends, which is handy for round-robin placement.
+#[derive(Debug, Clone)]
+pub struct Lcores {
+    prev: u32,
+    skip_main: bool,
+    wrap: bool,
+}
+
+impl Lcores {
+    pub fn new(skip_main: bool, wrap: bool) -> Self {
+        Self {
+            // rte_get_next_lcore() starts from `prev + 1`.
+            prev: u32::MAX,
+            skip_main,
+            wrap,
+        }
+    }
+
+    /// Start the iteration after `lcore` instead of from the first lcore.
+    pub fn after(mut self, lcore: Lcore) -> Self {
+        self.prev = lcore.id();
+        self
+    }
+}
+
+impl Iterator for Lcores {
+    type Item = Lcore;
+
+    fn next(&mut self) -> Option<Lcore> {
+        // rte_get_next_lcore() never returns when wrapping without any
+        // lcore to return.
+        if self.wrap && Lcore::count() <= self.skip_main as u32 {
+            return None;
+        }
+        let next =
+            unsafe { rte_get_next_lcore(self.prev, self.skip_main as i32, self.wrap as i32) };
+        if next >= RTE_MAX_LCORE {
+            self.prev = RTE_MAX_LCORE;
+            return None;
+        }
+        self.prev = next;
+        Some(Lcore::new(next))
+    }
+}
+
+/// All enabled lcores, the main lcore included.
+pub fn lcores() -> Lcores {
+    Lcores::new(false, false)
+}
+
+/// The enabled worker lcores.
+pub fn workers() -> Lcores {
+    Lcores::new(true, false)
+}
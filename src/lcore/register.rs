@@ -0,0 +1,64 @@
+use std::io;
+use std::marker::PhantomData;
+use std::thread;
+
+use super::Lcore;
+use crate::error::{check_errno, Error, Result, EEXIST};
+use crate::*;
+
+/// Give the calling thread an lcore id, so it can use the per-lcore caches of
+/// mempools and other lcore aware APIs.
+///
+/// The EAL must be initialized. Fails with `ENOMEM` once all `RTE_MAX_LCORE`
+/// lcore ids are taken, and with `EEXIST` if the thread already has one.
+pub fn register_thread() -> Result<Registration> {
+    if Lcore::current().is_some() {
+        return Err(Error::new(EEXIST));
+    }
+    check_errno(unsafe { rte_thread_register() })?;
+    let lcore = Lcore::current().ok_or_else(Error::last)?;
+    Ok(Registration {
+        lcore,
+        _thread: PhantomData,
+    })
+}
+
+/// The lcore id of a registered thread, released when dropped.
+///
+/// The registration belongs to the thread that made it, so it is neither
+/// `Send` nor `Sync`.
+#[derive(Debug)]
+pub struct Registration {
+    lcore: Lcore,
+    _thread: PhantomData<*const ()>,
+}
+
+impl Registration {
+    pub fn lcore(&self) -> Lcore {
+        self.lcore
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        unsafe { rte_thread_unregister() };
+    }
+}
+
+/// Spawn a thread from `builder` that registers itself before running `f`
+/// with its lcore, and unregisters once `f` returns or panics.
+///
+/// Registration failures are returned when joining the thread.
+pub fn spawn_registered<F, T>(
+    builder: thread::Builder,
+    f: F,
+) -> io::Result<thread::JoinHandle<Result<T>>>
+where
+    F: FnOnce(Lcore) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    builder.spawn(move || {
+        let registration = register_thread()?;
+        Ok(f(registration.lcore()))
+    })
+}
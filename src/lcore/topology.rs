@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::os::raw::c_ulong;
+
+use super::Lcore;
+use crate::error::{Error, Result};
+use crate::*;
+
+/// The role the EAL assigned to an lcore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Role {
+    /// An EAL thread, main or worker.
+    Rte,
+    /// The lcore is not in use.
+    Off,
+    /// A service lcore.
+    Service,
+    /// A non-EAL thread registered with `rte_thread_register`.
+    NonEal,
+}
+
+impl Role {
+    fn from_raw(role: rte_lcore_role_t) -> Self {
+        match role {
+            rte_lcore_role_t_ROLE_RTE => Role::Rte,
+            rte_lcore_role_t_ROLE_SERVICE => Role::Service,
+            rte_lcore_role_t_ROLE_NON_EAL => Role::NonEal,
+            _ => Role::Off,
+        }
+    }
+}
+
+impl Lcore {
+    pub fn is_enabled(&self) -> bool {
+        unsafe { rte_lcore_is_enabled(self.id()) == 1 }
+    }
+
+    pub fn role(&self) -> Role {
+        Role::from_raw(unsafe { rte_eal_lcore_role(self.id()) })
+    }
+
+    /// The position of the lcore among the lcores given to the EAL, `None`
+    /// if it is not one of them.
+    pub fn index(&self) -> Option<u32> {
+        let index = unsafe { rte_lcore_index(self.id() as i32) };
+        if index < 0 {
+            None
+        } else {
+            Some(index as u32)
+        }
+    }
+
+    /// The NUMA socket of the lcore.
+    pub fn socket_id(&self) -> u32 {
+        unsafe { rte_lcore_to_socket_id(self.id()) }
+    }
+
+    /// The physical core id of the lcore.
+    pub fn cpu_id(&self) -> Option<u32> {
+        let cpu = unsafe { rte_lcore_to_cpu_id(self.id() as i32) };
+        if cpu < 0 {
+            None
+        } else {
+            Some(cpu as u32)
+        }
+    }
+
+    /// The CPUs the lcore thread is allowed to run on.
+    pub fn cpuset(&self) -> Vec<u32> {
+        let set = unsafe { rte_lcore_cpuset(self.id()) };
+        let bits = c_ulong::BITS;
+        set.__bits
+            .iter()
+            .enumerate()
+            .flat_map(|(i, word)| {
+                (0..bits)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| i as u32 * bits + bit)
+            })
+            .collect()
+    }
+}
+
+/// The number of NUMA sockets detected by the EAL.
+pub fn socket_count() -> u32 {
+    unsafe { rte_socket_count() }
+}
+
+/// The ids of the NUMA sockets detected by the EAL.
+pub fn sockets() -> Result<Vec<u32>> {
+    (0..socket_count())
+        .map(|idx| {
+            let id = unsafe { rte_socket_id_by_idx(idx) };
+            if id < 0 {
+                Err(Error::last())
+            } else {
+                Ok(id as u32)
+            }
+        })
+        .collect()
+}
+
+/// What the EAL knows about one lcore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LcoreInfo {
+    pub lcore: Lcore,
+    pub role: Role,
+    pub index: Option<u32>,
+    pub socket_id: u32,
+    pub cpu_id: Option<u32>,
+    pub cpuset: Vec<u32>,
+}
+
+impl LcoreInfo {
+    pub fn get(lcore: Lcore) -> Self {
+        Self {
+            lcore,
+            role: lcore.role(),
+            index: lcore.index(),
+            socket_id: lcore.socket_id(),
+            cpu_id: lcore.cpu_id(),
+            cpuset: lcore.cpuset(),
+        }
+    }
+}
+
+/// A snapshot of the lcores in use, i.e. all lcore ids below
+/// `RTE_MAX_LCORE` whose role is not [`Role::Off`].
+#[derive(Debug, Clone)]
+pub struct Topology {
+    main: Lcore,
+    sockets: Vec<u32>,
+    lcores: Vec<LcoreInfo>,
+}
+
+impl Topology {
+    pub fn snapshot() -> Result<Self> {
+        let lcores = (0..RTE_MAX_LCORE)
+            .map(Lcore::new)
+            .filter(|lcore| lcore.role() != Role::Off)
+            .map(LcoreInfo::get)
+            .collect();
+        Ok(Self {
+            main: Lcore::main(),
+            sockets: sockets()?,
+            lcores,
+        })
+    }
+
+    pub fn main(&self) -> Lcore {
+        self.main
+    }
+
+    pub fn sockets(&self) -> &[u32] {
+        &self.sockets
+    }
+
+    /// The lcores in use, ordered by lcore id.
+    pub fn lcores(&self) -> &[LcoreInfo] {
+        &self.lcores
+    }
+
+    pub fn get(&self, lcore: Lcore) -> Option<&LcoreInfo> {
+        self.lcores.iter().find(|info| info.lcore == lcore)
+    }
+
+    pub fn on_socket(&self, socket_id: u32) -> impl Iterator<Item = &LcoreInfo> {
+        self.lcores
+            .iter()
+            .filter(move |info| info.socket_id == socket_id)
+    }
+
+    pub fn with_role(&self, role: Role) -> impl Iterator<Item = &LcoreInfo> {
+        self.lcores.iter().filter(move |info| info.role == role)
+    }
+
+    /// The EAL worker lcores on `socket_id`, the main lcore excluded.
+    pub fn workers_on_socket(&self, socket_id: u32) -> impl Iterator<Item = Lcore> + '_ {
+        self.on_socket(socket_id)
+            .filter(move |info| info.role == Role::Rte && info.lcore != self.main)
+            .map(|info| info.lcore)
+    }
+
+    /// The lcores grouped by socket, then by role.
+    pub fn by_socket(&self) -> BTreeMap<u32, BTreeMap<Role, Vec<Lcore>>> {
+        let mut map: BTreeMap<u32, BTreeMap<Role, Vec<Lcore>>> = BTreeMap::new();
+        for info in self.lcores.iter() {
+            map.entry(info.socket_id)
+                .or_default()
+                .entry(info.role)
+                .or_default()
+                .push(info.lcore);
+        }
+        map
+    }
+}
@@ -0,0 +1,175 @@
+use std::ffi::CString;
+use std::ptr::NonNull;
+use std::slice;
+
+use crate::error::{Error, Result, EINVAL, SOCKET_ID_ANY};
+use crate::*;
+
+/// A packet mbuf pool created with `rte_pktmbuf_pool_create`.
+///
+/// The pool is freed on drop once all its mbufs are back. If some are still
+/// in use, it is leaked rather than freed under them.
+#[derive(Debug)]
+pub struct Mempool {
+    raw: NonNull<rte_mempool>,
+    owned: bool,
+}
+
+unsafe impl Send for Mempool {}
+unsafe impl Sync for Mempool {}
+
+impl Mempool {
+    /// Create a pool of `n` mbufs with `data_room_size` bytes of buffer each,
+    /// headroom included. `socket_id` is `None` for any socket.
+    pub fn create(
+        name: &str,
+        n: u32,
+        cache_size: u32,
+        data_room_size: u16,
+        socket_id: Option<u32>,
+    ) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let socket_id = socket_id.map_or(SOCKET_ID_ANY, |id| id as i32);
+        let raw = unsafe {
+            rte_pktmbuf_pool_create(name.as_ptr(), n, cache_size, 0, data_room_size, socket_id)
+        };
+        NonNull::new(raw)
+            .map(|raw| Self { raw, owned: true })
+            .ok_or_else(Error::last)
+    }
+
+    /// Look up a pool created elsewhere, e.g. by the primary process. The
+    /// pool is not freed when the handle is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The pool must be a packet mbuf pool and outlive the handle.
+    pub unsafe fn lookup(name: &str) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let raw = NonNull::new(rte_mempool_lookup(name.as_ptr())).ok_or_else(Error::last)?;
+        Ok(Self { raw, owned: false })
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_mempool {
+        self.raw.as_ptr()
+    }
+
+    /// The number of mbufs that can still be allocated.
+    pub fn avail_count(&self) -> u32 {
+        unsafe { rte_mempool_avail_count(self.as_ptr()) }
+    }
+
+    pub fn is_full(&self) -> bool {
+        unsafe { rte_mempool_full_(self.as_ptr()) == 1 }
+    }
+
+    pub fn alloc(&self) -> Option<Mbuf> {
+        unsafe { Mbuf::from_raw(rte_pktmbuf_alloc_(self.as_ptr())) }
+    }
+}
+
+impl Drop for Mempool {
+    fn drop(&mut self) {
+        if self.owned && self.is_full() {
+            unsafe { rte_mempool_free(self.as_ptr()) };
+        }
+    }
+}
+
+/// An owned packet mbuf, freed back to its pool on drop.
+///
+/// The handle is a single pointer, so it can be moved between lcores through
+/// a [`Ring`](crate::ring::Ring).
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct Mbuf {
+    raw: NonNull<rte_mbuf>,
+}
+
+unsafe impl Send for Mbuf {}
+
+impl Mbuf {
+    /// # Safety
+    ///
+    /// `raw` must be a valid mbuf that is not owned by anything else.
+    pub unsafe fn from_raw(raw: *mut rte_mbuf) -> Option<Self> {
+        NonNull::new(raw).map(|raw| Self { raw })
+    }
+
+    /// Release ownership of the mbuf without freeing it.
+    pub fn into_raw(self) -> *mut rte_mbuf {
+        let raw = self.raw.as_ptr();
+        std::mem::forget(self);
+        raw
+    }
+
+    pub fn as_ptr(&self) -> *const rte_mbuf {
+        self.raw.as_ptr()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut rte_mbuf {
+        self.raw.as_ptr()
+    }
+
+    pub fn as_raw(&self) -> &rte_mbuf {
+        unsafe { self.raw.as_ref() }
+    }
+
+    pub fn as_raw_mut(&mut self) -> &mut rte_mbuf {
+        unsafe { self.raw.as_mut() }
+    }
+
+    /// The input port of a received packet.
+    pub fn port(&self) -> u16 {
+        self.as_raw().port
+    }
+
+    /// The length of the whole packet, all segments included.
+    pub fn pkt_len(&self) -> u32 {
+        self.as_raw().pkt_len
+    }
+
+    /// The length of the data in the first segment.
+    pub fn data_len(&self) -> u16 {
+        self.as_raw().data_len
+    }
+
+    pub fn nb_segs(&self) -> u16 {
+        self.as_raw().nb_segs
+    }
+
+    /// The data of the first segment.
+    pub fn data(&self) -> &[u8] {
+        let m = self.as_raw();
+        unsafe {
+            let data = (m.buf_addr as *const u8).add(m.data_off as usize);
+            slice::from_raw_parts(data, m.data_len as usize)
+        }
+    }
+
+    /// The data of the first segment.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let m = self.as_raw_mut();
+        unsafe {
+            let data = (m.buf_addr as *mut u8).add(m.data_off as usize);
+            slice::from_raw_parts_mut(data, m.data_len as usize)
+        }
+    }
+
+    /// Grow the packet by `len` bytes at the end of its last segment, and
+    /// return them. Fails if the segment has not enough tailroom.
+    pub fn append(&mut self, len: u16) -> Option<&mut [u8]> {
+        let data = unsafe { rte_pktmbuf_append_(self.as_mut_ptr(), len) };
+        if data.is_null() {
+            None
+        } else {
+            Some(unsafe { slice::from_raw_parts_mut(data as *mut u8, len as usize) })
+        }
+    }
+}
+
+impl Drop for Mbuf {
+    fn drop(&mut self) {
+        unsafe { rte_pktmbuf_free_(self.raw.as_ptr()) };
+    }
+}
@@ -0,0 +1,189 @@
+use std::alloc::{GlobalAlloc, Layout};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+
+use crate::error::{Error, Result, EINVAL, ENOMEM, SOCKET_ID_ANY};
+use crate::*;
+
+/// The heap statistics of `socket_id`, `rte_malloc_socket_stats`.
+pub fn heap_stats(socket_id: u32) -> Result<rte_malloc_socket_stats> {
+    let mut stats: rte_malloc_socket_stats = unsafe { mem::zeroed() };
+    if unsafe { rte_malloc_get_socket_stats(socket_id as i32, &mut stats) } < 0 {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(stats)
+}
+
+/// An allocator of DPDK heap memory, through `rte_malloc_socket`.
+///
+/// The memory comes from the hugepages of the EAL, on the chosen NUMA
+/// socket. It implements the stable [`GlobalAlloc`] trait to be called
+/// explicitly or through [`DpdkBox`], but must not be the
+/// `#[global_allocator]`: Rust allocates before the EAL is initialized,
+/// e.g. the arguments passed to it, and after `rte_eal_cleanup`, when there
+/// is no DPDK heap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DpdkAllocator {
+    socket_id: Option<u32>,
+}
+
+impl DpdkAllocator {
+    /// Allocate on any socket, preferably the one of the calling lcore.
+    pub const fn new() -> Self {
+        Self { socket_id: None }
+    }
+
+    pub const fn on_socket(socket_id: u32) -> Self {
+        Self {
+            socket_id: Some(socket_id),
+        }
+    }
+
+    pub const fn socket_id(&self) -> Option<u32> {
+        self.socket_id
+    }
+
+    fn raw_socket_id(&self) -> i32 {
+        self.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32)
+    }
+}
+
+unsafe impl GlobalAlloc for DpdkAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        rte_malloc_socket(
+            ptr::null(),
+            layout.size(),
+            layout.align() as u32,
+            self.raw_socket_id(),
+        ) as *mut u8
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        rte_zmalloc_socket(
+            ptr::null(),
+            layout.size(),
+            layout.align() as u32,
+            self.raw_socket_id(),
+        ) as *mut u8
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        rte_free(ptr as *mut _);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        rte_realloc_socket(
+            ptr as *mut _,
+            new_size,
+            layout.align() as u32,
+            self.raw_socket_id(),
+        ) as *mut u8
+    }
+}
+
+/// A value in DPDK heap memory, the [`Box`] of a [`DpdkAllocator`].
+pub struct DpdkBox<T> {
+    raw: NonNull<T>,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for DpdkBox<T> {}
+unsafe impl<T: Sync> Sync for DpdkBox<T> {}
+
+impl<T> DpdkBox<T> {
+    /// Move `value` to the heap of `socket_id`, or of any socket if `None`.
+    pub fn new(value: T, socket_id: Option<u32>) -> Result<Self> {
+        let raw = Self::alloc(socket_id, false)?;
+        unsafe { raw.as_ptr().write(value) };
+        Ok(Self {
+            raw,
+            _marker: PhantomData,
+        })
+    }
+
+    /// A zero-filled value on the heap of `socket_id`.
+    ///
+    /// # Safety
+    ///
+    /// All zeroes must be a valid `T`.
+    pub unsafe fn zeroed(socket_id: Option<u32>) -> Result<Self> {
+        Ok(Self {
+            raw: Self::alloc(socket_id, true)?,
+            _marker: PhantomData,
+        })
+    }
+
+    fn alloc(socket_id: Option<u32>, zeroed: bool) -> Result<NonNull<T>> {
+        let layout = Layout::new::<T>();
+        if layout.size() == 0 {
+            return Ok(NonNull::dangling());
+        }
+        let allocator = socket_id.map_or(DpdkAllocator::new(), DpdkAllocator::on_socket);
+        let raw = unsafe {
+            if zeroed {
+                allocator.alloc_zeroed(layout)
+            } else {
+                allocator.alloc(layout)
+            }
+        };
+        NonNull::new(raw as *mut T).ok_or(Error::new(ENOMEM))
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.raw.as_ptr()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.raw.as_ptr()
+    }
+
+    /// The IO address of the value, e.g. to hand it to a device.
+    pub fn iova(&self) -> u64 {
+        unsafe { rte_malloc_virt2iova(self.as_ptr() as *const _) }
+    }
+
+    /// Move the value out of DPDK memory.
+    pub fn into_inner(self) -> T {
+        let value = unsafe { self.raw.as_ptr().read() };
+        let raw = self.raw;
+        mem::forget(self);
+        Self::free(raw);
+        value
+    }
+
+    fn free(raw: NonNull<T>) {
+        if mem::size_of::<T>() != 0 {
+            unsafe { rte_free(raw.as_ptr() as *mut _) };
+        }
+    }
+}
+
+impl<T> Deref for DpdkBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.raw.as_ref() }
+    }
+}
+
+impl<T> DerefMut for DpdkBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.raw.as_mut() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DpdkBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for DpdkBox<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.raw.as_ptr()) };
+        Self::free(self.raw);
+    }
+}
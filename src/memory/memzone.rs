@@ -0,0 +1,153 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr::{self, NonNull};
+
+use crate::error::{check, Error, Result, EINVAL, SOCKET_ID_ANY};
+use crate::*;
+
+/// Builder for a [`Memzone`].
+#[derive(Debug, Clone)]
+pub struct MemzoneBuilder {
+    name: String,
+    len: usize,
+    socket_id: Option<u32>,
+    align: u32,
+    flags: u32,
+}
+
+impl MemzoneBuilder {
+    /// A memzone of `len` bytes on any socket, aligned on a cache line.
+    pub fn new<S: Into<String>>(name: S, len: usize) -> Self {
+        Self {
+            name: name.into(),
+            len,
+            socket_id: None,
+            align: RTE_CACHE_LINE_SIZE,
+            flags: 0,
+        }
+    }
+
+    pub fn socket_id(mut self, socket_id: u32) -> Self {
+        self.socket_id = Some(socket_id);
+        self
+    }
+
+    /// Align the memzone on `align` bytes, a power of two.
+    pub fn align(mut self, align: u32) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Reserve physically contiguous memory, e.g. for DMA.
+    pub fn iova_contig(mut self) -> Self {
+        self.flags |= RTE_MEMZONE_IOVA_CONTIG;
+        self
+    }
+
+    pub fn reserve(self) -> Result<Memzone> {
+        let name = CString::new(self.name).map_err(|_| Error::new(EINVAL))?;
+        let raw = unsafe {
+            rte_memzone_reserve_aligned(
+                name.as_ptr(),
+                self.len,
+                self.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32),
+                self.flags,
+                self.align,
+            )
+        };
+        let raw = NonNull::new(raw as *mut rte_memzone).ok_or_else(Error::last)?;
+        Ok(Memzone { raw, owned: true })
+    }
+}
+
+/// A named zone of DPDK memory, `rte_memzone`, shared by all the processes
+/// of the application.
+///
+/// A memzone reserved by this handle is freed on drop; one found by
+/// [`lookup`](Self::lookup) is not.
+pub struct Memzone {
+    raw: NonNull<rte_memzone>,
+    owned: bool,
+}
+
+unsafe impl Send for Memzone {}
+unsafe impl Sync for Memzone {}
+
+impl Memzone {
+    /// Reserve a memzone of `len` bytes on any socket.
+    pub fn reserve(name: &str, len: usize) -> Result<Self> {
+        MemzoneBuilder::new(name, len).reserve()
+    }
+
+    pub fn builder<S: Into<String>>(name: S, len: usize) -> MemzoneBuilder {
+        MemzoneBuilder::new(name, len)
+    }
+
+    /// Look up a memzone reserved elsewhere, e.g. by another process. The
+    /// memzone is not freed when the handle is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The memzone must outlive the handle.
+    pub unsafe fn lookup(name: &str) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let raw = rte_memzone_lookup(name.as_ptr());
+        let raw = NonNull::new(raw as *mut rte_memzone).ok_or_else(Error::last)?;
+        Ok(Self { raw, owned: false })
+    }
+
+    pub fn as_ptr(&self) -> *const rte_memzone {
+        self.raw.as_ptr()
+    }
+
+    fn as_raw(&self) -> &rte_memzone {
+        unsafe { self.raw.as_ref() }
+    }
+
+    pub fn name(&self) -> String {
+        let name = ptr::addr_of!(self.as_raw().name) as *const c_char;
+        unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// The start of the memzone. The memory may be written by other
+    /// processes, so it is only handed out as a raw pointer.
+    pub fn addr(&self) -> *mut u8 {
+        unsafe { self.as_raw().__bindgen_anon_1.addr as *mut u8 }
+    }
+
+    /// The IO address of the start of the memzone.
+    pub fn iova(&self) -> u64 {
+        self.as_raw().iova
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_raw().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The size of the pages backing the memzone.
+    pub fn hugepage_size(&self) -> u64 {
+        self.as_raw().hugepage_sz
+    }
+
+    pub fn socket_id(&self) -> Option<u32> {
+        let socket_id = self.as_raw().socket_id;
+        if socket_id < 0 {
+            None
+        } else {
+            Some(socket_id as u32)
+        }
+    }
+}
+
+impl Drop for Memzone {
+    fn drop(&mut self) {
+        if self.owned {
+            let _ = check(unsafe { rte_memzone_free(self.as_ptr()) });
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod malloc;
+mod memzone;
+
+pub use malloc::*;
+pub use memzone::*;
@@ -0,0 +1,117 @@
+use std::ffi::c_void;
+use std::mem;
+
+use crate::error::{Error, Result, EINVAL};
+use crate::*;
+
+/// The one's complement sum of `data`, not complemented, as
+/// `rte_raw_cksum`.
+pub fn raw_cksum(data: &[u8]) -> u16 {
+    unsafe { rte_raw_cksum_(data.as_ptr() as *const c_void, data.len()) }
+}
+
+/// The length of the IPv4 header starting `ip`, options included.
+fn ipv4_hdr_len(ip: &[u8]) -> Result<usize> {
+    let len = match ip.first() {
+        Some(version_ihl) => {
+            (*version_ihl as u32 & RTE_IPV4_HDR_IHL_MASK) * RTE_IPV4_IHL_MULTIPLIER
+        }
+        None => 0,
+    } as usize;
+    if len < mem::size_of::<rte_ipv4_hdr>() || ip.len() < len {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(len)
+}
+
+/// The IPv4 header checksum of the header starting `ip`, whose checksum
+/// field must be zero.
+pub fn ipv4_cksum(ip: &[u8]) -> Result<u16> {
+    ipv4_hdr_len(ip)?;
+    Ok(unsafe { rte_ipv4_cksum_(ip.as_ptr() as *const rte_ipv4_hdr) })
+}
+
+/// The checksum of the IPv4 pseudo header, to put in the L4 checksum field
+/// for checksum offload.
+///
+/// With `RTE_MBUF_F_TX_TCP_SEG` or `RTE_MBUF_F_TX_UDP_SEG` in `ol_flags`, the
+/// L4 length is left out as required for segmentation offload.
+pub fn ipv4_phdr_cksum(ip: &rte_ipv4_hdr, ol_flags: u64) -> u16 {
+    unsafe { rte_ipv4_phdr_cksum_(ip, ol_flags) }
+}
+
+/// Check that `ip` holds an IPv4 header and its whole payload, and return
+/// the offset of the payload.
+fn ipv4_payload(ip: &[u8]) -> Result<usize> {
+    let hdr_len = ipv4_hdr_len(ip)?;
+    let total_len = u16::from_be_bytes([ip[2], ip[3]]) as usize;
+    if total_len < hdr_len || ip.len() < total_len {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(hdr_len)
+}
+
+/// The TCP or UDP checksum of the IPv4 packet starting `ip`, whose L4
+/// checksum field must be zero.
+///
+/// `ip` must hold the whole packet, up to the IPv4 total length.
+pub fn ipv4_udptcp_cksum(ip: &[u8]) -> Result<u16> {
+    let l4 = ipv4_payload(ip)?;
+    let ptr = ip.as_ptr();
+    Ok(unsafe { rte_ipv4_udptcp_cksum_(ptr as *const rte_ipv4_hdr, ptr.add(l4) as *const c_void) })
+}
+
+/// Whether the TCP or UDP checksum of the IPv4 packet starting `ip` is
+/// valid.
+pub fn ipv4_udptcp_cksum_verify(ip: &[u8]) -> Result<bool> {
+    let l4 = ipv4_payload(ip)?;
+    let ptr = ip.as_ptr();
+    let ret = unsafe {
+        rte_ipv4_udptcp_cksum_verify_(ptr as *const rte_ipv4_hdr, ptr.add(l4) as *const c_void)
+    };
+    Ok(ret == 0)
+}
+
+/// The checksum of the IPv6 pseudo header, to put in the L4 checksum field
+/// for checksum offload.
+///
+/// With `RTE_MBUF_F_TX_TCP_SEG` or `RTE_MBUF_F_TX_UDP_SEG` in `ol_flags`, the
+/// L4 length is left out as required for segmentation offload.
+pub fn ipv6_phdr_cksum(ip: &rte_ipv6_hdr, ol_flags: u64) -> u16 {
+    unsafe { rte_ipv6_phdr_cksum_(ip, ol_flags) }
+}
+
+/// Check that `ip` holds an IPv6 header and its whole payload.
+fn ipv6_payload(ip: &[u8]) -> Result<usize> {
+    let hdr_len = mem::size_of::<rte_ipv6_hdr>();
+    if ip.len() < hdr_len {
+        return Err(Error::new(EINVAL));
+    }
+    let payload_len = u16::from_be_bytes([ip[4], ip[5]]) as usize;
+    if ip.len() < hdr_len + payload_len {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(hdr_len)
+}
+
+/// The TCP or UDP checksum of the IPv6 packet starting `ip`, whose L4
+/// checksum field must be zero.
+///
+/// As with `rte_ipv6_udptcp_cksum`, the L4 header must directly follow the
+/// IPv6 header, without extension headers.
+pub fn ipv6_udptcp_cksum(ip: &[u8]) -> Result<u16> {
+    let l4 = ipv6_payload(ip)?;
+    let ptr = ip.as_ptr();
+    Ok(unsafe { rte_ipv6_udptcp_cksum_(ptr as *const rte_ipv6_hdr, ptr.add(l4) as *const c_void) })
+}
+
+/// Whether the TCP or UDP checksum of the IPv6 packet starting `ip` is
+/// valid.
+pub fn ipv6_udptcp_cksum_verify(ip: &[u8]) -> Result<bool> {
+    let l4 = ipv6_payload(ip)?;
+    let ptr = ip.as_ptr();
+    let ret = unsafe {
+        rte_ipv6_udptcp_cksum_verify_(ptr as *const rte_ipv6_hdr, ptr.add(l4) as *const c_void)
+    };
+    Ok(ret == 0)
+}
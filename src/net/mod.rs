@@ -0,0 +1,19 @@
+mod cksum;
+mod offload;
+mod parse;
+mod ptype;
+
+pub use cksum::*;
+pub use offload::*;
+pub use parse::*;
+pub use ptype::*;
+
+// Protocol numbers from `netinet/in.h`, which is not part of the bindings.
+pub const IPPROTO_HOPOPTS: u8 = 0;
+pub const IPPROTO_IPIP: u8 = 4;
+pub const IPPROTO_TCP: u8 = 6;
+pub const IPPROTO_UDP: u8 = 17;
+pub const IPPROTO_IPV6: u8 = 41;
+pub const IPPROTO_ROUTING: u8 = 43;
+pub const IPPROTO_FRAGMENT: u8 = 44;
+pub const IPPROTO_DSTOPTS: u8 = 60;
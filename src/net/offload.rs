@@ -0,0 +1,201 @@
+use super::*;
+use crate::error::{check, Error, Result, EINVAL};
+use crate::ethdev::Port;
+use crate::mbuf::Mbuf;
+use crate::*;
+
+/// The checksum and segmentation offloads requested for a packet.
+///
+/// With a tunnel, the inner offloads apply to the inner headers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxOffload {
+    /// Compute the IPv4 header checksum. IPv6 has none, setting it for an
+    /// IPv6 packet is an error.
+    pub ip_cksum: bool,
+    /// Compute the TCP or UDP checksum.
+    pub l4_cksum: bool,
+    /// Segment the TCP or UDP payload in segments of that size, which implies
+    /// both checksums.
+    pub tso_segsz: Option<u16>,
+    /// Compute the outer IPv4 header checksum of tunneled packets, which
+    /// must then have an outer IPv4 header.
+    pub outer_ip_cksum: bool,
+    /// Compute the outer UDP checksum of tunneled packets.
+    pub outer_udp_cksum: bool,
+}
+
+impl Mbuf {
+    pub fn ol_flags(&self) -> u64 {
+        self.as_raw().ol_flags
+    }
+
+    /// Parse the headers of the packet and set its header lengths,
+    /// `tso_segsz` and TX offload flags for `offload`, replacing the TX
+    /// offload flags already set.
+    ///
+    /// Fails with `EINVAL` if the packet is not IP, an IPv4 checksum is
+    /// requested for an IPv6 header, or the requested L4 offloads are not for
+    /// TCP or UDP.
+    pub fn set_tx_offload(&mut self, offload: TxOffload) -> Result<HeaderLens> {
+        let headers = self.headers()?;
+        let lens = headers.lens();
+        let layers = headers.inner.as_ref().unwrap_or(&headers.outer);
+
+        let mut flags = match layers.l3 {
+            Some(L3::Ipv4(_)) if offload.ip_cksum || offload.tso_segsz.is_some() => {
+                RTE_MBUF_F_TX_IPV4 | RTE_MBUF_F_TX_IP_CKSUM
+            }
+            Some(L3::Ipv4(_)) => RTE_MBUF_F_TX_IPV4,
+            Some(L3::Ipv6(_)) if !offload.ip_cksum => RTE_MBUF_F_TX_IPV6,
+            _ => return Err(Error::new(EINVAL)),
+        };
+        if offload.l4_cksum || offload.tso_segsz.is_some() {
+            flags |= match layers.l4 {
+                Some(L4::Tcp(_)) => RTE_MBUF_F_TX_TCP_CKSUM,
+                Some(L4::Udp(_)) => RTE_MBUF_F_TX_UDP_CKSUM,
+                None => return Err(Error::new(EINVAL)),
+            };
+        }
+        if offload.tso_segsz.is_some() {
+            flags |= match layers.l4 {
+                Some(L4::Tcp(_)) => RTE_MBUF_F_TX_TCP_SEG,
+                _ => RTE_MBUF_F_TX_UDP_SEG,
+            };
+        }
+
+        if let Some(tunnel) = headers.tunnel {
+            flags |= match tunnel {
+                Tunnel::Vxlan(_) => RTE_MBUF_F_TX_TUNNEL_VXLAN,
+                Tunnel::Gtpu(_) => RTE_MBUF_F_TX_TUNNEL_GTP,
+                Tunnel::Ip => RTE_MBUF_F_TX_TUNNEL_IPIP,
+            };
+            flags |= match headers.outer.l3 {
+                Some(L3::Ipv4(_)) if offload.outer_ip_cksum => {
+                    RTE_MBUF_F_TX_OUTER_IPV4 | RTE_MBUF_F_TX_OUTER_IP_CKSUM
+                }
+                Some(L3::Ipv4(_)) => RTE_MBUF_F_TX_OUTER_IPV4,
+                _ if offload.outer_ip_cksum => return Err(Error::new(EINVAL)),
+                _ => RTE_MBUF_F_TX_OUTER_IPV6,
+            };
+            if offload.outer_udp_cksum {
+                match headers.outer.l4 {
+                    Some(L4::Udp(_)) => flags |= RTE_MBUF_F_TX_OUTER_UDP_CKSUM,
+                    _ => return Err(Error::new(EINVAL)),
+                }
+            }
+        }
+
+        self.set_header_lens(lens);
+        let m = self.as_raw_mut();
+        unsafe {
+            m.__bindgen_anon_3
+                .__bindgen_anon_1
+                .set_tso_segsz(offload.tso_segsz.unwrap_or(0) as u64);
+        }
+        m.ol_flags = m.ol_flags & !RTE_MBUF_F_TX_OFFLOAD_MASK | flags;
+        Ok(lens)
+    }
+
+    /// Write the pseudo header checksums and clear the IPv4 header checksums
+    /// the offloads set in the mbuf expect, as `rte_net_intel_cksum_prepare`.
+    ///
+    /// This is what the `tx_prepare` of most drivers does, see
+    /// [`Port::tx_prepare`].
+    pub fn prepare_cksum(&mut self) -> Result<()> {
+        check(unsafe { rte_net_intel_cksum_prepare_(self.as_mut_ptr()) }).map(|_| ())
+    }
+
+    /// Compute the IPv4 header, TCP and UDP checksums of the packet in
+    /// software, inner headers first for tunneled packets.
+    ///
+    /// The headers and payload must all be in the first segment, and IPv6
+    /// TCP and UDP headers may not follow extension headers.
+    pub fn fill_cksums(&mut self) -> Result<()> {
+        let headers = self.headers()?;
+        let outer = CksumLayers::new(&headers.outer, 0);
+        let inner = headers.inner.as_ref().map(|inner| {
+            let lens = headers.lens();
+            let l2 = lens.outer_l2_len + lens.outer_l3_len + lens.l2_len - inner.l2_len;
+            CksumLayers::new(inner, l2 as usize)
+        });
+
+        let data = self.data_mut();
+        if let Some(inner) = inner {
+            inner.fill(data)?;
+        }
+        outer.fill(data)
+    }
+}
+
+/// Where the checksums of one level of headers are.
+struct CksumLayers {
+    l3: Option<(usize, bool)>,
+    // The offset of the checksum field from the L3 header.
+    l4_cksum: Option<usize>,
+    l3_len: usize,
+}
+
+impl CksumLayers {
+    fn new(layers: &Layers<'_>, offset: usize) -> Self {
+        let l3 = offset + layers.l2_len as usize;
+        let l4 = layers.l3_len as usize;
+        Self {
+            l3: layers.l3.map(|l3_hdr| (l3, matches!(l3_hdr, L3::Ipv4(_)))),
+            l4_cksum: layers.l4.map(|l4_hdr| match l4_hdr {
+                L4::Tcp(_) => l4 + 16,
+                L4::Udp(_) => l4 + 6,
+            }),
+            l3_len: l4,
+        }
+    }
+
+    fn fill(&self, data: &mut [u8]) -> Result<()> {
+        let Some((l3, ipv4)) = self.l3 else {
+            return Ok(());
+        };
+        let ip = &mut data[l3..];
+        if ipv4 {
+            ip[10..12].fill(0);
+            let cksum = ipv4_cksum(ip)?;
+            ip[10..12].copy_from_slice(&cksum.to_ne_bytes());
+        }
+        if let Some(at) = self.l4_cksum {
+            if !ipv4 && self.l3_len != std::mem::size_of::<rte_ipv6_hdr>() {
+                return Err(Error::new(EINVAL));
+            }
+            ip[at..at + 2].fill(0);
+            let cksum = if ipv4 {
+                ipv4_udptcp_cksum(ip)?
+            } else {
+                ipv6_udptcp_cksum(ip)?
+            };
+            ip[at..at + 2].copy_from_slice(&cksum.to_ne_bytes());
+        }
+        Ok(())
+    }
+}
+
+impl Port {
+    /// Check and prepare `mbufs` for transmission on `queue_id` with the
+    /// offloads they request, as `rte_eth_tx_prepare`.
+    ///
+    /// Returns the number of packets prepared, and why the next one could
+    /// not be if not all were. At most `u16::MAX` packets are prepared per
+    /// call.
+    pub fn tx_prepare(&self, queue_id: u16, mbufs: &mut [Mbuf]) -> (usize, Result<()>) {
+        let len = mbufs.len().min(u16::MAX as usize);
+        let n = unsafe {
+            rte_eth_tx_prepare_(
+                self.id(),
+                queue_id,
+                mbufs.as_mut_ptr() as *mut *mut rte_mbuf,
+                len as u16,
+            )
+        } as usize;
+        if n < len {
+            (n, Err(Error::last()))
+        } else {
+            (n, Ok(()))
+        }
+    }
+}
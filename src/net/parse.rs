@@ -0,0 +1,380 @@
+use std::mem;
+
+use super::*;
+use crate::error::{Error, Result, EINVAL};
+use crate::mbuf::Mbuf;
+use crate::*;
+
+/// The header structs a packet can be viewed as in place.
+///
+/// # Safety
+///
+/// Any bytes must be a valid value of the type.
+unsafe trait Header {}
+
+unsafe impl Header for rte_ether_hdr {}
+unsafe impl Header for rte_vlan_hdr {}
+unsafe impl Header for rte_ipv4_hdr {}
+unsafe impl Header for rte_ipv6_hdr {}
+unsafe impl Header for rte_tcp_hdr {}
+unsafe impl Header for rte_udp_hdr {}
+unsafe impl Header for rte_vxlan_hdr {}
+unsafe impl Header for rte_gtp_hdr {}
+
+fn header<T: Header>(data: &[u8], offset: usize) -> Result<&T> {
+    let bytes = data
+        .get(offset..offset + mem::size_of::<T>())
+        .ok_or(Error::new(EINVAL))?;
+    if bytes.as_ptr().align_offset(mem::align_of::<T>()) != 0 {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(unsafe { &*(bytes.as_ptr() as *const T) })
+}
+
+/// A network header.
+#[derive(Clone, Copy)]
+pub enum L3<'a> {
+    Ipv4(&'a rte_ipv4_hdr),
+    Ipv6(&'a rte_ipv6_hdr),
+}
+
+/// A transport header.
+#[derive(Clone, Copy)]
+pub enum L4<'a> {
+    Tcp(&'a rte_tcp_hdr),
+    Udp(&'a rte_udp_hdr),
+}
+
+impl L4<'_> {
+    pub fn src_port(&self) -> u16 {
+        match self {
+            L4::Tcp(tcp) => u16::from_be(tcp.src_port),
+            L4::Udp(udp) => u16::from_be(udp.src_port),
+        }
+    }
+
+    pub fn dst_port(&self) -> u16 {
+        match self {
+            L4::Tcp(tcp) => u16::from_be(tcp.dst_port),
+            L4::Udp(udp) => u16::from_be(udp.dst_port),
+        }
+    }
+}
+
+/// The encapsulation between the outer and the inner headers.
+#[derive(Clone, Copy)]
+pub enum Tunnel<'a> {
+    /// VXLAN over UDP port `RTE_VXLAN_DEFAULT_PORT`, carrying Ethernet.
+    Vxlan(&'a rte_vxlan_hdr),
+    /// GTP-U over UDP port `RTE_GTPU_UDP_PORT`, carrying IP.
+    Gtpu(&'a rte_gtp_hdr),
+    /// IPv4 or IPv6 directly in IP.
+    Ip,
+}
+
+/// The headers of one level of encapsulation.
+#[derive(Clone, Copy, Default)]
+pub struct Layers<'a> {
+    /// `None` when the layers start at L3, inside GTP-U or IP tunnels.
+    pub ether: Option<&'a rte_ether_hdr>,
+    /// The VLAN tags, outermost first, up to two for QinQ.
+    pub vlans: [Option<&'a rte_vlan_hdr>; 2],
+    pub l3: Option<L3<'a>>,
+    /// The protocol after the L3 header and its IPv6 extension headers.
+    pub proto: Option<u8>,
+    /// `None` for fragments and protocols other than TCP and UDP.
+    pub l4: Option<L4<'a>>,
+    pub l2_len: u16,
+    /// IPv4 options and IPv6 extension headers included.
+    pub l3_len: u16,
+    pub l4_len: u16,
+}
+
+impl Layers<'_> {
+    /// The ether type of the L3 header, after the VLAN tags.
+    pub fn ether_type(&self) -> Option<u16> {
+        match self.vlans {
+            [_, Some(vlan)] | [Some(vlan), None] => Some(u16::from_be(vlan.eth_proto)),
+            [None, None] => self.ether.map(|ether| u16::from_be(ether.ether_type)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        (self.l2_len + self.l3_len + self.l4_len) as usize
+    }
+}
+
+/// The headers of a packet, viewed in place from its first byte.
+///
+/// Parsing stops at the first header it does not know, leaving the next
+/// layers empty, and fails with `EINVAL` on truncated or malformed headers.
+/// Headers must be suitably aligned, which is the case for mbuf data.
+#[derive(Clone, Copy)]
+pub struct Headers<'a> {
+    pub outer: Layers<'a>,
+    pub tunnel: Option<Tunnel<'a>>,
+    /// The encapsulated headers, when there is a tunnel.
+    pub inner: Option<Layers<'a>>,
+    tunnel_len: u16,
+}
+
+impl<'a> Headers<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        let outer = parse_l2(data, 0)?;
+        let mut headers = Headers {
+            outer,
+            tunnel: None,
+            inner: None,
+            tunnel_len: 0,
+        };
+
+        let offset = outer.len();
+        match (outer.proto, outer.l4) {
+            (Some(IPPROTO_IPIP), _) | (Some(IPPROTO_IPV6), _) => {
+                headers.tunnel = Some(Tunnel::Ip);
+                headers.inner = Some(parse_ip(data, offset)?);
+            }
+            (_, Some(L4::Udp(udp)))
+                if u16::from_be(udp.dst_port) == RTE_VXLAN_DEFAULT_PORT as u16 =>
+            {
+                let vxlan = header::<rte_vxlan_hdr>(data, offset)?;
+                headers.tunnel = Some(Tunnel::Vxlan(vxlan));
+                headers.tunnel_len = mem::size_of::<rte_vxlan_hdr>() as u16;
+                headers.inner = Some(parse_l2(data, offset + headers.tunnel_len as usize)?);
+            }
+            (_, Some(L4::Udp(udp))) if u16::from_be(udp.dst_port) == RTE_GTPU_UDP_PORT as u16 => {
+                let gtp = header::<rte_gtp_hdr>(data, offset)?;
+                // Only G-PDUs carry user packets, signalling messages are left
+                // as UDP payload.
+                if gtp.msg_type == GTP_MSG_TYPE_GPDU {
+                    headers.tunnel = Some(Tunnel::Gtpu(gtp));
+                    headers.tunnel_len = gtp_len(gtp, data, offset)?;
+                    headers.inner = Some(parse_ip(data, offset + headers.tunnel_len as usize)?);
+                }
+            }
+            _ => {}
+        }
+        Ok(headers)
+    }
+
+    /// The header lengths in the mbuf convention: with a tunnel, the outer
+    /// L2 and L3 lengths are the `outer_` ones and the inner L2 length
+    /// includes the outer L4 and tunnel headers.
+    pub fn lens(&self) -> HeaderLens {
+        match &self.inner {
+            None => HeaderLens {
+                l2_len: self.outer.l2_len,
+                l3_len: self.outer.l3_len,
+                l4_len: self.outer.l4_len,
+                ..Default::default()
+            },
+            Some(inner) => HeaderLens {
+                outer_l2_len: self.outer.l2_len,
+                outer_l3_len: self.outer.l3_len,
+                l2_len: self.outer.l4_len + self.tunnel_len + inner.l2_len,
+                l3_len: inner.l3_len,
+                l4_len: inner.l4_len,
+            },
+        }
+    }
+}
+
+/// `G-PDU`, the GTP message type of user packets.
+const GTP_MSG_TYPE_GPDU: u8 = 0xff;
+// The E, S and PN flags, any of which adds the optional 4 byte fields.
+const GTP_OPT_FLAGS: u8 = 0x07;
+const GTP_E_FLAG: u8 = 0x04;
+
+/// The length of a GTP-U header with its optional fields and extension
+/// headers.
+fn gtp_len(gtp: &rte_gtp_hdr, data: &[u8], offset: usize) -> Result<u16> {
+    let mut len = mem::size_of::<rte_gtp_hdr>();
+    let flags = unsafe { gtp.__bindgen_anon_1.gtp_hdr_info };
+    if flags & GTP_OPT_FLAGS == 0 {
+        return Ok(len as u16);
+    }
+    len += 4;
+    let byte = |at: usize| data.get(offset + at).copied().ok_or(Error::new(EINVAL));
+    if flags & GTP_E_FLAG != 0 {
+        // Each extension header starts with its length in 4 byte units and
+        // ends with the type of the next one.
+        let mut next = byte(len - 1)?;
+        while next != 0 {
+            let ext_len = byte(len)? as usize * 4;
+            if ext_len == 0 {
+                return Err(Error::new(EINVAL));
+            }
+            len += ext_len;
+            next = byte(len - 1)?;
+        }
+    }
+    Ok(len as u16)
+}
+
+fn parse_l2(data: &[u8], offset: usize) -> Result<Layers<'_>> {
+    let ether = header::<rte_ether_hdr>(data, offset)?;
+    let mut layers = Layers {
+        ether: Some(ether),
+        l2_len: mem::size_of::<rte_ether_hdr>() as u16,
+        ..Default::default()
+    };
+    let mut ether_type = u16::from_be(ether.ether_type);
+    for slot in layers.vlans.iter_mut() {
+        if !is_vlan(ether_type) {
+            break;
+        }
+        let vlan = header::<rte_vlan_hdr>(data, offset + layers.l2_len as usize)?;
+        *slot = Some(vlan);
+        layers.l2_len += mem::size_of::<rte_vlan_hdr>() as u16;
+        ether_type = u16::from_be(vlan.eth_proto);
+    }
+
+    let l3_offset = offset + layers.l2_len as usize;
+    match ether_type as u32 {
+        RTE_ETHER_TYPE_IPV4 => parse_ipv4(data, l3_offset, &mut layers)?,
+        RTE_ETHER_TYPE_IPV6 => parse_ipv6(data, l3_offset, &mut layers)?,
+        _ => {}
+    }
+    Ok(layers)
+}
+
+fn is_vlan(ether_type: u16) -> bool {
+    matches!(
+        ether_type as u32,
+        RTE_ETHER_TYPE_VLAN | RTE_ETHER_TYPE_QINQ | RTE_ETHER_TYPE_QINQ1 | RTE_ETHER_TYPE_QINQ2
+    )
+}
+
+/// Parse IP headers without L2, going by the version.
+fn parse_ip(data: &[u8], offset: usize) -> Result<Layers<'_>> {
+    let mut layers = Layers::default();
+    match data.get(offset).ok_or(Error::new(EINVAL))? >> 4 {
+        4 => parse_ipv4(data, offset, &mut layers)?,
+        6 => parse_ipv6(data, offset, &mut layers)?,
+        _ => return Err(Error::new(EINVAL)),
+    }
+    Ok(layers)
+}
+
+fn parse_ipv4<'a>(data: &'a [u8], offset: usize, layers: &mut Layers<'a>) -> Result<()> {
+    let ip = header::<rte_ipv4_hdr>(data, offset)?;
+    let version_ihl = unsafe { ip.__bindgen_anon_1.version_ihl };
+    let len = (version_ihl as u32 & RTE_IPV4_HDR_IHL_MASK) * RTE_IPV4_IHL_MULTIPLIER;
+    if version_ihl >> 4 != 4
+        || len < mem::size_of::<rte_ipv4_hdr>() as u32
+        || data.len() < offset + len as usize
+    {
+        return Err(Error::new(EINVAL));
+    }
+    layers.l3 = Some(L3::Ipv4(ip));
+    layers.l3_len = len as u16;
+    layers.proto = Some(ip.next_proto_id);
+
+    let frag = u16::from_be(ip.fragment_offset) as u32;
+    if frag & (RTE_IPV4_HDR_MF_FLAG | RTE_IPV4_HDR_OFFSET_MASK) == 0 {
+        parse_l4(data, offset + len as usize, layers)?;
+    }
+    Ok(())
+}
+
+fn parse_ipv6<'a>(data: &'a [u8], offset: usize, layers: &mut Layers<'a>) -> Result<()> {
+    let ip = header::<rte_ipv6_hdr>(data, offset)?;
+    if u32::from_be(ip.vtc_flow) >> 28 != 6 {
+        return Err(Error::new(EINVAL));
+    }
+    layers.l3 = Some(L3::Ipv6(ip));
+    let mut len = mem::size_of::<rte_ipv6_hdr>();
+    let mut proto = ip.proto;
+    loop {
+        match proto {
+            IPPROTO_HOPOPTS | IPPROTO_ROUTING | IPPROTO_DSTOPTS => {
+                // Next header, then the length in 8 byte units beyond the
+                // first 8 bytes.
+                let ext = data
+                    .get(offset + len..offset + len + 2)
+                    .ok_or(Error::new(EINVAL))?;
+                proto = ext[0];
+                len += (ext[1] as usize + 1) * 8;
+            }
+            IPPROTO_FRAGMENT => {
+                let ext = data
+                    .get(offset + len..offset + len + 8)
+                    .ok_or(Error::new(EINVAL))?;
+                layers.proto = Some(ext[0]);
+                layers.l3_len = len as u16 + 8;
+                return Ok(());
+            }
+            _ => break,
+        }
+    }
+    if data.len() < offset + len {
+        return Err(Error::new(EINVAL));
+    }
+    layers.l3_len = len as u16;
+    layers.proto = Some(proto);
+    parse_l4(data, offset + len, layers)
+}
+
+fn parse_l4<'a>(data: &'a [u8], offset: usize, layers: &mut Layers<'a>) -> Result<()> {
+    match layers.proto {
+        Some(IPPROTO_TCP) => {
+            let tcp = header::<rte_tcp_hdr>(data, offset)?;
+            let len = (tcp.data_off >> 4) as u16 * 4;
+            if len < mem::size_of::<rte_tcp_hdr>() as u16 || data.len() < offset + len as usize {
+                return Err(Error::new(EINVAL));
+            }
+            layers.l4 = Some(L4::Tcp(tcp));
+            layers.l4_len = len;
+        }
+        Some(IPPROTO_UDP) => {
+            layers.l4 = Some(L4::Udp(header::<rte_udp_hdr>(data, offset)?));
+            layers.l4_len = mem::size_of::<rte_udp_hdr>() as u16;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The header lengths stored in an mbuf for offloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeaderLens {
+    pub outer_l2_len: u16,
+    pub outer_l3_len: u16,
+    pub l2_len: u16,
+    pub l3_len: u16,
+    pub l4_len: u16,
+}
+
+impl Mbuf {
+    /// The headers of the packet, which must all be in the first segment.
+    pub fn headers(&self) -> Result<Headers<'_>> {
+        Headers::parse(self.data())
+    }
+
+    pub fn header_lens(&self) -> HeaderLens {
+        let lens = unsafe { self.as_raw().__bindgen_anon_3.__bindgen_anon_1 };
+        HeaderLens {
+            outer_l2_len: lens.outer_l2_len() as u16,
+            outer_l3_len: lens.outer_l3_len() as u16,
+            l2_len: lens.l2_len() as u16,
+            l3_len: lens.l3_len() as u16,
+            l4_len: lens.l4_len() as u16,
+        }
+    }
+
+    pub fn set_header_lens(&mut self, lens: HeaderLens) {
+        let raw = unsafe { &mut self.as_raw_mut().__bindgen_anon_3.__bindgen_anon_1 };
+        raw.set_outer_l2_len(lens.outer_l2_len as u64);
+        raw.set_outer_l3_len(lens.outer_l3_len as u64);
+        raw.set_l2_len(lens.l2_len as u64);
+        raw.set_l3_len(lens.l3_len as u64);
+        raw.set_l4_len(lens.l4_len as u64);
+    }
+
+    /// Parse the headers of the packet and store their lengths in the mbuf.
+    pub fn parse_header_lens(&mut self) -> Result<HeaderLens> {
+        let lens = self.headers()?.lens();
+        self.set_header_lens(lens);
+        Ok(lens)
+    }
+}
@@ -0,0 +1,422 @@
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::error::{check, Result};
+use crate::ethdev::Port;
+use crate::mbuf::Mbuf;
+use crate::*;
+
+/// The L2 type of a packet, from `RTE_PTYPE_L2_*` or `RTE_PTYPE_INNER_L2_*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum L2Type {
+    Unknown,
+    Ether,
+    /// IEEE 1588 timesync, outer only.
+    Timesync,
+    /// Outer only.
+    Arp,
+    /// Outer only.
+    Lldp,
+    /// Outer only.
+    Nsh,
+    Vlan,
+    Qinq,
+    /// Outer only.
+    Pppoe,
+    /// Outer only.
+    Fcoe,
+    /// Outer only.
+    Mpls,
+}
+
+impl L2Type {
+    fn from_bits(bits: u32) -> Self {
+        match bits {
+            RTE_PTYPE_L2_ETHER => L2Type::Ether,
+            RTE_PTYPE_L2_ETHER_TIMESYNC => L2Type::Timesync,
+            RTE_PTYPE_L2_ETHER_ARP => L2Type::Arp,
+            RTE_PTYPE_L2_ETHER_LLDP => L2Type::Lldp,
+            RTE_PTYPE_L2_ETHER_NSH => L2Type::Nsh,
+            RTE_PTYPE_L2_ETHER_VLAN => L2Type::Vlan,
+            RTE_PTYPE_L2_ETHER_QINQ => L2Type::Qinq,
+            RTE_PTYPE_L2_ETHER_PPPOE => L2Type::Pppoe,
+            RTE_PTYPE_L2_ETHER_FCOE => L2Type::Fcoe,
+            RTE_PTYPE_L2_ETHER_MPLS => L2Type::Mpls,
+            _ => L2Type::Unknown,
+        }
+    }
+
+    fn bits(&self) -> u32 {
+        match self {
+            L2Type::Unknown => RTE_PTYPE_UNKNOWN,
+            L2Type::Ether => RTE_PTYPE_L2_ETHER,
+            L2Type::Timesync => RTE_PTYPE_L2_ETHER_TIMESYNC,
+            L2Type::Arp => RTE_PTYPE_L2_ETHER_ARP,
+            L2Type::Lldp => RTE_PTYPE_L2_ETHER_LLDP,
+            L2Type::Nsh => RTE_PTYPE_L2_ETHER_NSH,
+            L2Type::Vlan => RTE_PTYPE_L2_ETHER_VLAN,
+            L2Type::Qinq => RTE_PTYPE_L2_ETHER_QINQ,
+            L2Type::Pppoe => RTE_PTYPE_L2_ETHER_PPPOE,
+            L2Type::Fcoe => RTE_PTYPE_L2_ETHER_FCOE,
+            L2Type::Mpls => RTE_PTYPE_L2_ETHER_MPLS,
+        }
+    }
+
+    fn from_inner_bits(bits: u32) -> Self {
+        match bits {
+            RTE_PTYPE_INNER_L2_ETHER => L2Type::Ether,
+            RTE_PTYPE_INNER_L2_ETHER_VLAN => L2Type::Vlan,
+            RTE_PTYPE_INNER_L2_ETHER_QINQ => L2Type::Qinq,
+            _ => L2Type::Unknown,
+        }
+    }
+
+    fn inner_bits(&self) -> u32 {
+        match self {
+            L2Type::Ether => RTE_PTYPE_INNER_L2_ETHER,
+            L2Type::Vlan => RTE_PTYPE_INNER_L2_ETHER_VLAN,
+            L2Type::Qinq => RTE_PTYPE_INNER_L2_ETHER_QINQ,
+            _ => RTE_PTYPE_UNKNOWN,
+        }
+    }
+}
+
+/// The L3 type of a packet, from `RTE_PTYPE_L3_*` or `RTE_PTYPE_INNER_L3_*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum L3Type {
+    Unknown,
+    /// IPv4 without options.
+    Ipv4,
+    /// IPv4 with options.
+    Ipv4Ext,
+    /// IPv4, with or without options.
+    Ipv4ExtUnknown,
+    /// IPv6 without extension headers.
+    Ipv6,
+    /// IPv6 with extension headers.
+    Ipv6Ext,
+    /// IPv6, with or without extension headers.
+    Ipv6ExtUnknown,
+}
+
+impl L3Type {
+    pub fn is_ipv4(&self) -> bool {
+        matches!(
+            self,
+            L3Type::Ipv4 | L3Type::Ipv4Ext | L3Type::Ipv4ExtUnknown
+        )
+    }
+
+    pub fn is_ipv6(&self) -> bool {
+        matches!(
+            self,
+            L3Type::Ipv6 | L3Type::Ipv6Ext | L3Type::Ipv6ExtUnknown
+        )
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        match bits {
+            RTE_PTYPE_L3_IPV4 => L3Type::Ipv4,
+            RTE_PTYPE_L3_IPV4_EXT => L3Type::Ipv4Ext,
+            RTE_PTYPE_L3_IPV4_EXT_UNKNOWN => L3Type::Ipv4ExtUnknown,
+            RTE_PTYPE_L3_IPV6 => L3Type::Ipv6,
+            RTE_PTYPE_L3_IPV6_EXT => L3Type::Ipv6Ext,
+            RTE_PTYPE_L3_IPV6_EXT_UNKNOWN => L3Type::Ipv6ExtUnknown,
+            _ => L3Type::Unknown,
+        }
+    }
+
+    fn bits(&self) -> u32 {
+        match self {
+            L3Type::Unknown => RTE_PTYPE_UNKNOWN,
+            L3Type::Ipv4 => RTE_PTYPE_L3_IPV4,
+            L3Type::Ipv4Ext => RTE_PTYPE_L3_IPV4_EXT,
+            L3Type::Ipv4ExtUnknown => RTE_PTYPE_L3_IPV4_EXT_UNKNOWN,
+            L3Type::Ipv6 => RTE_PTYPE_L3_IPV6,
+            L3Type::Ipv6Ext => RTE_PTYPE_L3_IPV6_EXT,
+            L3Type::Ipv6ExtUnknown => RTE_PTYPE_L3_IPV6_EXT_UNKNOWN,
+        }
+    }
+
+    fn from_inner_bits(bits: u32) -> Self {
+        match bits {
+            RTE_PTYPE_INNER_L3_IPV4 => L3Type::Ipv4,
+            RTE_PTYPE_INNER_L3_IPV4_EXT => L3Type::Ipv4Ext,
+            RTE_PTYPE_INNER_L3_IPV4_EXT_UNKNOWN => L3Type::Ipv4ExtUnknown,
+            RTE_PTYPE_INNER_L3_IPV6 => L3Type::Ipv6,
+            RTE_PTYPE_INNER_L3_IPV6_EXT => L3Type::Ipv6Ext,
+            RTE_PTYPE_INNER_L3_IPV6_EXT_UNKNOWN => L3Type::Ipv6ExtUnknown,
+            _ => L3Type::Unknown,
+        }
+    }
+
+    fn inner_bits(&self) -> u32 {
+        match self {
+            L3Type::Unknown => RTE_PTYPE_UNKNOWN,
+            L3Type::Ipv4 => RTE_PTYPE_INNER_L3_IPV4,
+            L3Type::Ipv4Ext => RTE_PTYPE_INNER_L3_IPV4_EXT,
+            L3Type::Ipv4ExtUnknown => RTE_PTYPE_INNER_L3_IPV4_EXT_UNKNOWN,
+            L3Type::Ipv6 => RTE_PTYPE_INNER_L3_IPV6,
+            L3Type::Ipv6Ext => RTE_PTYPE_INNER_L3_IPV6_EXT,
+            L3Type::Ipv6ExtUnknown => RTE_PTYPE_INNER_L3_IPV6_EXT_UNKNOWN,
+        }
+    }
+}
+
+/// The L4 type of a packet, from `RTE_PTYPE_L4_*` or `RTE_PTYPE_INNER_L4_*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum L4Type {
+    Unknown,
+    Tcp,
+    Udp,
+    /// An IP fragment, whose L4 header may not be there.
+    Frag,
+    Sctp,
+    Icmp,
+    /// Not a fragment, and none of the other L4 types.
+    NonFrag,
+    /// Outer only.
+    Igmp,
+}
+
+impl L4Type {
+    // The inner L4 types are the outer ones shifted.
+    const INNER_SHIFT: u32 =
+        RTE_PTYPE_INNER_L4_MASK.trailing_zeros() - RTE_PTYPE_L4_MASK.trailing_zeros();
+
+    fn from_bits(bits: u32) -> Self {
+        match bits {
+            RTE_PTYPE_L4_TCP => L4Type::Tcp,
+            RTE_PTYPE_L4_UDP => L4Type::Udp,
+            RTE_PTYPE_L4_FRAG => L4Type::Frag,
+            RTE_PTYPE_L4_SCTP => L4Type::Sctp,
+            RTE_PTYPE_L4_ICMP => L4Type::Icmp,
+            RTE_PTYPE_L4_NONFRAG => L4Type::NonFrag,
+            RTE_PTYPE_L4_IGMP => L4Type::Igmp,
+            _ => L4Type::Unknown,
+        }
+    }
+
+    fn bits(&self) -> u32 {
+        match self {
+            L4Type::Unknown => RTE_PTYPE_UNKNOWN,
+            L4Type::Tcp => RTE_PTYPE_L4_TCP,
+            L4Type::Udp => RTE_PTYPE_L4_UDP,
+            L4Type::Frag => RTE_PTYPE_L4_FRAG,
+            L4Type::Sctp => RTE_PTYPE_L4_SCTP,
+            L4Type::Icmp => RTE_PTYPE_L4_ICMP,
+            L4Type::NonFrag => RTE_PTYPE_L4_NONFRAG,
+            L4Type::Igmp => RTE_PTYPE_L4_IGMP,
+        }
+    }
+
+    fn from_inner_bits(bits: u32) -> Self {
+        match Self::from_bits(bits >> Self::INNER_SHIFT) {
+            L4Type::Igmp => L4Type::Unknown,
+            l4 => l4,
+        }
+    }
+
+    fn inner_bits(&self) -> u32 {
+        match self {
+            L4Type::Igmp => RTE_PTYPE_UNKNOWN,
+            l4 => l4.bits() << Self::INNER_SHIFT,
+        }
+    }
+}
+
+/// The tunnel type of a packet, from `RTE_PTYPE_TUNNEL_*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TunnelType {
+    Ip,
+    Gre,
+    Vxlan,
+    Nvgre,
+    Geneve,
+    /// Teredo, VXLAN or GRE encapsulating Ethernet.
+    Grenat,
+    Gtpc,
+    Gtpu,
+    Esp,
+    L2tp,
+    VxlanGpe,
+    MplsInGre,
+    MplsInUdp,
+}
+
+impl TunnelType {
+    fn from_bits(bits: u32) -> Option<Self> {
+        Some(match bits {
+            RTE_PTYPE_TUNNEL_IP => TunnelType::Ip,
+            RTE_PTYPE_TUNNEL_GRE => TunnelType::Gre,
+            RTE_PTYPE_TUNNEL_VXLAN => TunnelType::Vxlan,
+            RTE_PTYPE_TUNNEL_NVGRE => TunnelType::Nvgre,
+            RTE_PTYPE_TUNNEL_GENEVE => TunnelType::Geneve,
+            RTE_PTYPE_TUNNEL_GRENAT => TunnelType::Grenat,
+            RTE_PTYPE_TUNNEL_GTPC => TunnelType::Gtpc,
+            RTE_PTYPE_TUNNEL_GTPU => TunnelType::Gtpu,
+            RTE_PTYPE_TUNNEL_ESP => TunnelType::Esp,
+            RTE_PTYPE_TUNNEL_L2TP => TunnelType::L2tp,
+            RTE_PTYPE_TUNNEL_VXLAN_GPE => TunnelType::VxlanGpe,
+            RTE_PTYPE_TUNNEL_MPLS_IN_GRE => TunnelType::MplsInGre,
+            RTE_PTYPE_TUNNEL_MPLS_IN_UDP => TunnelType::MplsInUdp,
+            _ => return None,
+        })
+    }
+
+    fn bits(&self) -> u32 {
+        match self {
+            TunnelType::Ip => RTE_PTYPE_TUNNEL_IP,
+            TunnelType::Gre => RTE_PTYPE_TUNNEL_GRE,
+            TunnelType::Vxlan => RTE_PTYPE_TUNNEL_VXLAN,
+            TunnelType::Nvgre => RTE_PTYPE_TUNNEL_NVGRE,
+            TunnelType::Geneve => RTE_PTYPE_TUNNEL_GENEVE,
+            TunnelType::Grenat => RTE_PTYPE_TUNNEL_GRENAT,
+            TunnelType::Gtpc => RTE_PTYPE_TUNNEL_GTPC,
+            TunnelType::Gtpu => RTE_PTYPE_TUNNEL_GTPU,
+            TunnelType::Esp => RTE_PTYPE_TUNNEL_ESP,
+            TunnelType::L2tp => RTE_PTYPE_TUNNEL_L2TP,
+            TunnelType::VxlanGpe => RTE_PTYPE_TUNNEL_VXLAN_GPE,
+            TunnelType::MplsInGre => RTE_PTYPE_TUNNEL_MPLS_IN_GRE,
+            TunnelType::MplsInUdp => RTE_PTYPE_TUNNEL_MPLS_IN_UDP,
+        }
+    }
+}
+
+/// A packet type, the `RTE_PTYPE_*` bits of `rte_mbuf::packet_type` decoded
+/// layer by layer so it can be matched with struct patterns, e.g.
+/// `PacketType { l4: L4Type::Tcp, .. }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PacketType {
+    pub l2: L2Type,
+    pub l3: L3Type,
+    pub l4: L4Type,
+    pub tunnel: Option<TunnelType>,
+    pub inner_l2: L2Type,
+    pub inner_l3: L3Type,
+    pub inner_l4: L4Type,
+}
+
+impl PacketType {
+    pub const UNKNOWN: Self = Self {
+        l2: L2Type::Unknown,
+        l3: L3Type::Unknown,
+        l4: L4Type::Unknown,
+        tunnel: None,
+        inner_l2: L2Type::Unknown,
+        inner_l3: L3Type::Unknown,
+        inner_l4: L4Type::Unknown,
+    };
+
+    pub fn from_raw(ptype: u32) -> Self {
+        Self {
+            l2: L2Type::from_bits(ptype & RTE_PTYPE_L2_MASK),
+            l3: L3Type::from_bits(ptype & RTE_PTYPE_L3_MASK),
+            l4: L4Type::from_bits(ptype & RTE_PTYPE_L4_MASK),
+            tunnel: TunnelType::from_bits(ptype & RTE_PTYPE_TUNNEL_MASK),
+            inner_l2: L2Type::from_inner_bits(ptype & RTE_PTYPE_INNER_L2_MASK),
+            inner_l3: L3Type::from_inner_bits(ptype & RTE_PTYPE_INNER_L3_MASK),
+            inner_l4: L4Type::from_inner_bits(ptype & RTE_PTYPE_INNER_L4_MASK),
+        }
+    }
+
+    pub fn to_raw(&self) -> u32 {
+        self.l2.bits()
+            | self.l3.bits()
+            | self.l4.bits()
+            | self
+                .tunnel
+                .map_or(RTE_PTYPE_UNKNOWN, |tunnel| tunnel.bits())
+            | self.inner_l2.inner_bits()
+            | self.inner_l3.inner_bits()
+            | self.inner_l4.inner_bits()
+    }
+}
+
+impl Default for PacketType {
+    fn default() -> Self {
+        Self::UNKNOWN
+    }
+}
+
+impl From<u32> for PacketType {
+    fn from(ptype: u32) -> Self {
+        Self::from_raw(ptype)
+    }
+}
+
+/// Formats the type as `rte_get_ptype_name`, e.g. `L2_ETHER L3_IPV4 L4_TCP`.
+impl fmt::Display for PacketType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = [0 as c_char; 256];
+        let ret = unsafe { rte_get_ptype_name(self.to_raw(), buf.as_mut_ptr(), buf.len()) };
+        if ret < 0 {
+            return Err(fmt::Error);
+        }
+        let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        f.write_str(&name.to_string_lossy())
+    }
+}
+
+impl Mbuf {
+    /// The packet type set by the driver, or by
+    /// [`fill_packet_type`](Self::fill_packet_type).
+    pub fn packet_type(&self) -> PacketType {
+        PacketType::from_raw(unsafe { self.as_raw().__bindgen_anon_1.packet_type })
+    }
+
+    pub fn set_packet_type(&mut self, ptype: PacketType) {
+        self.as_raw_mut().__bindgen_anon_1.packet_type = ptype.to_raw();
+    }
+
+    /// Parse the packet type in software with `rte_net_get_ptype`, down to
+    /// the layers in `layers`, a mask of `RTE_PTYPE_*_MASK`.
+    pub fn detect_packet_type(&self, layers: u32) -> PacketType {
+        let ptype = unsafe { rte_net_get_ptype(self.as_ptr(), ptr::null_mut(), layers) };
+        PacketType::from_raw(ptype)
+    }
+
+    /// Parse the packet type of all layers in software and store it in the
+    /// mbuf, for packets from ports that do not report it.
+    pub fn fill_packet_type(&mut self) -> PacketType {
+        let ptype = self.detect_packet_type(RTE_PTYPE_ALL_MASK);
+        self.set_packet_type(ptype);
+        ptype
+    }
+}
+
+impl Port {
+    /// The packet types the port reports in `rte_mbuf::packet_type`, among
+    /// the layers in `mask`, a mask of `RTE_PTYPE_*_MASK`.
+    ///
+    /// Each returned type has a single layer set.
+    pub fn supported_ptypes(&self, mask: u32) -> Result<Vec<PacketType>> {
+        let n = check(unsafe {
+            rte_eth_dev_get_supported_ptypes(self.id(), mask, ptr::null_mut(), 0)
+        })?;
+        let mut ptypes = vec![0u32; n as usize];
+        let n = check(unsafe {
+            rte_eth_dev_get_supported_ptypes(self.id(), mask, ptypes.as_mut_ptr(), n)
+        })?;
+        ptypes.truncate(n as usize);
+        Ok(ptypes.into_iter().map(PacketType::from_raw).collect())
+    }
+
+    /// Let the port report only the packet types of the layers in `mask`,
+    /// which can speed up receiving. `RTE_PTYPE_UNKNOWN` disables packet type
+    /// parsing altogether.
+    ///
+    /// Returns the packet types the port reports from now on.
+    pub fn set_ptypes(&self, mask: u32) -> Result<Vec<PacketType>> {
+        let n = self.supported_ptypes(RTE_PTYPE_ALL_MASK)?.len() + 1;
+        let mut ptypes = vec![0u32; n];
+        check(unsafe { rte_eth_dev_set_ptypes(self.id(), mask, ptypes.as_mut_ptr(), n as u32) })?;
+        Ok(ptypes
+            .into_iter()
+            .take_while(|ptype| *ptype != RTE_PTYPE_UNKNOWN)
+            .map(PacketType::from_raw)
+            .collect())
+    }
+}
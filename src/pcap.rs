@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use crate::dev::Devargs;
+use crate::error::{Error, Result, ENODEV};
+use crate::ethdev::Port;
+
+/// A `net_pcap` virtual device, replaying the packets of pcap files or
+/// network interfaces and capturing the packets sent to it.
+///
+/// Every `rx_*` argument adds a receive queue and every `tx_*` argument a
+/// transmit queue, in order. Paths and interface names may not contain
+/// commas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcapVdev {
+    devargs: Devargs,
+}
+
+impl PcapVdev {
+    /// A device named `net_pcap<index>`.
+    pub fn new(index: u32) -> Self {
+        Self {
+            devargs: Devargs::vdev(format!("net_pcap{}", index)),
+        }
+    }
+
+    fn arg<V: ToString>(self, key: &str, value: V) -> Self {
+        Self {
+            devargs: self.devargs.arg(key, value),
+        }
+    }
+
+    /// Receive the packets of the pcap file at `path`.
+    pub fn rx_pcap<P: AsRef<Path>>(self, path: P) -> Self {
+        self.arg("rx_pcap", path.as_ref().display())
+    }
+
+    /// Write the packets sent to the pcap file at `path`, which is created
+    /// or truncated.
+    pub fn tx_pcap<P: AsRef<Path>>(self, path: P) -> Self {
+        self.arg("tx_pcap", path.as_ref().display())
+    }
+
+    /// Receive the packets of the network interface `iface`.
+    pub fn rx_iface(self, iface: &str) -> Self {
+        self.arg("rx_iface", iface)
+    }
+
+    /// Send to the network interface `iface`.
+    pub fn tx_iface(self, iface: &str) -> Self {
+        self.arg("tx_iface", iface)
+    }
+
+    /// Replay the `rx_pcap` files in a loop instead of once. The packets are
+    /// loaded in memory when the port starts, so the device must have a
+    /// single receive queue.
+    pub fn infinite_rx(self) -> Self {
+        self.arg("infinite_rx", 1)
+    }
+
+    pub fn devargs(&self) -> &Devargs {
+        &self.devargs
+    }
+
+    /// Probe the device and return its port.
+    pub fn attach(&self) -> Result<Port> {
+        self.devargs
+            .attach()?
+            .into_iter()
+            .next()
+            .ok_or(Error::new(ENODEV))
+    }
+}
+
+impl From<PcapVdev> for Devargs {
+    fn from(vdev: PcapVdev) -> Self {
+        vdev.devargs
+    }
+}
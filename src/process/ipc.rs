@@ -0,0 +1,360 @@
+use std::collections::BTreeMap;
+use std::ffi::{c_void, CStr, CString};
+use std::marker::PhantomData;
+use std::mem;
+use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use std::os::raw::{c_char, c_int};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::{check_errno, Error, Result, E2BIG, EEXIST, EINVAL, ETIMEDOUT};
+use crate::*;
+
+/// A typed message of the multi-process channel, `rte_mp_msg`.
+///
+/// A message carries at most `RTE_MP_MAX_PARAM_LEN` bytes of data and
+/// `RTE_MP_MAX_FD_NUM` file descriptors. The descriptors are duplicated into
+/// the receiving process, the sender keeps its own.
+pub trait Message: Sized {
+    /// The name of the action receiving the message, shorter than
+    /// `RTE_MP_MAX_NAME_LEN` bytes.
+    const NAME: &'static str;
+
+    fn encode<'a>(&'a self, data: &mut Vec<u8>, fds: &mut Vec<BorrowedFd<'a>>);
+
+    /// Decode a message encoded by [`encode`](Self::encode), taking
+    /// ownership of the received descriptors.
+    fn decode(data: &[u8], fds: Vec<OwnedFd>) -> Result<Self>;
+}
+
+/// Encode `msg` as an `rte_mp_msg`, for the raw `rte_mp_*` functions.
+///
+/// The descriptors of the raw message are borrowed from `msg`.
+pub fn to_raw<M: Message>(msg: &M) -> Result<rte_mp_msg> {
+    let mut raw: rte_mp_msg = unsafe { mem::zeroed() };
+    let name = M::NAME.as_bytes();
+    if name.is_empty() || name.len() >= raw.name.len() || name.contains(&0) {
+        return Err(Error::new(EINVAL));
+    }
+    for (dst, &src) in raw.name.iter_mut().zip(name) {
+        *dst = src as c_char;
+    }
+
+    let mut data = Vec::new();
+    let mut fds = Vec::new();
+    msg.encode(&mut data, &mut fds);
+    if data.len() > raw.param.len() || fds.len() > raw.fds.len() {
+        return Err(Error::new(E2BIG));
+    }
+    raw.param[..data.len()].copy_from_slice(&data);
+    raw.len_param = data.len() as c_int;
+    // The descriptors are borrowed from `msg`, which outlives the sending.
+    for (dst, fd) in raw.fds.iter_mut().zip(&fds) {
+        *dst = fd.as_raw_fd();
+    }
+    raw.num_fds = fds.len() as c_int;
+    Ok(raw)
+}
+
+/// The received descriptors of `raw`, owned by the caller.
+unsafe fn take_fds(raw: &rte_mp_msg) -> Vec<OwnedFd> {
+    let num_fds = (raw.num_fds.max(0) as usize).min(raw.fds.len());
+    raw.fds[..num_fds]
+        .iter()
+        .map(|&fd| OwnedFd::from_raw_fd(fd))
+        .collect()
+}
+
+/// Decode a received message, taking ownership of its descriptors even if
+/// decoding fails.
+///
+/// # Safety
+///
+/// The descriptors of `raw` must be open and owned by no one else, as the
+/// ones of a received message.
+pub unsafe fn from_raw<M: Message>(raw: &rte_mp_msg) -> Result<M> {
+    let fds = take_fds(raw);
+    let len = (raw.len_param.max(0) as usize).min(raw.param.len());
+    M::decode(&raw.param[..len], fds)
+}
+
+/// The process a message came from, to reply to it.
+#[derive(Debug, Clone)]
+pub struct Peer {
+    name: CString,
+}
+
+impl Peer {
+    /// Reply to a request of the peer, which waits for one reply from each
+    /// process it sent the request to.
+    pub fn reply<R: Message>(&self, reply: &R) -> Result<()> {
+        let mut raw = to_raw(reply)?;
+        check_errno(unsafe { rte_mp_reply(&mut raw, self.name.as_ptr()) }).map(|_| ())
+    }
+}
+
+type Handler = dyn Fn(&rte_mp_msg, &Peer) -> Result<()> + Send + Sync;
+
+/// The handlers of the registered actions, by name.
+///
+/// `rte_mp_t` callbacks get no user data, so a single callback dispatches
+/// on the message name.
+static ACTIONS: Mutex<BTreeMap<String, Arc<Handler>>> = Mutex::new(BTreeMap::new());
+
+unsafe extern "C" fn action_trampoline(msg: *const rte_mp_msg, peer: *const c_void) -> c_int {
+    let msg = &*msg;
+    let name = CStr::from_ptr(msg.name.as_ptr()).to_string_lossy();
+    let handler = ACTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name.as_ref())
+        .cloned();
+    let Some(handler) = handler else {
+        drop(take_fds(msg));
+        return -1;
+    };
+    let peer = Peer {
+        name: CStr::from_ptr(peer as *const c_char).to_owned(),
+    };
+    // Unwinding into the IPC thread of the EAL is undefined behavior.
+    match catch_unwind(AssertUnwindSafe(|| handler(msg, &peer))) {
+        Ok(Ok(())) => 0,
+        _ => -1,
+    }
+}
+
+/// A registered action, `rte_mp_action_register`, unregistered on drop.
+#[derive(Debug)]
+pub struct Action {
+    name: CString,
+}
+
+impl Action {
+    /// Call `handler` for every message of type `M` sent to this process,
+    /// on the IPC thread of the EAL.
+    ///
+    /// The handler must not wait for other processes, e.g. with
+    /// [`request`], as no other message is received until it returns.
+    /// Replies can be sent later through a clone of the [`Peer`].
+    pub fn register<M, F>(handler: F) -> Result<Self>
+    where
+        M: Message,
+        F: Fn(M, &Peer) + Send + Sync + 'static,
+    {
+        let name = CString::new(M::NAME).map_err(|_| Error::new(EINVAL))?;
+        let handler: Arc<Handler> = Arc::new(move |msg: &rte_mp_msg, peer: &Peer| {
+            handler(unsafe { from_raw(msg) }?, peer);
+            Ok(())
+        });
+
+        let mut actions = ACTIONS.lock().unwrap_or_else(|e| e.into_inner());
+        if actions.contains_key(M::NAME) {
+            return Err(Error::new(EEXIST));
+        }
+        check_errno(unsafe { rte_mp_action_register(name.as_ptr(), Some(action_trampoline)) })?;
+        actions.insert(M::NAME.to_owned(), handler);
+        Ok(Self { name })
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.to_str().unwrap_or_default()
+    }
+}
+
+impl Drop for Action {
+    fn drop(&mut self) {
+        unsafe { rte_mp_action_unregister(self.name.as_ptr()) };
+        ACTIONS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(self.name());
+    }
+}
+
+/// Send `msg` without waiting for it to be handled: from the primary to all
+/// secondaries, from a secondary to the primary.
+pub fn send<M: Message>(msg: &M) -> Result<()> {
+    let mut raw = to_raw(msg)?;
+    check_errno(unsafe { rte_mp_sendmsg(&mut raw) }).map(|_| ())
+}
+
+/// The replies to a request.
+#[derive(Debug)]
+pub struct Replies<R> {
+    /// The number of processes the request was sent to.
+    pub sent: usize,
+    /// The replies received before the timeout.
+    pub replies: Vec<R>,
+}
+
+impl<R> Replies<R> {
+    /// Whether every process replied.
+    pub fn is_complete(&self) -> bool {
+        self.replies.len() == self.sent
+    }
+}
+
+/// Decode the replies, closing the descriptors of all of them if one fails.
+unsafe fn decode_replies<R: Message>(sent: c_int, msgs: &[rte_mp_msg]) -> Result<Replies<R>> {
+    let replies: Vec<Result<R>> = msgs.iter().map(|msg| from_raw(msg)).collect();
+    Ok(Replies {
+        sent: sent.max(0) as usize,
+        replies: replies.into_iter().collect::<Result<_>>()?,
+    })
+}
+
+fn to_timespec(timeout: Duration) -> timespec {
+    timespec {
+        tv_sec: timeout.as_secs() as _,
+        tv_nsec: timeout.subsec_nanos() as _,
+    }
+}
+
+/// Send the request `msg` like [`send`] and wait up to `timeout` for the
+/// replies.
+pub fn request<M: Message, R: Message>(msg: &M, timeout: Duration) -> Result<Replies<R>> {
+    let mut raw = to_raw(msg)?;
+    let mut reply: rte_mp_reply = unsafe { mem::zeroed() };
+    let ts = to_timespec(timeout);
+    let ret = check_errno(unsafe { rte_mp_request_sync(&mut raw, &mut reply, &ts) });
+    let replies = ret.and_then(|_| unsafe {
+        let msgs = if reply.msgs.is_null() {
+            &[][..]
+        } else {
+            slice::from_raw_parts(reply.msgs, reply.nb_received.max(0) as usize)
+        };
+        decode_replies(reply.nb_sent, msgs)
+    });
+    // The replies are allocated with malloc() for the caller to free.
+    unsafe { rte_mp_reply_free_(&mut reply) };
+    replies
+}
+
+/// Replies received by `async_trampoline`, copied out of the EAL.
+struct RawReplies {
+    sent: c_int,
+    msgs: Vec<rte_mp_msg>,
+}
+
+impl RawReplies {
+    fn close(&self) {
+        for msg in self.msgs.iter() {
+            drop(unsafe { take_fds(msg) });
+        }
+    }
+}
+
+struct PendingRequest {
+    id: u64,
+    request: rte_mp_msg,
+    sender: Sender<RawReplies>,
+}
+
+impl PendingRequest {
+    fn matches(&self, request: &rte_mp_msg) -> bool {
+        let len = (request.len_param.max(0) as usize).min(request.param.len());
+        self.request.name == request.name
+            && self.request.len_param == request.len_param
+            && self.request.param[..len] == request.param[..len]
+    }
+}
+
+/// The asynchronous requests in flight.
+///
+/// `rte_mp_async_reply_t` callbacks get no user data either, so requests are
+/// matched on their content. Identical requests in flight at the same time
+/// may get each other's replies.
+static PENDING: Mutex<(u64, Vec<PendingRequest>)> = Mutex::new((0, Vec::new()));
+
+unsafe extern "C" fn async_trampoline(
+    request: *const rte_mp_msg,
+    reply: *const rte_mp_reply,
+) -> c_int {
+    let (request, reply) = (&*request, &*reply);
+    let msgs = if reply.msgs.is_null() {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(reply.msgs, reply.nb_received.max(0) as usize).to_vec()
+    };
+    let replies = RawReplies {
+        sent: reply.nb_sent,
+        msgs,
+    };
+
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(i) = pending.1.iter().position(|p| p.matches(request)) else {
+        replies.close();
+        return -1;
+    };
+    let pending = pending.1.remove(i);
+    if let Err(e) = pending.sender.send(replies) {
+        // Nobody waits for the replies anymore.
+        e.0.close();
+    }
+    0
+}
+
+/// A request sent by [`request_async`], whose replies are yet to come.
+///
+/// Dropping it discards the replies.
+pub struct PendingReplies<R> {
+    receiver: Receiver<RawReplies>,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R: Message> PendingReplies<R> {
+    /// Block until every process replied or the timeout of the request
+    /// expired.
+    pub fn wait(self) -> Result<Replies<R>> {
+        let replies = self.receiver.recv().map_err(|_| Error::new(ETIMEDOUT))?;
+        unsafe { decode_replies(replies.sent, &replies.msgs) }
+    }
+
+    /// The replies if they are all in, `None` otherwise.
+    pub fn try_wait(&self) -> Option<Result<Replies<R>>> {
+        match self.receiver.try_recv() {
+            Ok(replies) => Some(unsafe { decode_replies(replies.sent, &replies.msgs) }),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(Error::new(ETIMEDOUT))),
+        }
+    }
+}
+
+/// Send the request `msg` like [`send`] without waiting for the replies,
+/// which are collected in the background for up to `timeout`.
+///
+/// Unlike [`request`], this may be called from an action handler.
+pub fn request_async<M: Message, R: Message>(
+    msg: &M,
+    timeout: Duration,
+) -> Result<PendingReplies<R>> {
+    let mut raw = to_raw(msg)?;
+    let (sender, receiver) = mpsc::channel();
+
+    // Registered first, the replies may come before the call returns.
+    let id = {
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        pending.0 += 1;
+        let id = pending.0;
+        pending.1.push(PendingRequest {
+            id,
+            request: raw,
+            sender,
+        });
+        id
+    };
+    let ts = to_timespec(timeout);
+    let ret = unsafe { rte_mp_request_async(&mut raw, &ts, Some(async_trampoline)) };
+    if let Err(e) = check_errno(ret) {
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        pending.1.retain(|p| p.id != id);
+        return Err(e);
+    }
+    Ok(PendingReplies {
+        receiver,
+        _marker: PhantomData,
+    })
+}
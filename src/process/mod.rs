@@ -0,0 +1,73 @@
+mod ipc;
+
+pub use ipc::*;
+
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::ptr;
+
+use crate::*;
+
+/// The role of a process in a multi-process application.
+///
+/// A secondary process shares the hugepage memory of its primary. It finds
+/// the objects the primary created by name, with
+/// [`Mempool::lookup`](crate::mbuf::Mempool::lookup),
+/// [`Ring::lookup`](crate::ring::Ring::lookup),
+/// [`Memzone::lookup`](crate::memory::Memzone::lookup) and
+/// [`Port::by_name`](crate::ethdev::Port::by_name), and talks to the other
+/// processes with [`Message`]s.
+///
+/// All processes must run with the same file prefix, and none with
+/// `--in-memory`, which disables sharing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProcessType {
+    /// The process owning the shared memory, there is one per file prefix.
+    Primary,
+    /// A process attached to the memory of a running primary.
+    Secondary,
+}
+
+impl ProcessType {
+    fn from_raw(proc_type: rte_proc_type_t) -> Option<Self> {
+        match proc_type {
+            rte_proc_type_t_RTE_PROC_PRIMARY => Some(ProcessType::Primary),
+            rte_proc_type_t_RTE_PROC_SECONDARY => Some(ProcessType::Secondary),
+            _ => None,
+        }
+    }
+
+    /// The value of the `--proc-type` EAL argument.
+    pub(crate) fn as_arg(&self) -> &'static str {
+        match self {
+            ProcessType::Primary => "primary",
+            ProcessType::Secondary => "secondary",
+        }
+    }
+}
+
+/// The type of the current process, `None` if the EAL does not know it yet.
+pub fn process_type() -> Option<ProcessType> {
+    ProcessType::from_raw(unsafe { rte_eal_process_type() })
+}
+
+pub fn is_primary() -> bool {
+    process_type() == Some(ProcessType::Primary)
+}
+
+/// Whether a primary process is running, checked through its lock on the
+/// runtime config file, the one of the current file prefix if `None`.
+///
+/// The config file is only known once the EAL is initialized: before, e.g.
+/// to wait for the primary before starting a secondary, pass its path, such
+/// as `/var/run/dpdk/rte/config` for the default prefix as root.
+pub fn primary_alive(config_file: Option<&Path>) -> bool {
+    let path = match config_file.map(|p| CString::new(p.as_os_str().as_bytes())) {
+        Some(Ok(path)) => Some(path),
+        Some(Err(_)) => return false,
+        None => None,
+    };
+    let path = path.as_ref().map_or(ptr::null(), |p| p.as_ptr());
+    unsafe { rte_eal_primary_proc_alive(path) == 1 }
+}
@@ -0,0 +1,330 @@
+use std::ffi::{c_void, CStr, CString};
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::ptr::{self, NonNull};
+
+use crate::error::{Error, Result, EINVAL, ENOBUFS, SOCKET_ID_ANY};
+use crate::*;
+
+/// How several threads may enqueue to, or dequeue from, a [`Ring`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SyncMode {
+    /// Multi-thread safe, the default.
+    #[default]
+    Multi,
+    /// Multi-thread safe with relaxed tail sync, which behaves better when
+    /// threads are preempted, e.g. on overcommitted systems.
+    Rts,
+    /// Multi-thread safe with head/tail sync, one thread at a time moves the
+    /// head. Required for the peek API.
+    Hts,
+}
+
+/// Builder for a [`Ring`].
+#[derive(Debug, Clone)]
+pub struct RingBuilder {
+    name: String,
+    count: u32,
+    socket_id: Option<u32>,
+    flags: u32,
+}
+
+impl RingBuilder {
+    /// A ring of `count` slots, which must be a power of two unless
+    /// [`exact_size`](Self::exact_size) is set.
+    pub fn new<S: Into<String>>(name: S, count: u32) -> Self {
+        Self {
+            name: name.into(),
+            count,
+            socket_id: None,
+            flags: 0,
+        }
+    }
+
+    pub fn socket_id(mut self, socket_id: u32) -> Self {
+        self.socket_id = Some(socket_id);
+        self
+    }
+
+    /// Make the usable size exactly `count`, rounding the internal size up to
+    /// the next power of two.
+    pub fn exact_size(mut self) -> Self {
+        self.flags |= RING_F_EXACT_SZ;
+        self
+    }
+
+    pub fn producer(mut self, mode: SyncMode) -> Self {
+        self.flags &= !(RING_F_SP_ENQ | RING_F_MP_RTS_ENQ | RING_F_MP_HTS_ENQ);
+        self.flags |= match mode {
+            SyncMode::Multi => 0,
+            SyncMode::Rts => RING_F_MP_RTS_ENQ,
+            SyncMode::Hts => RING_F_MP_HTS_ENQ,
+        };
+        self
+    }
+
+    pub fn consumer(mut self, mode: SyncMode) -> Self {
+        self.flags &= !(RING_F_SC_DEQ | RING_F_MC_RTS_DEQ | RING_F_MC_HTS_DEQ);
+        self.flags |= match mode {
+            SyncMode::Multi => 0,
+            SyncMode::Rts => RING_F_MC_RTS_DEQ,
+            SyncMode::Hts => RING_F_MC_HTS_DEQ,
+        };
+        self
+    }
+
+    /// Use the faster single-producer enqueue.
+    ///
+    /// # Safety
+    ///
+    /// At most one thread may enqueue to the ring at any time.
+    pub unsafe fn single_producer(mut self) -> Self {
+        self = self.producer(SyncMode::Multi);
+        self.flags |= RING_F_SP_ENQ;
+        self
+    }
+
+    /// Use the faster single-consumer dequeue.
+    ///
+    /// # Safety
+    ///
+    /// At most one thread may dequeue from the ring at any time.
+    pub unsafe fn single_consumer(mut self) -> Self {
+        self = self.consumer(SyncMode::Multi);
+        self.flags |= RING_F_SC_DEQ;
+        self
+    }
+
+    /// Create the ring. The size of `T` must be a non-zero multiple of 4
+    /// bytes, which holds for boxes, [`Mbuf`](crate::mbuf::Mbuf) handles and
+    /// other pointers.
+    pub fn create<T: Send>(self) -> Result<Ring<T>> {
+        let esize = Ring::<T>::esize()?;
+        let name = CString::new(self.name).map_err(|_| Error::new(EINVAL))?;
+        let socket_id = self.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32);
+        let raw = unsafe {
+            rte_ring_create_elem(name.as_ptr(), esize, self.count, socket_id, self.flags)
+        };
+        let raw = NonNull::new(raw).ok_or_else(Error::last)?;
+        Ok(Ring {
+            raw,
+            owned: true,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// A typed `rte_ring`, moving values of `T` between threads.
+///
+/// Values are copied in and out of the ring by the elem API, so the ring
+/// owns whatever is enqueued until it is dequeued. Values left in a ring
+/// created by this handle are dropped with it.
+pub struct Ring<T> {
+    raw: NonNull<rte_ring>,
+    owned: bool,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for Ring<T> {}
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T: Send> Ring<T> {
+    /// A multi-producer, multi-consumer ring on any socket.
+    pub fn new(name: &str, count: u32) -> Result<Self> {
+        RingBuilder::new(name, count).create()
+    }
+
+    pub fn builder<S: Into<String>>(name: S, count: u32) -> RingBuilder {
+        RingBuilder::new(name, count)
+    }
+
+    /// Look up a ring created elsewhere, e.g. by another process. The ring is
+    /// not freed when the handle is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The ring must have been created for elements of `T` and outlive the
+    /// handle, and its sync modes must match the way the handle is used.
+    pub unsafe fn lookup(name: &str) -> Result<Self> {
+        Self::esize()?;
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let raw = NonNull::new(rte_ring_lookup(name.as_ptr())).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            owned: false,
+            _marker: PhantomData,
+        })
+    }
+
+    fn esize() -> Result<u32> {
+        let size = mem::size_of::<T>();
+        if size == 0 || !size.is_multiple_of(4) {
+            return Err(Error::new(EINVAL));
+        }
+        Ok(size as u32)
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_ring {
+        self.raw.as_ptr()
+    }
+
+    pub fn name(&self) -> String {
+        let name = unsafe { CStr::from_ptr((*self.as_ptr()).name.as_ptr()) };
+        name.to_string_lossy().into_owned()
+    }
+
+    /// The number of values in the ring.
+    pub fn len(&self) -> u32 {
+        unsafe { rte_ring_count_(self.as_ptr()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        unsafe { rte_ring_empty_(self.as_ptr()) == 1 }
+    }
+
+    pub fn is_full(&self) -> bool {
+        unsafe { rte_ring_full_(self.as_ptr()) == 1 }
+    }
+
+    /// The number of free slots.
+    pub fn free_count(&self) -> u32 {
+        unsafe { rte_ring_free_count_(self.as_ptr()) }
+    }
+
+    /// The number of values the ring can hold.
+    pub fn capacity(&self) -> u32 {
+        unsafe { rte_ring_get_capacity_(self.as_ptr()) }
+    }
+
+    /// Enqueue one value, handing it back if the ring is full.
+    pub fn enqueue(&self, value: T) -> std::result::Result<(), T> {
+        let value = mem::ManuallyDrop::new(value);
+        let n = unsafe {
+            rte_ring_enqueue_bulk_elem_(
+                self.as_ptr(),
+                &*value as *const T as *const c_void,
+                mem::size_of::<T>() as u32,
+                1,
+                ptr::null_mut(),
+            )
+        };
+        if n == 1 {
+            Ok(())
+        } else {
+            Err(mem::ManuallyDrop::into_inner(value))
+        }
+    }
+
+    /// Enqueue all of `values`, or none of them if they do not fit. On
+    /// success `values` is left empty.
+    pub fn enqueue_bulk(&self, values: &mut Vec<T>) -> Result<()> {
+        let n = unsafe { self.enqueue_raw(values, true) };
+        if n == 0 && !values.is_empty() {
+            return Err(Error::new(ENOBUFS));
+        }
+        Ok(())
+    }
+
+    /// Enqueue as many values as fit from the front of `values`, which are
+    /// removed from it, and return how many were enqueued.
+    pub fn enqueue_burst(&self, values: &mut Vec<T>) -> usize {
+        unsafe { self.enqueue_raw(values, false) }
+    }
+
+    unsafe fn enqueue_raw(&self, values: &mut Vec<T>, bulk: bool) -> usize {
+        let len = values.len();
+        let f = if bulk {
+            rte_ring_enqueue_bulk_elem_
+        } else {
+            rte_ring_enqueue_burst_elem_
+        };
+        let n = f(
+            self.as_ptr(),
+            values.as_ptr() as *const c_void,
+            mem::size_of::<T>() as u32,
+            len as u32,
+            ptr::null_mut(),
+        ) as usize;
+        // The ring owns the first `n` values now, move the rest to the front
+        // without dropping anything.
+        let p = values.as_mut_ptr();
+        ptr::copy(p.add(n), p, len - n);
+        values.set_len(len - n);
+        n
+    }
+
+    pub fn dequeue(&self) -> Option<T> {
+        let mut value = MaybeUninit::<T>::uninit();
+        let n = unsafe {
+            rte_ring_dequeue_bulk_elem_(
+                self.as_ptr(),
+                value.as_mut_ptr() as *mut c_void,
+                mem::size_of::<T>() as u32,
+                1,
+                ptr::null_mut(),
+            )
+        };
+        if n == 1 {
+            Some(unsafe { value.assume_init() })
+        } else {
+            None
+        }
+    }
+
+    /// Dequeue exactly `n` values appended to `out`, or none if fewer are
+    /// available.
+    pub fn dequeue_bulk(&self, out: &mut Vec<T>, n: usize) -> Result<()> {
+        if n > 0 && unsafe { self.dequeue_raw(out, n, true) } == 0 {
+            return Err(Error::new(ENOBUFS));
+        }
+        Ok(())
+    }
+
+    /// Dequeue up to `max` values appended to `out`, and return how many
+    /// were dequeued.
+    pub fn dequeue_burst(&self, out: &mut Vec<T>, max: usize) -> usize {
+        unsafe { self.dequeue_raw(out, max, false) }
+    }
+
+    unsafe fn dequeue_raw(&self, out: &mut Vec<T>, max: usize, bulk: bool) -> usize {
+        out.reserve(max);
+        let len = out.len();
+        let f = if bulk {
+            rte_ring_dequeue_bulk_elem_
+        } else {
+            rte_ring_dequeue_burst_elem_
+        };
+        let n = f(
+            self.as_ptr(),
+            out.as_mut_ptr().add(len) as *mut c_void,
+            mem::size_of::<T>() as u32,
+            max as u32,
+            ptr::null_mut(),
+        ) as usize;
+        out.set_len(len + n);
+        n
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        if !self.owned {
+            return;
+        }
+        unsafe {
+            let esize = mem::size_of::<T>() as u32;
+            let mut value = MaybeUninit::<T>::uninit();
+            while rte_ring_dequeue_burst_elem_(
+                self.raw.as_ptr(),
+                value.as_mut_ptr() as *mut c_void,
+                esize,
+                1,
+                ptr::null_mut(),
+            ) == 1
+            {
+                value.assume_init_drop();
+            }
+            rte_ring_free(self.raw.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::mem;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ptr::NonNull;
+
+use super::{FibConfig, Ipv4Prefix, Ipv6Prefix, NextHop};
+use crate::error::{check, Error, Result, EINVAL, SOCKET_ID_ANY};
+use crate::*;
+
+/// The number of bytes used for each next hop, the smallest of 1, 2, 4 and 8
+/// that holds `H`.
+fn next_hop_size<H>() -> u32 {
+    mem::size_of::<H>().clamp(1, 8).next_power_of_two() as u32
+}
+
+/// The largest next hop that fits, one bit being used by the tables. It is
+/// returned by lookups that match no route, so it is not a valid next hop.
+fn no_route(size: u32) -> u64 {
+    (1 << (size * 8 - 1)) - 1
+}
+
+fn next_hop_id<H: NextHop>(next_hop: H, size: u32) -> Result<u64> {
+    let id = next_hop.to_id();
+    if id >= no_route(size) {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(id)
+}
+
+/// An IPv4 forwarding table, `rte_fib` using the DIR24_8 algorithm.
+pub struct Fib<H = u32> {
+    raw: NonNull<rte_fib>,
+    nh_size: u32,
+    _marker: PhantomData<H>,
+}
+
+unsafe impl<H: Send> Send for Fib<H> {}
+unsafe impl<H: Sync> Sync for Fib<H> {}
+
+impl<H: NextHop> Fib<H> {
+    pub fn new(name: &str, config: &FibConfig) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let nh_size = next_hop_size::<H>();
+        let nh_sz = match nh_size {
+            1 => rte_fib_dir24_8_nh_sz_RTE_FIB_DIR24_8_1B,
+            2 => rte_fib_dir24_8_nh_sz_RTE_FIB_DIR24_8_2B,
+            4 => rte_fib_dir24_8_nh_sz_RTE_FIB_DIR24_8_4B,
+            _ => rte_fib_dir24_8_nh_sz_RTE_FIB_DIR24_8_8B,
+        };
+        let mut conf: rte_fib_conf = unsafe { mem::zeroed() };
+        conf.type_ = rte_fib_type_RTE_FIB_DIR24_8;
+        conf.default_nh = no_route(nh_size);
+        conf.max_routes = config.max_routes as i32;
+        conf.__bindgen_anon_1.dir24_8 = rte_fib_conf__bindgen_ty_1__bindgen_ty_1 {
+            nh_sz,
+            num_tbl8: config.num_tbl8,
+        };
+        let socket_id = config.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32);
+        let raw = unsafe { rte_fib_create(name.as_ptr(), socket_id, &mut conf) };
+        let raw = NonNull::new(raw).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            nh_size,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_fib {
+        self.raw.as_ptr()
+    }
+
+    /// Add a route, replacing the next hop of an existing one.
+    pub fn add(&mut self, prefix: Ipv4Prefix, next_hop: H) -> Result<()> {
+        let id = next_hop_id(next_hop, self.nh_size)?;
+        let ip = u32::from(prefix.addr());
+        check(unsafe { rte_fib_add(self.as_ptr(), ip, prefix.prefix_len(), id) }).map(|_| ())
+    }
+
+    pub fn delete(&mut self, prefix: Ipv4Prefix) -> Result<()> {
+        let ip = u32::from(prefix.addr());
+        check(unsafe { rte_fib_delete(self.as_ptr(), ip, prefix.prefix_len()) }).map(|_| ())
+    }
+
+    /// The next hop of the longest prefix matching `addr`.
+    pub fn lookup(&self, addr: Ipv4Addr) -> Option<H> {
+        self.lookup_bulk(&[addr])[0]
+    }
+
+    pub fn lookup_bulk(&self, addrs: &[Ipv4Addr]) -> Vec<Option<H>> {
+        let mut ips: Vec<u32> = addrs.iter().map(|a| u32::from(*a)).collect();
+        let mut ids = vec![0u64; ips.len()];
+        unsafe {
+            rte_fib_lookup_bulk(
+                self.as_ptr(),
+                ips.as_mut_ptr(),
+                ids.as_mut_ptr(),
+                ips.len() as i32,
+            )
+        };
+        let no_route = no_route(self.nh_size);
+        ids.into_iter()
+            .map(|id| {
+                if id == no_route {
+                    None
+                } else {
+                    Some(H::from_id(id))
+                }
+            })
+            .collect()
+    }
+}
+
+impl<H> Drop for Fib<H> {
+    fn drop(&mut self) {
+        unsafe { rte_fib_free(self.raw.as_ptr()) };
+    }
+}
+
+/// An IPv6 forwarding table, `rte_fib6` using the TRIE algorithm.
+pub struct Fib6<H = u32> {
+    raw: NonNull<rte_fib6>,
+    nh_size: u32,
+    _marker: PhantomData<H>,
+}
+
+unsafe impl<H: Send> Send for Fib6<H> {}
+unsafe impl<H: Sync> Sync for Fib6<H> {}
+
+impl<H: NextHop> Fib6<H> {
+    pub fn new(name: &str, config: &FibConfig) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        // The trie has no 1 byte next hops.
+        let nh_size = next_hop_size::<H>().max(2);
+        let nh_sz = match nh_size {
+            2 => rte_fib_trie_nh_sz_RTE_FIB6_TRIE_2B,
+            4 => rte_fib_trie_nh_sz_RTE_FIB6_TRIE_4B,
+            _ => rte_fib_trie_nh_sz_RTE_FIB6_TRIE_8B,
+        };
+        let mut conf: rte_fib6_conf = unsafe { mem::zeroed() };
+        conf.type_ = rte_fib6_type_RTE_FIB6_TRIE;
+        conf.default_nh = no_route(nh_size);
+        conf.max_routes = config.max_routes as i32;
+        conf.__bindgen_anon_1.trie = rte_fib6_conf__bindgen_ty_1__bindgen_ty_1 {
+            nh_sz,
+            num_tbl8: config.num_tbl8,
+        };
+        let socket_id = config.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32);
+        let raw = unsafe { rte_fib6_create(name.as_ptr(), socket_id, &mut conf) };
+        let raw = NonNull::new(raw).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            nh_size,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_fib6 {
+        self.raw.as_ptr()
+    }
+
+    /// Add a route, replacing the next hop of an existing one.
+    pub fn add(&mut self, prefix: Ipv6Prefix, next_hop: H) -> Result<()> {
+        let id = next_hop_id(next_hop, self.nh_size)?;
+        let ip = prefix.addr().octets();
+        check(unsafe { rte_fib6_add(self.as_ptr(), ip.as_ptr(), prefix.prefix_len(), id) })
+            .map(|_| ())
+    }
+
+    pub fn delete(&mut self, prefix: Ipv6Prefix) -> Result<()> {
+        let ip = prefix.addr().octets();
+        check(unsafe { rte_fib6_delete(self.as_ptr(), ip.as_ptr(), prefix.prefix_len()) })
+            .map(|_| ())
+    }
+
+    /// The next hop of the longest prefix matching `addr`.
+    pub fn lookup(&self, addr: Ipv6Addr) -> Option<H> {
+        self.lookup_bulk(&[addr])[0]
+    }
+
+    pub fn lookup_bulk(&self, addrs: &[Ipv6Addr]) -> Vec<Option<H>> {
+        let mut ips: Vec<[u8; 16]> = addrs.iter().map(|a| a.octets()).collect();
+        let mut ids = vec![0u64; ips.len()];
+        unsafe {
+            rte_fib6_lookup_bulk(
+                self.as_ptr(),
+                ips.as_mut_ptr(),
+                ids.as_mut_ptr(),
+                ips.len() as i32,
+            )
+        };
+        let no_route = no_route(self.nh_size);
+        ids.into_iter()
+            .map(|id| {
+                if id == no_route {
+                    None
+                } else {
+                    Some(H::from_id(id))
+                }
+            })
+            .collect()
+    }
+}
+
+impl<H> Drop for Fib6<H> {
+    fn drop(&mut self) {
+        unsafe { rte_fib6_free(self.raw.as_ptr()) };
+    }
+}
@@ -0,0 +1,245 @@
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ptr::NonNull;
+
+use super::{Ipv4Prefix, Ipv6Prefix, LpmConfig, NextHop};
+use crate::error::{check, Error, Result, EINVAL, SOCKET_ID_ANY};
+use crate::*;
+
+/// Next hops of an [`Lpm`] table are 24 bits wide.
+const LPM_MAX_NEXT_HOP: u64 = (1 << 24) - 1;
+
+/// Next hops of an [`Lpm6`] table are 21 bits wide.
+const LPM6_MAX_NEXT_HOP: u64 = (1 << 21) - 1;
+
+fn next_hop_id<H: NextHop>(next_hop: H, max: u64) -> Result<u32> {
+    let id = next_hop.to_id();
+    if id > max {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(id as u32)
+}
+
+/// An IPv4 longest prefix match table, `rte_lpm`.
+///
+/// Prefixes must be at least 1 bit long, a default route is added as
+/// `0.0.0.0/1` and `128.0.0.0/1`.
+pub struct Lpm<H = u32> {
+    raw: NonNull<rte_lpm>,
+    _marker: PhantomData<H>,
+}
+
+unsafe impl<H: Send> Send for Lpm<H> {}
+unsafe impl<H: Sync> Sync for Lpm<H> {}
+
+impl<H: NextHop> Lpm<H> {
+    pub fn new(name: &str, config: &LpmConfig) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let cfg = rte_lpm_config {
+            max_rules: config.max_rules,
+            number_tbl8s: config.number_tbl8s,
+            flags: 0,
+        };
+        let socket_id = config.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32);
+        let raw = unsafe { rte_lpm_create(name.as_ptr(), socket_id, &cfg) };
+        let raw = NonNull::new(raw).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_lpm {
+        self.raw.as_ptr()
+    }
+
+    /// Add a route, replacing the next hop of an existing one.
+    pub fn add(&mut self, prefix: Ipv4Prefix, next_hop: H) -> Result<()> {
+        let id = next_hop_id(next_hop, LPM_MAX_NEXT_HOP)?;
+        let ip = u32::from(prefix.addr());
+        check(unsafe { rte_lpm_add(self.as_ptr(), ip, prefix.prefix_len(), id) }).map(|_| ())
+    }
+
+    pub fn delete(&mut self, prefix: Ipv4Prefix) -> Result<()> {
+        let ip = u32::from(prefix.addr());
+        check(unsafe { rte_lpm_delete(self.as_ptr(), ip, prefix.prefix_len()) }).map(|_| ())
+    }
+
+    /// Delete all routes.
+    pub fn clear(&mut self) {
+        unsafe { rte_lpm_delete_all(self.as_ptr()) };
+    }
+
+    /// The next hop of the route for exactly `prefix`.
+    pub fn get(&self, prefix: Ipv4Prefix) -> Option<H> {
+        let ip = u32::from(prefix.addr());
+        let mut id = 0;
+        let ret =
+            unsafe { rte_lpm_is_rule_present(self.as_ptr(), ip, prefix.prefix_len(), &mut id) };
+        if ret == 1 {
+            Some(H::from_id(id as u64))
+        } else {
+            None
+        }
+    }
+
+    /// The next hop of the longest prefix matching `addr`.
+    pub fn lookup(&self, addr: Ipv4Addr) -> Option<H> {
+        let mut id = 0;
+        let ret = unsafe { rte_lpm_lookup_(self.as_ptr(), u32::from(addr), &mut id) };
+        if ret == 0 {
+            Some(H::from_id(id as u64))
+        } else {
+            None
+        }
+    }
+
+    pub fn lookup_bulk(&self, addrs: &[Ipv4Addr]) -> Vec<Option<H>> {
+        let ips: Vec<u32> = addrs.iter().map(|a| u32::from(*a)).collect();
+        let mut ids = vec![0u32; ips.len()];
+        unsafe {
+            rte_lpm_lookup_bulk_(
+                self.as_ptr(),
+                ips.as_ptr(),
+                ids.as_mut_ptr(),
+                ips.len() as u32,
+            )
+        };
+        ids.into_iter()
+            .map(|id| {
+                if id & RTE_LPM_LOOKUP_SUCCESS != 0 {
+                    Some(H::from_id((id as u64) & LPM_MAX_NEXT_HOP))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl<H> Drop for Lpm<H> {
+    fn drop(&mut self) {
+        unsafe { rte_lpm_free(self.raw.as_ptr()) };
+    }
+}
+
+/// An IPv6 longest prefix match table, `rte_lpm6`.
+///
+/// Prefixes must be at least 1 bit long, like in [`Lpm`].
+pub struct Lpm6<H = u32> {
+    raw: NonNull<rte_lpm6>,
+    _marker: PhantomData<H>,
+}
+
+unsafe impl<H: Send> Send for Lpm6<H> {}
+unsafe impl<H: Sync> Sync for Lpm6<H> {}
+
+impl<H: NextHop> Lpm6<H> {
+    pub fn new(name: &str, config: &LpmConfig) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::new(EINVAL))?;
+        let cfg = rte_lpm6_config {
+            max_rules: config.max_rules,
+            number_tbl8s: config.number_tbl8s,
+            flags: 0,
+        };
+        let socket_id = config.socket_id.map_or(SOCKET_ID_ANY, |id| id as i32);
+        let raw = unsafe { rte_lpm6_create(name.as_ptr(), socket_id, &cfg) };
+        let raw = NonNull::new(raw).ok_or_else(Error::last)?;
+        Ok(Self {
+            raw,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn as_ptr(&self) -> *mut rte_lpm6 {
+        self.raw.as_ptr()
+    }
+
+    /// Add a route, replacing the next hop of an existing one.
+    pub fn add(&mut self, prefix: Ipv6Prefix, next_hop: H) -> Result<()> {
+        let id = next_hop_id(next_hop, LPM6_MAX_NEXT_HOP)?;
+        let ip = prefix.addr().octets();
+        check(unsafe { rte_lpm6_add(self.as_ptr(), ip.as_ptr(), prefix.prefix_len(), id) })
+            .map(|_| ())
+    }
+
+    pub fn delete(&mut self, prefix: Ipv6Prefix) -> Result<()> {
+        let ip = prefix.addr().octets();
+        check(unsafe { rte_lpm6_delete(self.as_ptr(), ip.as_ptr(), prefix.prefix_len()) })
+            .map(|_| ())
+    }
+
+    pub fn delete_bulk(&mut self, prefixes: &[Ipv6Prefix]) -> Result<()> {
+        let mut ips: Vec<[u8; 16]> = prefixes.iter().map(|p| p.addr().octets()).collect();
+        let mut depths: Vec<u8> = prefixes.iter().map(|p| p.prefix_len()).collect();
+        check(unsafe {
+            rte_lpm6_delete_bulk_func(
+                self.as_ptr(),
+                ips.as_mut_ptr(),
+                depths.as_mut_ptr(),
+                prefixes.len() as u32,
+            )
+        })
+        .map(|_| ())
+    }
+
+    /// Delete all routes.
+    pub fn clear(&mut self) {
+        unsafe { rte_lpm6_delete_all(self.as_ptr()) };
+    }
+
+    /// The next hop of the route for exactly `prefix`.
+    pub fn get(&self, prefix: Ipv6Prefix) -> Option<H> {
+        let ip = prefix.addr().octets();
+        let mut id = 0;
+        let ret = unsafe {
+            rte_lpm6_is_rule_present(self.as_ptr(), ip.as_ptr(), prefix.prefix_len(), &mut id)
+        };
+        if ret == 1 {
+            Some(H::from_id(id as u64))
+        } else {
+            None
+        }
+    }
+
+    /// The next hop of the longest prefix matching `addr`.
+    pub fn lookup(&self, addr: Ipv6Addr) -> Option<H> {
+        let ip = addr.octets();
+        let mut id = 0;
+        let ret = unsafe { rte_lpm6_lookup(self.as_ptr(), ip.as_ptr(), &mut id) };
+        if ret == 0 {
+            Some(H::from_id(id as u64))
+        } else {
+            None
+        }
+    }
+
+    pub fn lookup_bulk(&self, addrs: &[Ipv6Addr]) -> Vec<Option<H>> {
+        let mut ips: Vec<[u8; 16]> = addrs.iter().map(|a| a.octets()).collect();
+        let mut ids = vec![0i32; ips.len()];
+        unsafe {
+            rte_lpm6_lookup_bulk_func(
+                self.as_ptr(),
+                ips.as_mut_ptr(),
+                ids.as_mut_ptr(),
+                ips.len() as u32,
+            )
+        };
+        ids.into_iter()
+            .map(|id| {
+                if id < 0 {
+                    None
+                } else {
+                    Some(H::from_id(id as u64))
+                }
+            })
+            .collect()
+    }
+}
+
+impl<H> Drop for Lpm6<H> {
+    fn drop(&mut self) {
+        unsafe { rte_lpm6_free(self.raw.as_ptr()) };
+    }
+}
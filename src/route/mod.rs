@@ -0,0 +1,178 @@
+mod fib;
+mod lpm;
+
+pub use fib::*;
+pub use lpm::*;
+
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::error::{Error, Result, EINVAL};
+
+/// A next-hop id stored in a route table.
+///
+/// Implement it for a newtype to keep next hops of different tables apart.
+pub trait NextHop: Copy {
+    fn to_id(self) -> u64;
+
+    fn from_id(id: u64) -> Self;
+}
+
+macro_rules! impl_next_hop {
+    ($($t:ty),*) => {
+        $(impl NextHop for $t {
+            fn to_id(self) -> u64 {
+                self as u64
+            }
+
+            fn from_id(id: u64) -> Self {
+                id as $t
+            }
+        })*
+    };
+}
+
+impl_next_hop!(u8, u16, u32, u64, usize);
+
+/// An IPv4 prefix, `addr/len`, with the host bits cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv4Prefix {
+    addr: Ipv4Addr,
+    len: u8,
+}
+
+impl Ipv4Prefix {
+    pub fn new(addr: Ipv4Addr, len: u8) -> Result<Self> {
+        if len > 32 {
+            return Err(Error::new(EINVAL));
+        }
+        let mask = u32::MAX.checked_shl(32 - len as u32).unwrap_or(0);
+        Ok(Self {
+            addr: Ipv4Addr::from(u32::from(addr) & mask),
+            len,
+        })
+    }
+
+    pub fn addr(&self) -> Ipv4Addr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+
+    pub fn contains(&self, addr: Ipv4Addr) -> bool {
+        Self::new(addr, self.len).is_ok_and(|p| p.addr == self.addr)
+    }
+}
+
+impl fmt::Display for Ipv4Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+
+impl FromStr for Ipv4Prefix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (addr, len) = parse_prefix(s)?;
+        Self::new(addr, len.unwrap_or(32))
+    }
+}
+
+/// An IPv6 prefix, `addr/len`, with the host bits cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv6Prefix {
+    addr: Ipv6Addr,
+    len: u8,
+}
+
+impl Ipv6Prefix {
+    pub fn new(addr: Ipv6Addr, len: u8) -> Result<Self> {
+        if len > 128 {
+            return Err(Error::new(EINVAL));
+        }
+        let mask = u128::MAX.checked_shl(128 - len as u32).unwrap_or(0);
+        Ok(Self {
+            addr: Ipv6Addr::from(u128::from(addr) & mask),
+            len,
+        })
+    }
+
+    pub fn addr(&self) -> Ipv6Addr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+
+    pub fn contains(&self, addr: Ipv6Addr) -> bool {
+        Self::new(addr, self.len).is_ok_and(|p| p.addr == self.addr)
+    }
+}
+
+impl fmt::Display for Ipv6Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+
+impl FromStr for Ipv6Prefix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (addr, len) = parse_prefix(s)?;
+        Self::new(addr, len.unwrap_or(128))
+    }
+}
+
+fn parse_prefix<A: FromStr>(s: &str) -> Result<(A, Option<u8>)> {
+    let (addr, len) = match s.split_once('/') {
+        Some((addr, len)) => (addr, Some(len.parse().map_err(|_| Error::new(EINVAL))?)),
+        None => (s, None),
+    };
+    let addr = addr.parse().map_err(|_| Error::new(EINVAL))?;
+    Ok((addr, len))
+}
+
+/// Sizing of an [`Lpm`] or [`Lpm6`] table.
+#[derive(Debug, Clone, Copy)]
+pub struct LpmConfig {
+    pub max_rules: u32,
+    /// The number of second level tables, one being used by each prefix
+    /// longer than 24 bits (IPv4) or each 8 bits past the first 24 (IPv6).
+    pub number_tbl8s: u32,
+    pub socket_id: Option<u32>,
+}
+
+impl Default for LpmConfig {
+    fn default() -> Self {
+        Self {
+            max_rules: 1024,
+            number_tbl8s: 256,
+            socket_id: None,
+        }
+    }
+}
+
+/// Sizing of a [`Fib`] or [`Fib6`] table.
+#[derive(Debug, Clone, Copy)]
+pub struct FibConfig {
+    pub max_routes: u32,
+    /// The number of second level tables, see [`LpmConfig::number_tbl8s`].
+    pub num_tbl8: u32,
+    pub socket_id: Option<u32>,
+}
+
+impl Default for FibConfig {
+    fn default() -> Self {
+        Self {
+            max_routes: 1024,
+            num_tbl8: 256,
+            socket_id: None,
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{check, Error, Result, EEXIST, EINVAL, ENOMEM};
+use crate::*;
+
+/// A value returned by a telemetry command, built into a `rte_tel_data`.
+///
+/// Arrays hold values of a single kind, taken from their first element.
+/// Dicts may hold any value, arrays only scalars and arrays; the telemetry
+/// library rejects other values with `EINVAL`. Dict keys are made of
+/// alphanumerics, `_` and `/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// A signed integer. At the top level, it is sent as a string, the
+    /// telemetry protocol having no plain numbers.
+    Int(i64),
+    /// An unsigned integer, sent as a string at the top level as well.
+    Uint(u64),
+    String(String),
+    Array(Vec<Value>),
+    Dict(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Fill the empty `d` with the value.
+    unsafe fn fill(&self, d: *mut rte_tel_data) -> Result<()> {
+        let s = match self {
+            Value::Int(n) => n.to_string(),
+            Value::Uint(n) => n.to_string(),
+            Value::String(s) => s.clone(),
+            Value::Array(values) => return fill_array(d, values),
+            Value::Dict(entries) => return fill_dict(d, entries),
+        };
+        check(rte_tel_data_string(d, to_cstring(&s)?.as_ptr())).map(|_| ())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Int(n)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::Int(n as i64)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Uint(n)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(n: u32) -> Self {
+        Value::Uint(n as u64)
+    }
+}
+
+impl From<u16> for Value {
+    fn from(n: u16) -> Self {
+        Value::Uint(n as u64)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+fn to_cstring(s: &str) -> Result<CString> {
+    CString::new(s).map_err(|_| Error::new(EINVAL))
+}
+
+unsafe fn fill_array(d: *mut rte_tel_data, values: &[Value]) -> Result<()> {
+    let value_type = match values.first() {
+        None | Some(Value::Int(_)) => rte_tel_value_type_RTE_TEL_INT_VAL,
+        Some(Value::Uint(_)) => rte_tel_value_type_RTE_TEL_UINT_VAL,
+        Some(Value::String(_)) => rte_tel_value_type_RTE_TEL_STRING_VAL,
+        Some(Value::Array(_) | Value::Dict(_)) => rte_tel_value_type_RTE_TEL_CONTAINER,
+    };
+    check(rte_tel_data_start_array(d, value_type))?;
+    for value in values {
+        check(match value {
+            Value::Int(n) => rte_tel_data_add_array_int(d, *n),
+            Value::Uint(n) => rte_tel_data_add_array_uint(d, *n),
+            Value::String(s) => rte_tel_data_add_array_string(d, to_cstring(s)?.as_ptr()),
+            Value::Array(_) | Value::Dict(_) => Container::new(value)?
+                .add_to(|container| rte_tel_data_add_array_container(d, container, 0)),
+        })?;
+    }
+    Ok(())
+}
+
+unsafe fn fill_dict(d: *mut rte_tel_data, entries: &[(String, Value)]) -> Result<()> {
+    check(rte_tel_data_start_dict(d))?;
+    for (name, value) in entries {
+        let name = to_cstring(name)?;
+        let name = name.as_ptr();
+        check(match value {
+            Value::Int(n) => rte_tel_data_add_dict_int(d, name, *n),
+            Value::Uint(n) => rte_tel_data_add_dict_uint(d, name, *n),
+            Value::String(s) => rte_tel_data_add_dict_string(d, name, to_cstring(s)?.as_ptr()),
+            Value::Array(_) | Value::Dict(_) => Container::new(value)?
+                .add_to(|container| rte_tel_data_add_dict_container(d, name, container, 0)),
+        })?;
+    }
+    Ok(())
+}
+
+/// A nested `rte_tel_data`, freed unless its parent took it.
+struct Container {
+    raw: NonNull<rte_tel_data>,
+}
+
+impl Container {
+    fn new(value: &Value) -> Result<Self> {
+        let raw = NonNull::new(unsafe { rte_tel_data_alloc() }).ok_or(Error::new(ENOMEM))?;
+        let container = Self { raw };
+        unsafe { value.fill(container.as_ptr()) }?;
+        Ok(container)
+    }
+
+    fn as_ptr(&self) -> *mut rte_tel_data {
+        self.raw.as_ptr()
+    }
+
+    /// Add the container to its parent with `add`. Once added, the parent
+    /// frees it.
+    fn add_to<F: FnOnce(*mut rte_tel_data) -> c_int>(self, add: F) -> c_int {
+        let ret = add(self.as_ptr());
+        if ret == 0 {
+            std::mem::forget(self);
+        }
+        ret
+    }
+}
+
+impl Drop for Container {
+    fn drop(&mut self) {
+        unsafe { rte_tel_data_free(self.as_ptr()) };
+    }
+}
+
+type Command = dyn Fn(Option<&str>) -> Result<Value> + Send + Sync;
+
+/// The handlers of the registered commands.
+///
+/// `telemetry_cb` callbacks get no user data, so a single callback
+/// dispatches on the command.
+static COMMANDS: Mutex<BTreeMap<String, Arc<Command>>> = Mutex::new(BTreeMap::new());
+
+unsafe extern "C" fn command_trampoline(
+    cmd: *const c_char,
+    params: *const c_char,
+    info: *mut rte_tel_data,
+) -> c_int {
+    let cmd = CStr::from_ptr(cmd).to_string_lossy();
+    let handler = COMMANDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(cmd.as_ref())
+        .cloned();
+    let Some(handler) = handler else {
+        return -EINVAL;
+    };
+    let params = if params.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(params).to_str() {
+            Ok("") => None,
+            Ok(params) => Some(params),
+            Err(_) => return -EINVAL,
+        }
+    };
+    // Unwinding into the telemetry thread is undefined behavior.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        handler(params).and_then(|value| value.fill(info))
+    }));
+    match result {
+        Ok(Ok(())) => 0,
+        Ok(Err(e)) => -e.errno(),
+        Err(_) => -EINVAL,
+    }
+}
+
+/// Register the telemetry command `cmd`, e.g. `/app/stats`, answered with
+/// the value `handler` returns for the parameters following the command, if
+/// any.
+///
+/// Commands start with `/` and cannot be unregistered. The handler runs on
+/// the telemetry thread; an error makes the reply `null`.
+pub fn register_command<F>(cmd: &str, help: &str, handler: F) -> Result<()>
+where
+    F: Fn(Option<&str>) -> Result<Value> + Send + Sync + 'static,
+{
+    let c_cmd = to_cstring(cmd)?;
+    let c_help = to_cstring(help)?;
+
+    let mut commands = COMMANDS.lock().unwrap_or_else(|e| e.into_inner());
+    if commands.contains_key(cmd) {
+        return Err(Error::new(EEXIST));
+    }
+    check(unsafe {
+        rte_telemetry_register_cmd(c_cmd.as_ptr(), Some(command_trampoline), c_help.as_ptr())
+    })?;
+    commands.insert(cmd.to_owned(), Arc::new(handler));
+    Ok(())
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "acl")]
+
+mod common;
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use dpdk_sys::classify::{Acl, AclLayout, AclRule, Ipv4FiveTuple, Ipv6FiveTuple};
+
+const TCP: u8 = 6;
+const UDP: u8 = 17;
+
+/// The start of an IPv4 header without options followed by the ports, as
+/// far as the 5-tuple layout reads.
+fn ipv4_key(proto: u8, src: Ipv4Addr, dst: Ipv4Addr, src_port: u16, dst_port: u16) -> Vec<u8> {
+    let mut key = vec![0u8; 24];
+    key[0] = 0x45;
+    key[9] = proto;
+    key[12..16].copy_from_slice(&src.octets());
+    key[16..20].copy_from_slice(&dst.octets());
+    key[20..22].copy_from_slice(&src_port.to_be_bytes());
+    key[22..24].copy_from_slice(&dst_port.to_be_bytes());
+    key
+}
+
+fn ipv6_key(proto: u8, src: Ipv6Addr, dst: Ipv6Addr, src_port: u16, dst_port: u16) -> Vec<u8> {
+    let mut key = vec![0u8; 44];
+    key[0] = 0x60;
+    key[6] = proto;
+    key[8..24].copy_from_slice(&src.octets());
+    key[24..40].copy_from_slice(&dst.octets());
+    key[40..42].copy_from_slice(&src_port.to_be_bytes());
+    key[42..44].copy_from_slice(&dst_port.to_be_bytes());
+    key
+}
+
+fn classify(acl: &Acl, keys: &[Vec<u8>]) -> Vec<Option<u32>> {
+    let keys: Vec<&[u8]> = keys.iter().map(|key| key.as_slice()).collect();
+    acl.classify(&keys).unwrap()
+}
+
+#[test]
+fn key_len() {
+    assert_eq!(Ipv4FiveTuple::new().layout.key_len(), 24);
+    assert_eq!(Ipv6FiveTuple::new().layout.key_len(), 44);
+
+    // The last 1 byte field is still read as a whole word.
+    let mut b = AclLayout::builder();
+    b.u8_field(0);
+    b.ipv4(4);
+    b.u8_field(10);
+    assert_eq!(b.build().unwrap().key_len(), 14);
+
+    // The first field must be 1 byte.
+    let mut b = AclLayout::builder();
+    b.ipv4(0);
+    assert!(b.build().is_err());
+}
+
+#[test]
+fn ipv4_classify() {
+    common::eal();
+
+    let t = Ipv4FiveTuple::new();
+    let mut acl = Acl::new("test_acl4", t.layout.clone(), 16).unwrap();
+    acl.add_rules(&[
+        AclRule::new(1, 1).proto(t.proto, TCP),
+        AclRule::new(2, 2)
+            .proto(t.proto, TCP)
+            .ipv4(t.dst, Ipv4Addr::new(10, 0, 0, 0), 8)
+            .port_range(t.dst_port, 80..=89),
+        AclRule::new(3, 3)
+            .ipv4(t.src, Ipv4Addr::new(192, 168, 1, 0), 24)
+            .port(t.dst_port, 443),
+        AclRule::new(4, 4).proto(t.proto, UDP).port(t.src_port, 53),
+    ])
+    .unwrap();
+
+    // Not built yet.
+    let any = ipv4_key(TCP, Ipv4Addr::LOCALHOST, Ipv4Addr::LOCALHOST, 1, 1);
+    assert!(acl.classify(&[&any]).is_err());
+    acl.build().unwrap();
+
+    let host = Ipv4Addr::new(172, 16, 0, 1);
+    let net10 = |last| Ipv4Addr::new(10, 255, 255, last);
+    let cases = [
+        (ipv4_key(TCP, host, host, 1000, 80), Some(1)),
+        // Port range edges.
+        (ipv4_key(TCP, host, net10(1), 1000, 79), Some(1)),
+        (ipv4_key(TCP, host, net10(1), 1000, 80), Some(2)),
+        (ipv4_key(TCP, host, net10(1), 1000, 89), Some(2)),
+        (ipv4_key(TCP, host, net10(1), 1000, 90), Some(1)),
+        // Prefix edges.
+        (
+            ipv4_key(TCP, host, Ipv4Addr::new(11, 0, 0, 0), 1000, 80),
+            Some(1),
+        ),
+        (
+            ipv4_key(UDP, Ipv4Addr::new(192, 168, 1, 0), host, 1, 443),
+            Some(3),
+        ),
+        (
+            ipv4_key(UDP, Ipv4Addr::new(192, 168, 1, 255), host, 1, 443),
+            Some(3),
+        ),
+        (
+            ipv4_key(UDP, Ipv4Addr::new(192, 168, 2, 0), host, 1, 443),
+            None,
+        ),
+        (
+            ipv4_key(UDP, Ipv4Addr::new(192, 168, 0, 255), host, 1, 443),
+            None,
+        ),
+        // Priorities.
+        (
+            ipv4_key(TCP, Ipv4Addr::new(192, 168, 1, 1), host, 1, 443),
+            Some(3),
+        ),
+        (ipv4_key(UDP, host, net10(1), 53, 443), Some(4)),
+        (ipv4_key(UDP, host, host, 54, 443), None),
+    ];
+    let keys: Vec<Vec<u8>> = cases.iter().map(|(key, _)| key.clone()).collect();
+    let expected: Vec<Option<u32>> = cases.iter().map(|(_, found)| *found).collect();
+    assert_eq!(classify(&acl, &keys), expected);
+
+    // Keys shorter than the layout are refused.
+    assert!(acl.classify(&[&keys[0][..23]]).is_err());
+
+    acl.reset_rules();
+    acl.add_rules(&[AclRule::new(1, 5).port_range(t.src_port, 0..=999)])
+        .unwrap();
+    acl.build().unwrap();
+    assert_eq!(
+        classify(&acl, &[keys[0].clone(), keys[11].clone()]),
+        [None, Some(5)]
+    );
+}
+
+#[test]
+fn ipv6_classify() {
+    common::eal();
+
+    let t = Ipv6FiveTuple::new();
+    let mut acl = Acl::new("test_acl6", t.layout.clone(), 16).unwrap();
+    let net: Ipv6Addr = "2001:db8:100::".parse().unwrap();
+    // The prefix ends inside the second quarter of the address.
+    acl.add_rules(&[
+        AclRule::new(1, 1).ipv6(t.dst, net, 40),
+        AclRule::new(2, 2)
+            .proto(t.proto, UDP)
+            .ipv6(t.dst, net, 40)
+            .port_range(t.dst_port, 1000..=2000),
+    ])
+    .unwrap();
+    acl.build().unwrap();
+
+    let src: Ipv6Addr = "fd00::1".parse().unwrap();
+    let addr = |s: &str| s.parse::<Ipv6Addr>().unwrap();
+    let cases = [
+        (ipv6_key(TCP, src, addr("2001:db8:100::1"), 1, 1), Some(1)),
+        (
+            ipv6_key(TCP, src, addr("2001:db8:1ff:ffff::1"), 1, 1),
+            Some(1),
+        ),
+        (ipv6_key(TCP, src, addr("2001:db8:ff::1"), 1, 1), None),
+        (ipv6_key(TCP, src, addr("2001:db8:200::1"), 1, 1), None),
+        (ipv6_key(TCP, src, addr("2001:db9:100::1"), 1, 1), None),
+        (ipv6_key(UDP, src, addr("2001:db8:100::1"), 1, 999), Some(1)),
+        (
+            ipv6_key(UDP, src, addr("2001:db8:100::1"), 1, 1000),
+            Some(2),
+        ),
+        (
+            ipv6_key(UDP, src, addr("2001:db8:100::1"), 1, 2000),
+            Some(2),
+        ),
+        (
+            ipv6_key(UDP, src, addr("2001:db8:100::1"), 1, 2001),
+            Some(1),
+        ),
+    ];
+    let keys: Vec<Vec<u8>> = cases.iter().map(|(key, _)| key.clone()).collect();
+    let expected: Vec<Option<u32>> = cases.iter().map(|(_, found)| *found).collect();
+    assert_eq!(classify(&acl, &keys), expected);
+}
+
+#[test]
+fn categories() {
+    common::eal();
+
+    let t = Ipv4FiveTuple::new();
+    let mut acl = Acl::new("test_acl_categories", t.layout.clone(), 16).unwrap();
+    assert!(acl.set_categories(3).is_err());
+    acl.set_categories(4).unwrap();
+    acl.add_rules(&[
+        AclRule::new(1, 1).proto(t.proto, TCP).categories(0b0011),
+        AclRule::new(2, 2).port(t.dst_port, 22).categories(0b0010),
+        AclRule::new(1, 3).categories(0b1000),
+    ])
+    .unwrap();
+    acl.build().unwrap();
+
+    let host = Ipv4Addr::new(172, 16, 0, 1);
+    let keys = [
+        ipv4_key(TCP, host, host, 1000, 22),
+        ipv4_key(UDP, host, host, 1000, 80),
+    ];
+    assert_eq!(
+        classify(&acl, &keys),
+        [Some(1), Some(2), None, Some(3), None, None, None, Some(3)]
+    );
+}
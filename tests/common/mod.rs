@@ -0,0 +1,109 @@
+#![allow(dead_code)]
+
+pub mod telemetry;
+
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+
+use dpdk_sys::dev::Devargs;
+use dpdk_sys::ethdev::Port;
+use dpdk_sys::init::EalArgs;
+use dpdk_sys::mbuf::Mempool;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The queue of the thread that initialized the EAL, and so is its main
+/// lcore. The thread lives as long as the test binary.
+fn main_lcore() -> &'static Mutex<Sender<Job>> {
+    static MAIN: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+
+    MAIN.get_or_init(|| {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let (done, init) = mpsc::channel();
+        thread::Builder::new()
+            .name("eal-main".to_string())
+            .spawn(move || {
+                let ret = EalArgs::new("dpdk-sys-test")
+                    .lcores("0-1")
+                    .no_huge()
+                    .in_memory()
+                    .no_pci()
+                    .init();
+                let ok = ret.is_ok();
+                done.send(ret).unwrap();
+                if ok {
+                    for job in queue {
+                        job();
+                    }
+                }
+            })
+            .expect("failed to spawn the main lcore thread");
+        init.recv().unwrap().expect("failed to initialize the EAL");
+        Mutex::new(jobs)
+    })
+}
+
+/// Initialize the EAL once for the whole test binary, on lcores 0 and 1 and
+/// without hugepages, shared files or PCI devices so the tests run as an
+/// ordinary user.
+///
+/// The EAL cannot be initialized twice in a process, so all the tests of a
+/// binary share it: names of pools, rings and devices must be unique within
+/// the binary. The test threads themselves are not EAL threads, see
+/// [`on_main_lcore`].
+pub fn eal() {
+    main_lcore();
+}
+
+/// Run `f` on the main lcore and return its result, or resume its panic.
+///
+/// Calls are run one at a time, so `f` has the worker lcore to itself.
+pub fn on_main_lcore<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let job: Job = Box::new(move || {
+        let _ = tx.send(catch_unwind(AssertUnwindSafe(f)));
+    });
+    main_lcore()
+        .lock()
+        .unwrap()
+        .send(job)
+        .expect("the main lcore thread is gone");
+    rx.recv()
+        .expect("the main lcore thread is gone")
+        .unwrap_or_else(|e| resume_unwind(e))
+}
+
+/// A pool of `n` mbufs of the usual 2 KiB of data.
+pub fn pool(name: &str, n: u32) -> Mempool {
+    eal();
+    Mempool::create(name, n, 0, 2048 + 128, None).expect("failed to create mempool")
+}
+
+/// Attach the virtual device `devargs`, and configure and start its port
+/// with `nb_queues` receive and transmit queues.
+pub fn start_vdev(devargs: &Devargs, nb_queues: u16, pool: &Mempool) -> Port {
+    eal();
+    let ports = devargs.attach().expect("failed to attach vdev");
+    assert_eq!(ports.len(), 1);
+    let port = ports[0];
+    port.configure(nb_queues, nb_queues).unwrap();
+    for queue in 0..nb_queues {
+        port.rx_queue_setup(queue, 0, pool).unwrap();
+        port.tx_queue_setup(queue, 0).unwrap();
+    }
+    port.start().unwrap();
+    port
+}
+
+/// Stop and close the port of `devargs`, and detach the device.
+pub fn stop_vdev(devargs: &Devargs, port: Port) {
+    port.stop().unwrap();
+    port.close().unwrap();
+    devargs.detach().unwrap();
+}
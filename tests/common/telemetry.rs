@@ -0,0 +1,131 @@
+use std::io::{Read, Write};
+use std::mem;
+use std::os::fd::FromRawFd;
+use std::os::raw::{c_char, c_int, c_ushort};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use dpdk_sys::init::runtime_dir;
+
+const AF_UNIX: c_int = 1;
+const SOCK_SEQPACKET: c_int = 5;
+
+#[repr(C)]
+struct SockaddrUn {
+    sun_family: c_ushort,
+    sun_path: [c_char; 108],
+}
+
+// The standard library has no SOCK_SEQPACKET sockets.
+extern "C" {
+    fn socket(domain: c_int, ty: c_int, protocol: c_int) -> c_int;
+    fn connect(fd: c_int, addr: *const SockaddrUn, len: u32) -> c_int;
+}
+
+/// A client of the telemetry socket of this process, speaking the protocol
+/// of `dpdk-telemetry.py`.
+pub struct TelemetryClient {
+    sock: UnixStream,
+    max_output_len: usize,
+}
+
+impl TelemetryClient {
+    pub fn connect() -> Self {
+        super::eal();
+        let dir = runtime_dir();
+        // Processes sharing the runtime directory, e.g. other in-memory test
+        // binaries, get sockets with a `:N` suffix.
+        for i in 0..16 {
+            let name = match i {
+                0 => "dpdk_telemetry.v2".to_owned(),
+                i => format!("dpdk_telemetry.v2:{}", i),
+            };
+            if let Some(client) = Self::connect_to(&dir.join(name)) {
+                return client;
+            }
+        }
+        panic!("no telemetry socket in {}", dir.display());
+    }
+
+    /// Connect to `path` if it is the socket of this process.
+    fn connect_to(path: &Path) -> Option<Self> {
+        let path = path.as_os_str().as_bytes();
+        let mut addr = SockaddrUn {
+            sun_family: AF_UNIX as c_ushort,
+            sun_path: [0; 108],
+        };
+        assert!(path.len() < addr.sun_path.len());
+        for (dst, &src) in addr.sun_path.iter_mut().zip(path) {
+            *dst = src as c_char;
+        }
+
+        let fd = unsafe { socket(AF_UNIX, SOCK_SEQPACKET, 0) };
+        assert!(fd >= 0, "failed to create socket");
+        let sock = unsafe { UnixStream::from_raw_fd(fd) };
+        let len = mem::size_of::<SockaddrUn>() as u32;
+        if unsafe { connect(fd, &addr, len) } != 0 {
+            return None;
+        }
+
+        // The server greets with its version, pid and maximum reply length.
+        let mut client = Self {
+            sock,
+            max_output_len: 1024,
+        };
+        let info = client.recv();
+        if json_uint(&info, "pid")? != std::process::id() as u64 {
+            return None;
+        }
+        client.max_output_len = json_uint(&info, "max_output_len")? as usize;
+        Some(client)
+    }
+
+    fn recv(&mut self) -> String {
+        let mut buf = vec![0; self.max_output_len];
+        let n = self.sock.read(&mut buf).expect("failed to read reply");
+        String::from_utf8(buf[..n].to_vec()).expect("reply is not UTF-8")
+    }
+
+    /// Send `cmd`, e.g. `/ethdev/stats,0`, and return the JSON reply
+    /// without whitespace.
+    pub fn query(&mut self, cmd: &str) -> String {
+        self.sock
+            .write_all(cmd.as_bytes())
+            .expect("failed to send command");
+        compact(&self.recv())
+    }
+}
+
+/// The unsigned integer `key` of the JSON object `json`.
+fn json_uint(json: &str, key: &str) -> Option<u64> {
+    let key = format!("\"{}\"", key);
+    let rest = json[json.find(&key)? + key.len()..].trim_start();
+    let rest = rest.strip_prefix(':')?.trim_start();
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+/// `json` without the whitespace outside of strings.
+fn compact(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let (mut in_string, mut escaped) = (false, false);
+    for c in json.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c.is_whitespace() {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use dpdk_sys::error::{Error, EEXIST, EINVAL, ENODEV, ENOENT, EPERM};
+use dpdk_sys::ethdev::Port;
+use dpdk_sys::lcore;
+use dpdk_sys::mbuf::Mempool;
+use dpdk_sys::ring::Ring;
+
+#[test]
+fn error_messages() {
+    common::eal();
+    let err = Error::new(EINVAL);
+    assert_eq!(err.errno(), EINVAL);
+    assert_eq!(err.to_string(), "Invalid argument (errno 22)");
+    // DPDK specific errno values have their own messages.
+    assert!(Error::new(1001).to_string().contains("secondary process"));
+}
+
+#[test]
+fn invalid_names_are_rejected() {
+    common::eal();
+    let err = Mempool::create("bad\0name", 15, 0, 256, None).unwrap_err();
+    assert_eq!(err.errno(), EINVAL);
+}
+
+#[test]
+fn rte_errno_is_reported() {
+    let pool = common::pool("errno_exists", 15);
+    let err = Mempool::create("errno_exists", 15, 0, 256, None).unwrap_err();
+    assert_eq!(err.errno(), EEXIST);
+    assert_eq!(Error::last(), err);
+    drop(pool);
+
+    let err = unsafe { Ring::<u64>::lookup("errno_no_such_ring") }
+        .err()
+        .unwrap();
+    assert_eq!(err.errno(), ENOENT);
+}
+
+#[test]
+fn negative_returns_are_reported() {
+    common::eal();
+    let port = Port::new(u16::MAX - 1);
+    assert!(!port.is_valid());
+    assert_eq!(port.configure(1, 1).unwrap_err().errno(), ENODEV);
+    assert_eq!(port.stats().unwrap_err().errno(), ENODEV);
+}
+
+#[test]
+fn leaked_pool_keeps_its_name() {
+    let pool = common::pool("errno_leaked", 15);
+    let m = pool.alloc().unwrap();
+    // A pool dropped with mbufs in use is leaked rather than freed.
+    drop(pool);
+    let err = Mempool::create("errno_leaked", 15, 0, 256, None).unwrap_err();
+    assert_eq!(err.errno(), EEXIST);
+    drop(m);
+}
+
+#[test]
+fn launch_needs_main_lcore() {
+    common::eal();
+    // The test threads are not EAL threads.
+    let worker = lcore::workers().next().unwrap();
+    let err = lcore::launch(worker, || ()).err().unwrap();
+    assert_eq!(err.errno(), EPERM);
+    // The main lcore may.
+    let launched = common::on_main_lcore(move || lcore::launch(worker, || ()).map(|h| h.join()));
+    assert!(matches!(launched, Ok(Ok(()))));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use dpdk_sys::dev::Devargs;
+use dpdk_sys::ethdev::{LoopbackPort, Port};
+use dpdk_sys::mbuf::{Mbuf, Mempool};
+
+fn packets(pool: &Mempool, n: usize, tag: u8) -> Vec<Mbuf> {
+    (0..n)
+        .map(|i| {
+            let mut m = pool.alloc().unwrap();
+            let data = m.append(64 + i as u16).unwrap();
+            data.fill(tag);
+            data[0] = i as u8;
+            m
+        })
+        .collect()
+}
+
+fn receive(port: Port, queue_id: u16, n: usize) -> Vec<Mbuf> {
+    let mut pkts = Vec::new();
+    for _ in 0..100 {
+        let missing = n - pkts.len();
+        if missing == 0 {
+            break;
+        }
+        port.rx_burst(queue_id, &mut pkts, missing);
+    }
+    pkts
+}
+
+#[test]
+fn null_port_info() {
+    let pool = common::pool("ethdev_null_info", 255);
+    let devargs = Devargs::vdev("net_null_info");
+    let port = common::start_vdev(&devargs, 1, &pool);
+
+    assert!(port.is_valid());
+    assert_eq!(Port::by_name("net_null_info").unwrap(), port);
+    let info = port.info().unwrap();
+    assert_eq!(info.name, "net_null_info");
+    assert_eq!(info.driver_name, "net_null");
+    assert_eq!(info.bus_name.as_deref(), Some("vdev"));
+    assert_eq!(info.pci_addr, None);
+
+    common::stop_vdev(&devargs, port);
+    assert!(!port.is_valid());
+}
+
+#[test]
+fn null_port_bursts() {
+    let pool = common::pool("ethdev_null", 255);
+    let devargs = Devargs::vdev("net_null_burst").arg("size", 128);
+    let port = common::start_vdev(&devargs, 1, &pool);
+
+    // net_null makes up packets of `size` bytes on receive.
+    let mut pkts = receive(port, 0, 32);
+    assert_eq!(pkts.len(), 32);
+    assert!(pkts.iter().all(|m| m.pkt_len() == 128));
+    assert_eq!(pool.avail_count(), 255 - 32);
+
+    // ... and drops whatever is sent.
+    assert_eq!(port.tx_burst(0, &mut pkts), 32);
+    assert!(pkts.is_empty());
+    assert!(pool.is_full());
+
+    let stats = port.stats().unwrap();
+    assert_eq!(stats.ipackets, 32);
+    assert_eq!(stats.opackets, 32);
+    port.reset_stats().unwrap();
+    assert_eq!(port.stats().unwrap().ipackets, 0);
+
+    common::stop_vdev(&devargs, port);
+}
+
+#[test]
+fn ring_vdev_loops_back() {
+    let pool = common::pool("ethdev_ring", 255);
+    let devargs = Devargs::vdev("net_ring_loop");
+    let port = common::start_vdev(&devargs, 1, &pool);
+
+    let mut pkts = packets(&pool, 8, 0xab);
+    assert_eq!(port.tx_burst(0, &mut pkts), 8);
+
+    let received = receive(port, 0, 8);
+    assert_eq!(received.len(), 8);
+    for (i, m) in received.iter().enumerate() {
+        assert_eq!(m.pkt_len() as usize, 64 + i);
+        assert_eq!(m.data()[0], i as u8);
+    }
+
+    drop(received);
+    common::stop_vdev(&devargs, port);
+    assert!(pool.is_full());
+}
+
+#[test]
+fn loopback_pair_connects_queues() {
+    let pool = common::pool("ethdev_loopback", 255);
+    let (a, b) = LoopbackPort::pair("lo_pair", 2, 64, &pool).unwrap();
+    assert_ne!(a.port(), b.port());
+
+    let mut pkts = packets(&pool, 10, 1);
+    assert_eq!(a.tx_burst(1, &mut pkts), 10);
+    assert!(receive(b.port(), 0, 1).is_empty());
+    let received = receive(b.port(), 1, 10);
+    assert_eq!(received.len(), 10);
+    assert!(received.iter().all(|m| m.data()[1] == 1));
+
+    let mut pkts = packets(&pool, 5, 2);
+    assert_eq!(b.tx_burst(0, &mut pkts), 5);
+    let mut back = Vec::new();
+    assert_eq!(a.rx_burst(0, &mut back, 32), 5);
+    assert!(back.iter().all(|m| m.data()[1] == 2));
+    assert!(receive(a.port(), 1, 1).is_empty());
+
+    drop((received, back));
+    assert!(pool.is_full());
+}
+
+#[test]
+fn loopback_ring_full_keeps_packets() {
+    let pool = common::pool("ethdev_full", 255);
+    let (a, b) = LoopbackPort::pair("lo_full", 1, 16, &pool).unwrap();
+
+    let mut pkts = packets(&pool, 20, 3);
+    assert_eq!(a.tx_burst(0, &mut pkts), 16);
+    assert_eq!(pkts.len(), 4);
+    assert_eq!(pkts[0].data()[0], 16);
+
+    // Packets left in the rings are freed with the ports.
+    drop((a, b, pkts));
+    assert!(pool.is_full());
+}
@@ -0,0 +1,59 @@
+use dpdk_sys::error::{Error, EINVAL};
+use dpdk_sys::ethdev::EtherAddr;
+
+const ADDR: EtherAddr = EtherAddr::new([0x02, 0x1a, 0x2b, 0x3c, 0x4d, 0xfe]);
+
+#[test]
+fn display_round_trip() {
+    assert_eq!(ADDR.to_string(), "02:1A:2B:3C:4D:FE");
+    assert_eq!(ADDR.to_string().parse::<EtherAddr>().unwrap(), ADDR);
+    assert_eq!(format!("{:?}", ADDR), "EtherAddr(02:1A:2B:3C:4D:FE)");
+    for addr in [EtherAddr::ZERO, EtherAddr::BROADCAST] {
+        assert_eq!(addr.to_string().parse::<EtherAddr>().unwrap(), addr);
+    }
+}
+
+#[test]
+fn parse_valid() {
+    for s in [
+        "02:1a:2b:3c:4d:fe",
+        "02:1A:2B:3C:4D:FE",
+        "02-1a-2b-3c-4d-fe",
+        "021a:2b3c:4dfe",
+        "2:1a:2b:3c:4d:fe",
+    ] {
+        assert_eq!(s.parse::<EtherAddr>(), Ok(ADDR), "{}", s);
+    }
+    assert_eq!(
+        "1:2:3".parse::<EtherAddr>(),
+        Ok(EtherAddr::new([0, 1, 0, 2, 0, 3]))
+    );
+}
+
+#[test]
+fn parse_invalid() {
+    for s in [
+        "",
+        // Wrong or mixed separators.
+        "02.1a.2b.3c.4d.fe",
+        "02 1a 2b 3c 4d fe",
+        "02:1a:2b-3c:4d:fe",
+        "021a-2b3c-4dfe",
+        "02:1a:2b:3c:4d:fe:",
+        // Too short or too long.
+        "02:1a:2b:3c:4d",
+        "02:1a:2b:3c:4d:fe:01",
+        "021a:2b3c",
+        "021a:2b3c:4dfe:0000",
+        "002:1a:2b:3c:4d:fe",
+        "0021a:2b3c:4dfe",
+        "02:1a::3c:4d:fe",
+        // Not hex.
+        "02:1a:2b:3c:4d:fg",
+        "+2:1a:2b:3c:4d:fe",
+        "0x02:1a:2b:3c:4d:fe",
+        "02:1a:2b:3c:4d:fé",
+    ] {
+        assert_eq!(s.parse::<EtherAddr>(), Err(Error::new(EINVAL)), "{}", s);
+    }
+}
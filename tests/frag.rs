@@ -0,0 +1,197 @@
+mod common;
+
+use std::slice;
+use std::thread;
+use std::time::Duration;
+
+use dpdk_sys::frag::{fragment_ipv4, fragment_ipv6, FragTable, FragTableConfig};
+use dpdk_sys::mbuf::{Mbuf, Mempool};
+use dpdk_sys::net::HeaderLens;
+
+const MTU: u16 = 500;
+const PAYLOAD_LEN: usize = 1200;
+
+/// An IPv4 packet without options nor checksum, fragmentable, carrying
+/// `PAYLOAD_LEN` bytes of UDP.
+fn ipv4(id: u16) -> Vec<u8> {
+    let mut ip = vec![0u8; 20];
+    ip[0] = 0x45;
+    ip[2..4].copy_from_slice(&((20 + PAYLOAD_LEN) as u16).to_be_bytes());
+    ip[4..6].copy_from_slice(&id.to_be_bytes());
+    ip[8] = 64;
+    ip[9] = 17;
+    ip[12..16].copy_from_slice(&[10, 0, 0, 1]);
+    ip[16..20].copy_from_slice(&[10, 0, 0, 2]);
+    ip.extend((0..PAYLOAD_LEN).map(|i| i as u8));
+    ip
+}
+
+/// An IPv6 packet without extension headers carrying `PAYLOAD_LEN` bytes of
+/// UDP.
+fn ipv6() -> Vec<u8> {
+    let mut ip = vec![0u8; 40];
+    ip[0] = 0x60;
+    ip[4..6].copy_from_slice(&(PAYLOAD_LEN as u16).to_be_bytes());
+    ip[6] = 17;
+    ip[7] = 64;
+    ip[8..10].copy_from_slice(&[0x20, 0x01]);
+    ip[23] = 1;
+    ip[24..26].copy_from_slice(&[0x20, 0x01]);
+    ip[39] = 2;
+    ip.extend((0..PAYLOAD_LEN).map(|i| i as u8));
+    ip
+}
+
+fn mbuf(pool: &Mempool, pkt: &[u8]) -> Mbuf {
+    let mut m = pool.alloc().expect("mempool exhausted");
+    m.append(pkt.len() as u16)
+        .expect("not enough tailroom")
+        .copy_from_slice(pkt);
+    m
+}
+
+/// The bytes of all the segments of `m`.
+fn contents(m: &Mbuf) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut seg = m.as_ptr();
+    while !seg.is_null() {
+        unsafe {
+            let s = &*seg;
+            let data = (s.buf_addr as *const u8).add(s.data_off as usize);
+            out.extend_from_slice(slice::from_raw_parts(data, s.data_len as usize));
+            seg = s.next;
+        }
+    }
+    out
+}
+
+/// Set the header lengths as the reassembly expects, the fragments start at
+/// the IP header.
+fn set_l3_len(fragments: &mut [Mbuf], l3_len: u16) {
+    for m in fragments {
+        m.set_header_lens(HeaderLens {
+            l3_len,
+            ..HeaderLens::default()
+        });
+    }
+}
+
+fn table(max_age: Duration) -> FragTable {
+    FragTable::new(&FragTableConfig {
+        bucket_num: 16,
+        bucket_entries: 4,
+        max_entries: 64,
+        max_age,
+        socket_id: None,
+    })
+    .unwrap()
+}
+
+#[test]
+fn ipv4_round_trip() {
+    let direct = common::pool("frag4_direct", 63);
+    let indirect = common::pool("frag4_indirect", 63);
+    let pkt = ipv4(0x1234);
+
+    let mut fragments = fragment_ipv4(mbuf(&direct, &pkt), MTU, &direct, &indirect).unwrap();
+    // 480 payload bytes fit in each fragment.
+    assert_eq!(fragments.len(), 3);
+    for m in &fragments {
+        assert!(m.pkt_len() <= MTU as u32);
+    }
+    set_l3_len(&mut fragments, 20);
+
+    // Out of order.
+    let mut table = table(Duration::from_secs(10));
+    let last = fragments.pop().unwrap();
+    assert!(table.reassemble(last).is_none());
+    assert!(table.reassemble(fragments.remove(0)).is_none());
+    let whole = table.reassemble(fragments.remove(0)).unwrap();
+
+    assert_eq!(whole.pkt_len() as usize, pkt.len());
+    let data = contents(&whole);
+    // Length, id and cleared fragment offset.
+    assert_eq!(data[2..8], pkt[2..8]);
+    assert_eq!(data[20..], pkt[20..]);
+    assert_eq!(table.death_row_len(), 0);
+
+    drop((whole, table));
+    assert!(direct.is_full());
+    assert!(indirect.is_full());
+}
+
+#[test]
+fn ipv6_round_trip() {
+    let direct = common::pool("frag6_direct", 63);
+    let indirect = common::pool("frag6_indirect", 63);
+    let pkt = ipv6();
+
+    let mut fragments = fragment_ipv6(mbuf(&direct, &pkt), MTU, &direct, &indirect).unwrap();
+    // 448 payload bytes fit after the fragment header.
+    assert_eq!(fragments.len(), 3);
+    set_l3_len(&mut fragments, 40 + 8);
+
+    let mut table = table(Duration::from_secs(10));
+    let mut whole = None;
+    for m in fragments.into_iter().rev() {
+        assert!(whole.is_none());
+        whole = table.reassemble(m);
+    }
+    let whole = whole.unwrap();
+
+    // The fragment header is gone.
+    assert_eq!(contents(&whole), pkt);
+
+    drop((whole, table));
+    assert!(direct.is_full());
+    assert!(indirect.is_full());
+}
+
+#[test]
+fn unfragmented_packets_pass_through() {
+    let pool = common::pool("frag_pass", 15);
+    let mut table = table(Duration::from_secs(10));
+    let pkt = ipv4(1);
+    let mut m = mbuf(&pool, &pkt);
+    m.set_header_lens(HeaderLens {
+        l3_len: 20,
+        ..HeaderLens::default()
+    });
+    let m = table.reassemble(m).unwrap();
+    assert_eq!(m.data(), &pkt[..]);
+}
+
+#[test]
+fn incomplete_packets_expire() {
+    let direct = common::pool("frag_expire_direct", 63);
+    let indirect = common::pool("frag_expire_indirect", 63);
+    let max_age = Duration::from_millis(10);
+    let mut table = table(max_age);
+
+    // Two datagrams missing their last fragment.
+    let mut first = fragment_ipv4(mbuf(&direct, &ipv4(1)), MTU, &direct, &indirect).unwrap();
+    let mut second = fragment_ipv4(mbuf(&direct, &ipv4(2)), MTU, &direct, &indirect).unwrap();
+    set_l3_len(&mut first, 20);
+    set_l3_len(&mut second, 20);
+    drop((first.pop(), second.pop()));
+    for m in first.drain(..1).chain(second.drain(..)) {
+        assert!(table.reassemble(m).is_none());
+    }
+    assert!(!direct.is_full());
+
+    thread::sleep(max_age * 5);
+
+    // A late fragment finds its datagram timed out: the fragments received
+    // so far go to the death row and the datagram starts over.
+    assert!(table.reassemble(first.remove(0)).is_none());
+    assert_eq!(table.death_row_len(), 1);
+    table.free_death_row();
+    assert_eq!(table.death_row_len(), 0);
+
+    // The rest is dropped once expired.
+    thread::sleep(max_age * 5);
+    table.del_expired();
+    assert_eq!(table.death_row_len(), 0);
+    assert!(direct.is_full());
+    assert!(indirect.is_full());
+}
@@ -0,0 +1,222 @@
+mod common;
+
+use dpdk_sys::gro::{self, GroConfig, GroContext};
+use dpdk_sys::gso::GsoContext;
+use dpdk_sys::mbuf::{Mbuf, Mempool};
+use dpdk_sys::net::{L2Type, L3Type, L4Type, PacketType, TxOffload, L3, L4};
+
+const HDR_LEN: usize = 14 + 20 + 20;
+const MSS: usize = 1000;
+
+/// A TCP/IPv4 segment of a stream from 10.0.0.1:`src_port` to 10.0.0.2:80,
+/// with its packet type and header lengths set as GRO expects.
+fn tcp4(pool: &Mempool, src_port: u16, seq: u32, payload_len: usize) -> Mbuf {
+    let mut m = pool.alloc().expect("mempool exhausted");
+    let data = m
+        .append((HDR_LEN + payload_len) as u16)
+        .expect("not enough tailroom");
+
+    data[0..6].copy_from_slice(&[0x02, 0, 0, 0, 0, 0x02]);
+    data[6..12].copy_from_slice(&[0x02, 0, 0, 0, 0, 0x01]);
+    data[12..14].copy_from_slice(&0x0800u16.to_be_bytes());
+
+    let ip = &mut data[14..34];
+    ip.fill(0);
+    ip[0] = 0x45;
+    ip[2..4].copy_from_slice(&((HDR_LEN - 14 + payload_len) as u16).to_be_bytes());
+    // Don't fragment, so GRO does not look at the IP ids.
+    ip[6..8].copy_from_slice(&0x4000u16.to_be_bytes());
+    ip[8] = 64;
+    ip[9] = 6;
+    ip[12..16].copy_from_slice(&[10, 0, 0, 1]);
+    ip[16..20].copy_from_slice(&[10, 0, 0, 2]);
+
+    let tcp = &mut data[34..54];
+    tcp.fill(0);
+    tcp[0..2].copy_from_slice(&src_port.to_be_bytes());
+    tcp[2..4].copy_from_slice(&80u16.to_be_bytes());
+    tcp[4..8].copy_from_slice(&seq.to_be_bytes());
+    tcp[8..12].copy_from_slice(&1u32.to_be_bytes());
+    tcp[12] = 0x50;
+    // ACK only, GRO leaves packets with other flags alone.
+    tcp[13] = 0x10;
+    tcp[14..16].copy_from_slice(&0xffffu16.to_be_bytes());
+
+    for (i, byte) in data[HDR_LEN..].iter_mut().enumerate() {
+        *byte = (seq as usize + i) as u8;
+    }
+
+    set_metadata(&mut m);
+    m
+}
+
+fn set_metadata(m: &mut Mbuf) {
+    m.set_packet_type(PacketType {
+        l2: L2Type::Ether,
+        l3: L3Type::Ipv4,
+        l4: L4Type::Tcp,
+        ..PacketType::UNKNOWN
+    });
+    m.parse_header_lens().expect("failed to parse headers");
+}
+
+fn stream(pool: &Mempool, src_port: u16, first_seq: u32, n: usize) -> Vec<Mbuf> {
+    (0..n)
+        .map(|i| tcp4(pool, src_port, first_seq + (i * MSS) as u32, MSS))
+        .collect()
+}
+
+fn seq(m: &Mbuf) -> u32 {
+    match m.headers().unwrap().outer.l4 {
+        Some(L4::Tcp(tcp)) => u32::from_be(tcp.sent_seq),
+        _ => panic!("not a TCP packet"),
+    }
+}
+
+fn ip_total_len(m: &Mbuf) -> usize {
+    match m.headers().unwrap().outer.l3 {
+        Some(L3::Ipv4(ip)) => u16::from_be(ip.total_length) as usize,
+        _ => panic!("not an IPv4 packet"),
+    }
+}
+
+fn pool(name: &str, data_room_size: u16) -> Mempool {
+    common::eal();
+    Mempool::create(name, 256, 0, data_room_size, None).expect("failed to create mempool")
+}
+
+#[test]
+fn gro_burst_merges_stream() {
+    let pool = pool("gro_burst", 2048 + 128);
+    let mut pkts = stream(&pool, 1000, 1, 8);
+
+    gro::reassemble_burst(&mut pkts, &GroConfig::default());
+
+    assert_eq!(pkts.len(), 1);
+    let m = &pkts[0];
+    assert_eq!(m.nb_segs(), 8);
+    assert_eq!(m.pkt_len() as usize, HDR_LEN + 8 * MSS);
+    assert_eq!(ip_total_len(m), HDR_LEN - 14 + 8 * MSS);
+    assert_eq!(seq(m), 1);
+}
+
+#[test]
+fn gro_burst_keeps_flows_and_gaps_apart() {
+    let pool = pool("gro_flows", 2048 + 128);
+    let mut pkts = Vec::new();
+    // Two interleaved flows, and a third one missing a segment.
+    for (a, b) in stream(&pool, 1000, 1, 4)
+        .into_iter()
+        .zip(stream(&pool, 2000, 1, 4))
+    {
+        pkts.push(a);
+        pkts.push(b);
+    }
+    pkts.push(tcp4(&pool, 3000, 1, MSS));
+    pkts.push(tcp4(&pool, 3000, 1 + 2 * MSS as u32, MSS));
+
+    gro::reassemble_burst(&mut pkts, &GroConfig::default());
+
+    assert_eq!(pkts.len(), 4);
+    let mut lens: Vec<usize> = pkts.iter().map(|m| m.pkt_len() as usize).collect();
+    lens.sort();
+    assert_eq!(
+        lens,
+        [
+            HDR_LEN + MSS,
+            HDR_LEN + MSS,
+            HDR_LEN + 4 * MSS,
+            HDR_LEN + 4 * MSS
+        ]
+    );
+}
+
+#[test]
+fn gro_context_merges_across_bursts() {
+    let pool = pool("gro_ctx", 2048 + 128);
+    let mut ctx = GroContext::new(&GroConfig::default()).unwrap();
+
+    let mut pkts = stream(&pool, 1000, 1, 8);
+    let mut second = pkts.split_off(4);
+    ctx.reassemble(&mut pkts);
+    ctx.reassemble(&mut second);
+
+    assert!(pkts.is_empty());
+    assert!(second.is_empty());
+    assert_eq!(ctx.len(), 1);
+
+    let out = ctx.flush();
+    assert!(ctx.is_empty());
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].nb_segs(), 8);
+    assert_eq!(out[0].pkt_len() as usize, HDR_LEN + 8 * MSS);
+}
+
+#[test]
+fn gso_segments_stream() {
+    let direct = pool("gso_direct", 8192 + 128);
+    let indirect = pool("gso_indirect", 0);
+    let ctx = GsoContext::new(&direct, &indirect, (HDR_LEN + MSS) as u16);
+
+    let mut m = tcp4(&direct, 1000, 1, 4 * MSS + 100);
+    m.set_tx_offload(TxOffload {
+        tso_segsz: Some(MSS as u16),
+        ..Default::default()
+    })
+    .unwrap();
+    let segments = ctx.segment(m).unwrap();
+
+    assert_eq!(segments.len(), 5);
+    for (i, seg) in segments.iter().enumerate() {
+        let payload_len = if i < 4 { MSS } else { 100 };
+        assert_eq!(seg.pkt_len() as usize, HDR_LEN + payload_len);
+        assert_eq!(ip_total_len(seg), HDR_LEN - 14 + payload_len);
+        assert_eq!(seq(seg), 1 + (i * MSS) as u32);
+    }
+    drop(segments);
+    assert!(indirect.is_full());
+}
+
+#[test]
+fn gso_leaves_small_packets_alone() {
+    let direct = pool("gso_small", 2048 + 128);
+    let indirect = pool("gso_small_indirect", 0);
+    let ctx = GsoContext::new(&direct, &indirect, (HDR_LEN + MSS) as u16);
+
+    let mut m = tcp4(&direct, 1000, 1, 200);
+    m.set_tx_offload(TxOffload {
+        tso_segsz: Some(MSS as u16),
+        ..Default::default()
+    })
+    .unwrap();
+    let segments = ctx.segment(m).unwrap();
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].pkt_len() as usize, HDR_LEN + 200);
+}
+
+#[test]
+fn gso_then_gro_round_trip() {
+    let direct = pool("gso_gro_direct", 8192 + 128);
+    let indirect = pool("gso_gro_indirect", 0);
+    let ctx = GsoContext::new(&direct, &indirect, (HDR_LEN + MSS) as u16);
+
+    let mut m = tcp4(&direct, 1000, 1, 6 * MSS);
+    m.set_tx_offload(TxOffload {
+        tso_segsz: Some(MSS as u16),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut segments = ctx.segment(m).unwrap();
+    assert_eq!(segments.len(), 6);
+
+    for seg in segments.iter_mut() {
+        set_metadata(seg);
+    }
+    gro::reassemble_burst(&mut segments, &GroConfig::default());
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].pkt_len() as usize, HDR_LEN + 6 * MSS);
+    assert_eq!(ip_total_len(&segments[0]), HDR_LEN - 14 + 6 * MSS);
+    assert_eq!(seq(&segments[0]), 1);
+}
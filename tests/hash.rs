@@ -0,0 +1,196 @@
+#![cfg(feature = "hash")]
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use dpdk_sys::error::{EINVAL, ENOSPC};
+use dpdk_sys::hash_table::{hash_crc, jhash, DpdkHash, HashBuilder, HashFunction};
+
+/// A value counting its drops in `drops`.
+#[derive(Debug)]
+struct Counted {
+    value: u32,
+    drops: Arc<AtomicUsize>,
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn counted(value: u32, drops: &Arc<AtomicUsize>) -> Counted {
+    Counted {
+        value,
+        drops: drops.clone(),
+    }
+}
+
+#[test]
+fn insert_lookup_remove() {
+    common::eal();
+    let mut hash = DpdkHash::<u32, String>::new("hash_basic", 64).unwrap();
+    assert!(hash.is_empty());
+    assert_eq!(hash.get(&1), None);
+
+    assert_eq!(hash.insert(1, "one".to_string()), Ok(None));
+    assert_eq!(hash.insert(2, "two".to_string()), Ok(None));
+    assert_eq!(hash.len(), 2);
+    assert_eq!(hash.get(&1).map(String::as_str), Some("one"));
+    assert!(hash.contains_key(&2));
+    assert!(!hash.contains_key(&3));
+
+    // Overwriting hands back the previous value.
+    assert_eq!(
+        hash.insert(1, "uno".to_string()),
+        Ok(Some("one".to_string()))
+    );
+    assert_eq!(hash.len(), 2);
+    assert_eq!(hash.get(&1).map(String::as_str), Some("uno"));
+
+    hash.get_mut(&2).unwrap().push('!');
+    assert_eq!(hash.get(&2).map(String::as_str), Some("two!"));
+
+    assert_eq!(hash.remove(&1), Some("uno".to_string()));
+    assert_eq!(hash.remove(&1), None);
+    assert_eq!(hash.get(&1), None);
+    assert_eq!(hash.len(), 1);
+
+    let entries: BTreeMap<u32, &String> = hash.iter().collect();
+    assert_eq!(entries, BTreeMap::from([(2, &"two!".to_string())]));
+
+    hash.clear();
+    assert!(hash.is_empty());
+    assert_eq!(hash.iter().count(), 0);
+}
+
+#[test]
+fn with_hash_and_bulk() {
+    common::eal();
+    let mut hash = DpdkHash::<[u8; 6], u32>::new("hash_bulk", 256).unwrap();
+    let key = |i: u32| [0x02, 0, 0, 0, (i >> 8) as u8, i as u8];
+
+    for i in 0..100 {
+        let sig = hash.hash(&key(i));
+        hash.insert_with_hash(key(i), sig, i).unwrap();
+    }
+    let sig = hash.hash(&key(7));
+    assert_eq!(hash.get_with_hash(&key(7), sig), Some(&7));
+    assert_eq!(hash.remove_with_hash(&key(7), sig), Some(7));
+    assert_eq!(hash.get_with_hash(&key(7), sig), None);
+
+    // More keys than one lookup burst, hits and misses mixed.
+    let keys: Vec<[u8; 6]> = (0..200).map(key).collect();
+    let found = hash.get_bulk(&keys);
+    assert_eq!(found.len(), 200);
+    for (i, found) in found.into_iter().enumerate() {
+        let expected = if i < 100 && i != 7 {
+            Some(i as u32)
+        } else {
+            None
+        };
+        assert_eq!(found.copied(), expected, "{}", i);
+    }
+}
+
+#[test]
+fn values_are_dropped() {
+    common::eal();
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut hash = DpdkHash::<u64, Counted>::new("hash_drops", 64).unwrap();
+    for i in 0..10 {
+        hash.insert(i, counted(i as u32, &drops)).unwrap();
+    }
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    // Overwritten and removed values go to the caller.
+    let old = hash.insert(0, counted(100, &drops)).unwrap().unwrap();
+    assert_eq!(old.value, 0);
+    let removed = hash.remove(&1).unwrap();
+    assert_eq!(removed.value, 1);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    drop((old, removed));
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+
+    drop(hash.remove(&2));
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+
+    hash.clear();
+    assert_eq!(drops.load(Ordering::SeqCst), 11);
+
+    for i in 0..5 {
+        hash.insert(i, counted(i as u32, &drops)).unwrap();
+    }
+    drop(hash);
+    assert_eq!(drops.load(Ordering::SeqCst), 16);
+}
+
+#[test]
+fn full_table() {
+    common::eal();
+    let drops = Arc::new(AtomicUsize::new(0));
+    // With the extendable bucket table, only the key slots run out.
+    let mut hash = HashBuilder::new("hash_full", 64)
+        .ext_table()
+        .create::<u32, Counted>()
+        .unwrap();
+    for i in 0..64 {
+        hash.insert(i, counted(i, &drops)).unwrap();
+    }
+    let err = hash.insert(64, counted(64, &drops)).unwrap_err();
+    assert_eq!(err.errno(), ENOSPC);
+    // The value that did not fit is dropped, the table is unchanged.
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+    assert_eq!(hash.len(), 64);
+    assert!(!hash.contains_key(&64));
+
+    // Overwriting needs no new slot.
+    hash.insert(0, counted(0, &drops)).unwrap();
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+    drop(hash.remove(&1));
+    hash.insert(64, counted(64, &drops)).unwrap();
+    assert!(hash.contains_key(&64));
+}
+
+#[test]
+fn invalid_tables() {
+    common::eal();
+    let err = |name: &str, entries| {
+        DpdkHash::<u32, u32>::new(name, entries)
+            .err()
+            .map(|e| e.errno())
+    };
+    // Fewer entries than a bucket holds.
+    assert_eq!(err("hash_too_small", 4), Some(EINVAL));
+    assert_eq!(err("hash\0nul", 64), Some(EINVAL));
+}
+
+#[test]
+fn hash_functions() {
+    // lookup3 `hashlittle` test vectors.
+    let text = b"Four score and seven years ago";
+    assert_eq!(jhash(b"", 0), 0xdeadbeef);
+    assert_eq!(jhash(text, 0), 0x17770551);
+    assert_eq!(jhash(text, 1), 0xcd628161);
+
+    // CRC32C, without the final inversion.
+    assert_eq!(hash_crc(b"123456789", !0) ^ !0, 0xe3069283);
+    assert_eq!(hash_crc(b"", 42), 42);
+
+    common::eal();
+    let key: u64 = 0x0123_4567_89ab_cdef;
+    let jhash_table = HashBuilder::new("hash_jhash", 64)
+        .init_val(7)
+        .create::<u64, u32>()
+        .unwrap();
+    assert_eq!(jhash_table.hash(&key), jhash(&key.to_ne_bytes(), 7));
+    let crc_table = HashBuilder::new("hash_crc", 64)
+        .hash_func(HashFunction::Crc)
+        .init_val(7)
+        .create::<u64, u32>()
+        .unwrap();
+    assert_eq!(crc_table.hash(&key), hash_crc(&key.to_ne_bytes(), 7));
+}
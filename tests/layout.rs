@@ -0,0 +1,93 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// The layout of a type of the bindings as seen by Rust.
+struct Layout {
+    c_type: &'static str,
+    size: usize,
+    align: usize,
+    fields: Vec<(&'static str, usize)>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/layout.rs"));
+
+/// C static asserts checking that the C compiler agrees with `layouts`.
+fn static_asserts(layouts: &[Layout]) -> String {
+    let mut src = format!(
+        "#include \"{}/csrc/header.h\"\n#include <stddef.h>\n\n",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    writeln!(
+        src,
+        "_Static_assert(RTE_CACHE_LINE_SIZE == {}, \"RTE_CACHE_LINE_SIZE\");",
+        dpdk_sys::RTE_CACHE_LINE_SIZE
+    )
+    .unwrap();
+    for layout in layouts {
+        let t = layout.c_type;
+        writeln!(
+            src,
+            "_Static_assert(sizeof({}) == {}, \"size of {}\");",
+            t, layout.size, t
+        )
+        .unwrap();
+        writeln!(
+            src,
+            "_Static_assert(_Alignof({}) == {}, \"alignment of {}\");",
+            t, layout.align, t
+        )
+        .unwrap();
+        for (field, offset) in layout.fields.iter() {
+            writeln!(
+                src,
+                "_Static_assert(offsetof({}, {}) == {}, \"offset of {}.{}\");",
+                t, field, offset, t, field
+            )
+            .unwrap();
+        }
+    }
+    src
+}
+
+#[test]
+fn c_agrees_with_rust_layouts() {
+    let layouts = layouts();
+    assert!(layouts.iter().any(|l| l.c_type == "struct rte_mbuf"));
+
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let src = dir.join("layout.c");
+    fs::write(&src, static_asserts(&layouts)).unwrap();
+
+    // Compiled with the compiler and flags of csrc/impl.c.
+    let output = Command::new(env!("DPDK_SYS_CC"))
+        .args(env!("DPDK_SYS_CFLAGS").split_whitespace())
+        .arg("-c")
+        .arg("-o")
+        .arg(dir.join("layout.o"))
+        .arg(&src)
+        .output()
+        .expect("failed to run the C compiler");
+    assert!(
+        output.status.success(),
+        "layouts differ between C and Rust:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn mbuf_is_cache_aligned() {
+    // The second half of the mbuf starts at a minimal cache line, whatever
+    // the cache line size of the target.
+    let min_line = dpdk_sys::RTE_CACHE_LINE_MIN_SIZE as usize;
+    assert_eq!(
+        std::mem::align_of::<dpdk_sys::rte_mbuf>(),
+        dpdk_sys::RTE_CACHE_LINE_SIZE as usize
+    );
+    assert_eq!(std::mem::size_of::<dpdk_sys::rte_mbuf>(), 2 * min_line);
+    assert_eq!(
+        std::mem::offset_of!(dpdk_sys::rte_mbuf, cacheline1),
+        min_line
+    );
+}
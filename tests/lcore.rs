@@ -0,0 +1,151 @@
+mod common;
+
+use std::sync::mpsc;
+use std::thread;
+
+use dpdk_sys::error::{EBUSY, EEXIST, EINVAL};
+use dpdk_sys::lcore::{self, register_thread, spawn_registered, Lcore, Lcores, Role, Topology};
+
+// The EAL runs on lcores 0 and 1, see `common::eal`. The tests that count
+// lcores or register threads run on the main lcore, one at a time.
+const MAIN: Lcore = Lcore::new(0);
+const WORKER: Lcore = Lcore::new(1);
+
+#[test]
+fn lcores() {
+    common::eal();
+    // The test threads are not EAL threads.
+    assert_eq!(Lcore::current(), None);
+
+    common::on_main_lcore(|| {
+        assert_eq!(Lcore::current(), Some(MAIN));
+        assert!(MAIN.is_main());
+        assert_eq!(Lcore::count(), 2);
+        assert_eq!(lcore::lcores().collect::<Vec<_>>(), [MAIN, WORKER]);
+        assert_eq!(lcore::workers().collect::<Vec<_>>(), [WORKER]);
+
+        // Wrapping goes round the lcores forever.
+        let round: Vec<Lcore> = Lcores::new(false, true).after(WORKER).take(3).collect();
+        assert_eq!(round, [MAIN, WORKER, MAIN]);
+        let round: Vec<Lcore> = Lcores::new(true, true).take(3).collect();
+        assert_eq!(round, [WORKER, WORKER, WORKER]);
+    });
+}
+
+#[test]
+fn launch_returns_values() {
+    common::on_main_lcore(|| {
+        let handle = lcore::launch(WORKER, || (Lcore::current(), 42)).unwrap();
+        assert_eq!(handle.lcore(), WORKER);
+        assert_eq!(handle.join().unwrap(), (Some(WORKER), 42));
+
+        let handles = lcore::launch_workers(|| Lcore::current().unwrap()).unwrap();
+        let ran: Vec<Lcore> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(ran, [WORKER]);
+    });
+}
+
+#[test]
+fn launch_propagates_panics() {
+    common::on_main_lcore(|| {
+        let handle = lcore::launch::<_, ()>(WORKER, || panic!("worker panic")).unwrap();
+        let payload = handle.join().unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"worker panic"));
+
+        // The worker is back waiting for a function.
+        let handle = lcore::launch(WORKER, || 1).unwrap();
+        assert_eq!(handle.join().unwrap(), 1);
+    });
+}
+
+#[test]
+fn launch_on_a_busy_lcore() {
+    common::on_main_lcore(|| {
+        let (release, wait) = mpsc::channel::<()>();
+        let handle = lcore::launch(WORKER, move || wait.recv().unwrap()).unwrap();
+        assert!(lcore::is_running(WORKER));
+        assert!(!handle.is_finished());
+        let err = lcore::launch(WORKER, || ()).err().unwrap();
+        assert_eq!(err.errno(), EBUSY);
+
+        release.send(()).unwrap();
+        handle.join().unwrap();
+        assert!(!lcore::is_running(WORKER));
+
+        // Handles may be dropped and the workers waited for at once.
+        drop(lcore::launch(WORKER, || ()).unwrap());
+        lcore::wait_all();
+        assert!(!lcore::is_running(WORKER));
+    });
+}
+
+#[test]
+fn launch_needs_a_worker() {
+    let errno = |lcore| lcore::launch(lcore, || ()).err().map(|e| e.errno());
+    common::on_main_lcore(move || {
+        assert_eq!(errno(MAIN), Some(EINVAL));
+        assert_eq!(errno(Lcore::new(2)), Some(EINVAL));
+        assert_eq!(errno(Lcore::new(u32::MAX)), Some(EINVAL));
+    });
+}
+
+#[test]
+fn topology() {
+    let topology = common::on_main_lcore(|| Topology::snapshot().unwrap());
+    assert_eq!(topology.main(), MAIN);
+    assert!(!topology.sockets().is_empty());
+
+    let eal: Vec<Lcore> = topology
+        .with_role(Role::Rte)
+        .map(|info| info.lcore)
+        .collect();
+    assert_eq!(eal, [MAIN, WORKER]);
+    for info in topology.with_role(Role::Rte) {
+        assert_eq!(info.index, Some(info.lcore.id()));
+        assert!(topology.sockets().contains(&info.socket_id));
+        // `-l` pins each lcore to the CPU of the same number.
+        assert_eq!(info.cpuset, [info.lcore.id()]);
+    }
+    assert_eq!(topology.get(Lcore::new(2)), None);
+
+    let socket_id = topology.get(WORKER).unwrap().socket_id;
+    let workers: Vec<Lcore> = topology.workers_on_socket(socket_id).collect();
+    assert_eq!(workers, [WORKER]);
+    let by_socket = topology.by_socket();
+    assert!(by_socket[&socket_id][&Role::Rte].contains(&WORKER));
+}
+
+#[test]
+fn registration() {
+    common::on_main_lcore(|| {
+        // EAL threads have an lcore already.
+        assert_eq!(register_thread().unwrap_err().errno(), EEXIST);
+
+        let lcore = thread::spawn(|| {
+            let registration = register_thread().unwrap();
+            let lcore = registration.lcore();
+            assert_eq!(Lcore::current(), Some(lcore));
+            assert_eq!(lcore.role(), Role::NonEal);
+            // Registered threads count, but are not EAL lcores.
+            assert_eq!(Lcore::count(), 3);
+            assert!(!lcore.is_enabled());
+            assert_eq!(register_thread().unwrap_err().errno(), EEXIST);
+            drop(registration);
+            assert_eq!(Lcore::current(), None);
+            lcore
+        })
+        .join()
+        .unwrap();
+        assert_eq!(lcore.role(), Role::Off);
+        assert_eq!(Lcore::count(), 2);
+
+        let handle = spawn_registered(thread::Builder::new(), |lcore| {
+            (lcore, Lcore::current(), lcore.role())
+        })
+        .unwrap();
+        let (lcore, current, role) = handle.join().unwrap().unwrap();
+        assert_eq!(current, Some(lcore));
+        assert_eq!(role, Role::NonEal);
+        assert_eq!(lcore.role(), Role::Off);
+    });
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use dpdk_sys::mbuf::{Mbuf, Mempool};
+
+#[test]
+fn pool_counts_allocations() {
+    let pool = common::pool("mbuf_counts", 63);
+    assert_eq!(pool.avail_count(), 63);
+    assert!(pool.is_full());
+
+    let mbufs: Vec<Mbuf> = (0..10).map(|_| pool.alloc().unwrap()).collect();
+    assert_eq!(pool.avail_count(), 53);
+    assert!(!pool.is_full());
+
+    drop(mbufs);
+    assert_eq!(pool.avail_count(), 63);
+    assert!(pool.is_full());
+}
+
+#[test]
+fn exhausted_pool_returns_none() {
+    let pool = common::pool("mbuf_exhausted", 15);
+    let mbufs: Vec<Mbuf> = std::iter::from_fn(|| pool.alloc()).collect();
+    assert_eq!(mbufs.len(), 15);
+    assert!(pool.alloc().is_none());
+    drop(mbufs);
+    assert!(pool.alloc().is_some());
+}
+
+#[test]
+fn pool_is_freed_on_drop() {
+    let pool = common::pool("mbuf_freed", 15);
+    drop(pool);
+    // The name is free again once the pool is gone.
+    common::pool("mbuf_freed", 15);
+}
+
+#[test]
+fn append_grows_the_packet() {
+    let pool = common::pool("mbuf_append", 15);
+    let mut m = pool.alloc().unwrap();
+    assert_eq!(m.pkt_len(), 0);
+    assert_eq!(m.nb_segs(), 1);
+
+    let data = m.append(100).unwrap();
+    data.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    m.append(28).unwrap().fill(0xff);
+
+    assert_eq!(m.pkt_len(), 128);
+    assert_eq!(m.data_len(), 128);
+    assert_eq!(m.data()[99], 99);
+    assert_eq!(m.data()[100], 0xff);
+
+    m.data_mut()[0] = 42;
+    assert_eq!(m.data()[0], 42);
+
+    // The data room is 2048 bytes, past the headroom.
+    assert!(m.append(2048).is_none());
+    assert_eq!(m.pkt_len(), 128);
+}
+
+#[test]
+fn raw_round_trip_keeps_the_mbuf() {
+    let pool = common::pool("mbuf_raw", 15);
+    let mut m = pool.alloc().unwrap();
+    m.append(10).unwrap().fill(7);
+
+    let raw = m.into_raw();
+    assert_eq!(pool.avail_count(), 14);
+    let m = unsafe { Mbuf::from_raw(raw) }.unwrap();
+    assert_eq!(m.data(), &[7; 10]);
+
+    drop(m);
+    assert!(pool.is_full());
+}
+
+#[test]
+fn mbufs_move_between_threads() {
+    let pool = common::pool("mbuf_threads", 63);
+    let mbufs: Vec<Mbuf> = (0..32u8)
+        .map(|i| {
+            let mut m = pool.alloc().unwrap();
+            m.append(1).unwrap()[0] = i;
+            m
+        })
+        .collect();
+
+    let sum = std::thread::spawn(move || mbufs.iter().map(|m| m.data()[0] as u32).sum::<u32>())
+        .join()
+        .unwrap();
+    assert_eq!(sum, (0..32).sum());
+    assert!(pool.is_full());
+}
+
+#[test]
+fn mempool_on_socket_zero() {
+    common::eal();
+    let pool = Mempool::create("mbuf_socket", 15, 0, 256, Some(0)).unwrap();
+    assert_eq!(pool.avail_count(), 15);
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dpdk_sys::error::{EEXIST, ENOENT};
+use dpdk_sys::memory::{heap_stats, DpdkAllocator, DpdkBox, Memzone};
+
+#[test]
+fn memzone_reserve_and_lookup() {
+    common::eal();
+    let mz = Memzone::builder("memory_zone", 4096)
+        .align(256)
+        .reserve()
+        .unwrap();
+    assert_eq!(mz.name(), "memory_zone");
+    assert!(mz.len() >= 4096);
+    assert_eq!(mz.addr() as usize % 256, 0);
+    assert_eq!(mz.socket_id(), Some(0));
+    unsafe { mz.addr().write_bytes(0xab, 4096) };
+
+    let found = unsafe { Memzone::lookup("memory_zone") }.unwrap();
+    assert_eq!(found.addr(), mz.addr());
+    assert_eq!(unsafe { *found.addr().add(4095) }, 0xab);
+    drop(found);
+
+    let err = Memzone::reserve("memory_zone", 64).err().unwrap();
+    assert_eq!(err.errno(), EEXIST);
+
+    // Dropping the reserving handle frees the zone.
+    drop(mz);
+    let err = unsafe { Memzone::lookup("memory_zone") }.err().unwrap();
+    assert_eq!(err.errno(), ENOENT);
+}
+
+#[test]
+fn allocator_honours_layouts() {
+    common::eal();
+    let allocator = DpdkAllocator::on_socket(0);
+    for align in [8, 64, 4096] {
+        let layout = Layout::from_size_align(1000, align).unwrap();
+        unsafe {
+            let p = allocator.alloc_zeroed(layout);
+            assert!(!p.is_null());
+            assert_eq!(p as usize % align, 0);
+            assert!(std::slice::from_raw_parts(p, 1000).iter().all(|&b| b == 0));
+            p.write_bytes(7, 1000);
+
+            let p = allocator.realloc(p, layout, 3000);
+            assert!(!p.is_null());
+            assert_eq!(*p.add(999), 7);
+            allocator.dealloc(p, Layout::from_size_align(3000, align).unwrap());
+        }
+    }
+}
+
+#[test]
+fn dpdk_box_drops_its_value() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted([u64; 32]);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    common::eal();
+    let mut b = DpdkBox::new(Counted([1; 32]), Some(0)).unwrap();
+    b.0[31] = 2;
+    assert_eq!(b.0.iter().sum::<u64>(), 33);
+    drop(b);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+    let b = DpdkBox::new(Counted([3; 32]), None).unwrap();
+    let inner = b.into_inner();
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+    assert_eq!(inner.0[0], 3);
+    drop(inner);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+
+    let zst = DpdkBox::new((), None).unwrap();
+    assert_eq!(*zst, ());
+}
+
+#[test]
+fn heap_stats_account_for_allocations() {
+    common::eal();
+    let b = unsafe { DpdkBox::<[u8; 1 << 20]>::zeroed(Some(0)) }.unwrap();
+    assert!(b.iter().all(|&x| x == 0));
+    // Other tests allocate concurrently, so only check consistency.
+    let stats = heap_stats(0).unwrap();
+    assert!(stats.heap_allocsz_bytes >= 1 << 20);
+    assert!(stats.alloc_count >= 1);
+    assert_eq!(
+        stats.heap_totalsz_bytes,
+        stats.heap_allocsz_bytes + stats.heap_freesz_bytes
+    );
+    assert!(stats.greatest_free_size <= stats.heap_freesz_bytes);
+}
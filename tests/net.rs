@@ -0,0 +1,604 @@
+mod common;
+
+use dpdk_sys::mbuf::{Mbuf, Mempool};
+use dpdk_sys::net::{
+    ipv4_cksum, ipv4_udptcp_cksum, ipv4_udptcp_cksum_verify, ipv6_udptcp_cksum,
+    ipv6_udptcp_cksum_verify, raw_cksum, HeaderLens, Headers, L2Type, L3Type, L4Type, PacketType,
+    Tunnel, TunnelType, TxOffload, L3, L4,
+};
+use dpdk_sys::{
+    RTE_PTYPE_INNER_L2_MASK, RTE_PTYPE_INNER_L3_MASK, RTE_PTYPE_INNER_L4_MASK, RTE_PTYPE_L2_MASK,
+    RTE_PTYPE_L3_MASK, RTE_PTYPE_L4_MASK, RTE_PTYPE_TUNNEL_MASK,
+};
+
+const SRC4: [u8; 4] = [192, 168, 0, 1];
+const DST4: [u8; 4] = [192, 168, 0, 199];
+// 2001:db8::1 and 2001:db8::2.
+const SRC6: [u8; 16] = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+const DST6: [u8; 16] = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+/// A UDP header from port 1234 to `dst_port` followed by `payload`, without
+/// checksum.
+fn udp(dst_port: u16, payload: &[u8]) -> Vec<u8> {
+    let mut udp = Vec::new();
+    udp.extend_from_slice(&1234u16.to_be_bytes());
+    udp.extend_from_slice(&dst_port.to_be_bytes());
+    udp.extend_from_slice(&(8 + payload.len() as u16).to_be_bytes());
+    udp.extend_from_slice(&[0, 0]);
+    udp.extend_from_slice(payload);
+    udp
+}
+
+/// A TCP header without options from port 1234 to `dst_port`.
+fn tcp(dst_port: u16) -> Vec<u8> {
+    let mut tcp = vec![0u8; 20];
+    tcp[0..2].copy_from_slice(&1234u16.to_be_bytes());
+    tcp[2..4].copy_from_slice(&dst_port.to_be_bytes());
+    tcp[12] = 0x50;
+    tcp
+}
+
+/// An IPv4 header from `SRC4` to `DST4` without checksum, followed by `l4`.
+fn ipv4(proto: u8, l4: &[u8]) -> Vec<u8> {
+    let mut ip = vec![0u8; 20];
+    ip[0] = 0x45;
+    ip[2..4].copy_from_slice(&(20 + l4.len() as u16).to_be_bytes());
+    ip[6..8].copy_from_slice(&0x4000u16.to_be_bytes());
+    ip[8] = 64;
+    ip[9] = proto;
+    ip[12..16].copy_from_slice(&SRC4);
+    ip[16..20].copy_from_slice(&DST4);
+    ip.extend_from_slice(l4);
+    ip
+}
+
+/// An IPv6 header from `SRC6` to `DST6`, followed by `l4`.
+fn ipv6(proto: u8, l4: &[u8]) -> Vec<u8> {
+    let mut ip = vec![0u8; 40];
+    ip[0] = 0x60;
+    ip[4..6].copy_from_slice(&(l4.len() as u16).to_be_bytes());
+    ip[6] = proto;
+    ip[7] = 64;
+    ip[8..24].copy_from_slice(&SRC6);
+    ip[24..40].copy_from_slice(&DST6);
+    ip.extend_from_slice(l4);
+    ip
+}
+
+/// An Ethernet header for `l3`, followed by it.
+fn ether(ether_type: u16, l3: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x02, 0, 0, 0, 0, 0x02, 0x02, 0, 0, 0, 0, 0x01];
+    frame.extend_from_slice(&ether_type.to_be_bytes());
+    frame.extend_from_slice(l3);
+    frame
+}
+
+/// A VXLAN header for VNI 42 followed by `inner`.
+fn vxlan(inner: &[u8]) -> Vec<u8> {
+    let mut vxlan = vec![0x08, 0, 0, 0, 0, 0, 42, 0];
+    vxlan.extend_from_slice(inner);
+    vxlan
+}
+
+/// A GTP-U header of type `msg_type` followed by `exts`, the optional
+/// fields and extension headers if any, and `inner`.
+fn gtpu(msg_type: u8, exts: &[u8], inner: &[u8]) -> Vec<u8> {
+    // Version 1, GTP, and the E flag with extension headers.
+    let flags = if exts.is_empty() { 0x30 } else { 0x34 };
+    let mut gtp = vec![flags, msg_type];
+    gtp.extend_from_slice(&((exts.len() + inner.len()) as u16).to_be_bytes());
+    gtp.extend_from_slice(&7u32.to_be_bytes());
+    gtp.extend_from_slice(exts);
+    gtp.extend_from_slice(inner);
+    gtp
+}
+
+/// `frame` copied into `buf` at `misalign` bytes past an 8 byte boundary.
+fn place<'a>(buf: &'a mut Vec<u8>, frame: &[u8], misalign: usize) -> &'a [u8] {
+    buf.clear();
+    buf.resize(frame.len() + 16, 0);
+    let start = buf.as_ptr().align_offset(8) + misalign;
+    buf[start..start + frame.len()].copy_from_slice(frame);
+    &buf[start..start + frame.len()]
+}
+
+fn mbuf(pool: &Mempool, frame: &[u8]) -> Mbuf {
+    let mut m = pool.alloc().expect("mempool exhausted");
+    m.append(frame.len() as u16)
+        .expect("not enough tailroom")
+        .copy_from_slice(frame);
+    m
+}
+
+#[test]
+fn raw_cksum_vectors() {
+    // The example of RFC 1071, section 3: the sum is the same in either
+    // byte order once stored back.
+    let data = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+    assert_eq!(raw_cksum(&data).to_ne_bytes(), 0xddf2u16.to_be_bytes());
+    // An odd trailing byte is padded with zero.
+    assert_eq!(raw_cksum(&data[..3]).to_ne_bytes(), 0xf201u16.to_be_bytes());
+    assert_eq!(raw_cksum(&[]), 0);
+    assert_eq!(raw_cksum(&[0xff, 0xff, 0xff, 0xff]), 0xffff);
+}
+
+#[test]
+fn ipv4_cksum_vectors() {
+    let hdr = [
+        0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0xc0, 0xa8, 0x00,
+        0x01, 0xc0, 0xa8, 0x00, 0xc7,
+    ];
+    assert_eq!(ipv4_cksum(&hdr).unwrap().to_ne_bytes(), [0xb8, 0x61]);
+
+    assert!(ipv4_cksum(&hdr[..19]).is_err());
+    let mut options = hdr;
+    options[0] = 0x46;
+    assert!(ipv4_cksum(&options).is_err());
+}
+
+#[test]
+fn udptcp_cksum_vectors() {
+    let mut ip = ipv4(17, &udp(80, b"hello"));
+    let cksum = ipv4_udptcp_cksum(&ip).unwrap();
+    assert_eq!(cksum.to_ne_bytes(), 0x34c7u16.to_be_bytes());
+    assert!(!ipv4_udptcp_cksum_verify(&ip).unwrap());
+    ip[26..28].copy_from_slice(&cksum.to_ne_bytes());
+    assert!(ipv4_udptcp_cksum_verify(&ip).unwrap());
+    ip[32] ^= 1;
+    assert!(!ipv4_udptcp_cksum_verify(&ip).unwrap());
+    // The packet must hold the whole IPv4 total length.
+    assert!(ipv4_udptcp_cksum(&ip[..ip.len() - 1]).is_err());
+
+    let mut ip = ipv6(17, &udp(80, b"hello"));
+    let cksum = ipv6_udptcp_cksum(&ip).unwrap();
+    assert_eq!(cksum.to_ne_bytes(), 0x5b6bu16.to_be_bytes());
+    ip[46..48].copy_from_slice(&cksum.to_ne_bytes());
+    assert!(ipv6_udptcp_cksum_verify(&ip).unwrap());
+    ip[52] ^= 1;
+    assert!(!ipv6_udptcp_cksum_verify(&ip).unwrap());
+    assert!(ipv6_udptcp_cksum(&ip[..ip.len() - 1]).is_err());
+}
+
+#[test]
+fn fill_cksums() {
+    let pool = common::pool("net_cksum", 64);
+
+    let mut m = mbuf(&pool, &ether(0x0800, &ipv4(17, &udp(80, b"hello"))));
+    m.fill_cksums().unwrap();
+    let ip = &m.data()[14..];
+    assert_eq!(ip[10..12], 0xb8b3u16.to_be_bytes());
+    assert_eq!(ip[26..28], 0x34c7u16.to_be_bytes());
+    assert!(ipv4_udptcp_cksum_verify(ip).unwrap());
+
+    let mut m = mbuf(&pool, &ether(0x86dd, &ipv6(17, &udp(80, b"hello"))));
+    m.fill_cksums().unwrap();
+    let ip = &m.data()[14..];
+    assert_eq!(ip[46..48], 0x5b6bu16.to_be_bytes());
+    assert!(ipv6_udptcp_cksum_verify(ip).unwrap());
+
+    // Filling again over valid checksums gives the same ones.
+    let before = m.data().to_vec();
+    m.fill_cksums().unwrap();
+    assert_eq!(m.data(), before);
+}
+
+#[test]
+fn tx_offload_ipv6_has_no_ip_cksum() {
+    let pool = common::pool("net_offload", 64);
+    let frame = ether(0x86dd, &ipv6(17, &udp(80, b"hello")));
+
+    let mut m = mbuf(&pool, &frame);
+    let offload = TxOffload {
+        ip_cksum: true,
+        l4_cksum: true,
+        ..Default::default()
+    };
+    assert!(m.set_tx_offload(offload).is_err());
+
+    let lens = m
+        .set_tx_offload(TxOffload {
+            l4_cksum: true,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!((lens.l2_len, lens.l3_len, lens.l4_len), (14, 40, 8));
+
+    let mut m = mbuf(&pool, &ether(0x0800, &ipv4(17, &udp(80, b"hello"))));
+    m.set_tx_offload(offload).unwrap();
+}
+
+#[test]
+fn parse_plain() {
+    let mut buf = Vec::new();
+
+    let frame = ether(0x0800, &ipv4(17, &udp(80, b"hello")));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    let outer = headers.outer;
+    assert!(outer.ether.is_some());
+    assert_eq!(outer.ether_type(), Some(0x0800));
+    assert!(matches!(outer.l3, Some(L3::Ipv4(_))));
+    assert_eq!(outer.proto, Some(17));
+    assert!(matches!(outer.l4, Some(L4::Udp(_))));
+    assert_eq!(
+        outer.l4.map(|l4| (l4.src_port(), l4.dst_port())),
+        Some((1234, 80))
+    );
+    assert!(headers.tunnel.is_none() && headers.inner.is_none());
+    assert_eq!(
+        headers.lens(),
+        HeaderLens {
+            l2_len: 14,
+            l3_len: 20,
+            l4_len: 8,
+            ..Default::default()
+        }
+    );
+
+    let frame = ether(0x86dd, &ipv6(6, &tcp(443)));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert!(matches!(headers.outer.l3, Some(L3::Ipv6(_))));
+    assert!(matches!(headers.outer.l4, Some(L4::Tcp(_))));
+    assert_eq!(headers.outer.l4.unwrap().dst_port(), 443);
+    assert_eq!((headers.outer.l3_len, headers.outer.l4_len), (40, 20));
+
+    // Parsing stops at unknown protocols.
+    let frame = ether(0x0806, &[0; 28]);
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert_eq!(headers.outer.ether_type(), Some(0x0806));
+    assert!(headers.outer.l3.is_none());
+    assert_eq!(headers.lens().l2_len, 14);
+
+    let frame = ether(0x0800, &ipv4(47, &[0; 4]));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert_eq!(headers.outer.proto, Some(47));
+    assert!(headers.outer.l4.is_none());
+}
+
+#[test]
+fn parse_vlans() {
+    let mut buf = Vec::new();
+    let mut tags = vec![0x00, 0x0a, 0x81, 0x00, 0x00, 0x0b, 0x08, 0x00];
+    tags.extend_from_slice(&ipv4(17, &udp(80, b"")));
+    let frame = ether(0x88a8, &tags);
+
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    let outer = headers.outer;
+    assert!(outer.vlans.iter().all(|vlan| vlan.is_some()));
+    assert_eq!(outer.ether_type(), Some(0x0800));
+    assert_eq!(outer.l2_len, 22);
+    assert!(matches!(outer.l4, Some(L4::Udp(_))));
+}
+
+#[test]
+fn parse_ipv6_extensions() {
+    let mut buf = Vec::new();
+
+    // Hop-by-hop options, then UDP.
+    let mut ext = vec![17, 0, 0, 0, 0, 0, 0, 0];
+    ext.extend_from_slice(&udp(80, b""));
+    let frame = ether(0x86dd, &ipv6(0, &ext));
+    let outer = Headers::parse(place(&mut buf, &frame, 0)).unwrap().outer;
+    assert_eq!((outer.proto, outer.l3_len, outer.l4_len), (Some(17), 48, 8));
+
+    // Fragments have no L4 header.
+    let mut ext = vec![17, 0, 0, 0, 0, 0, 0, 1];
+    ext.extend_from_slice(&udp(80, b""));
+    let frame = ether(0x86dd, &ipv6(44, &ext));
+    let outer = Headers::parse(place(&mut buf, &frame, 0)).unwrap().outer;
+    assert_eq!((outer.proto, outer.l3_len), (Some(17), 48));
+    assert!(outer.l4.is_none());
+
+    let mut ip = ipv4(17, &udp(80, b""));
+    ip[6] |= 0x20;
+    let frame = ether(0x0800, &ip);
+    let outer = Headers::parse(place(&mut buf, &frame, 0)).unwrap().outer;
+    assert!(outer.l4.is_none());
+}
+
+#[test]
+fn parse_vxlan() {
+    let mut buf = Vec::new();
+    let inner = ether(0x0800, &ipv4(6, &tcp(80)));
+    let frame = ether(0x0800, &ipv4(17, &udp(4789, &vxlan(&inner))));
+
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert!(matches!(headers.tunnel, Some(Tunnel::Vxlan(_))));
+    let inner = headers.inner.unwrap();
+    assert!(inner.ether.is_some());
+    assert!(matches!(inner.l4, Some(L4::Tcp(_))));
+    assert_eq!(
+        headers.lens(),
+        HeaderLens {
+            outer_l2_len: 14,
+            outer_l3_len: 20,
+            l2_len: 8 + 8 + 14,
+            l3_len: 20,
+            l4_len: 20,
+        }
+    );
+}
+
+#[test]
+fn parse_gtpu() {
+    let mut buf = Vec::new();
+    let inner = ipv6(17, &udp(53, b""));
+
+    let frame = ether(0x0800, &ipv4(17, &udp(2152, &gtpu(0xff, &[], &inner))));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert!(matches!(headers.tunnel, Some(Tunnel::Gtpu(_))));
+    let layers = headers.inner.unwrap();
+    assert!(layers.ether.is_none());
+    assert!(matches!(layers.l3, Some(L3::Ipv6(_))));
+    assert_eq!(headers.lens().l2_len, 8 + 8);
+
+    // The optional fields, then one 4 byte extension header.
+    let exts = [0, 0, 0, 0x85, 1, 0, 0, 0];
+    let frame = ether(0x0800, &ipv4(17, &udp(2152, &gtpu(0xff, &exts, &inner))));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert_eq!(headers.lens().l2_len, 8 + 8 + 8);
+    assert_eq!(headers.inner.unwrap().l4.unwrap().dst_port(), 53);
+
+    let exts = [0, 0, 0, 0x85, 0, 0, 0, 0];
+    let frame = ether(0x0800, &ipv4(17, &udp(2152, &gtpu(0xff, &exts, &inner))));
+    assert!(Headers::parse(place(&mut buf, &frame, 0)).is_err());
+
+    // Signalling messages are not tunnels.
+    let frame = ether(0x0800, &ipv4(17, &udp(2152, &gtpu(1, &[], &inner))));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert!(headers.tunnel.is_none() && headers.inner.is_none());
+}
+
+#[test]
+fn parse_ip_in_ip() {
+    let mut buf = Vec::new();
+
+    let frame = ether(0x0800, &ipv4(4, &ipv4(17, &udp(80, b""))));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert!(matches!(headers.tunnel, Some(Tunnel::Ip)));
+    assert!(matches!(headers.inner.unwrap().l3, Some(L3::Ipv4(_))));
+    assert_eq!(
+        headers.lens(),
+        HeaderLens {
+            outer_l2_len: 14,
+            outer_l3_len: 20,
+            l2_len: 0,
+            l3_len: 20,
+            l4_len: 8,
+        }
+    );
+
+    let frame = ether(0x86dd, &ipv6(41, &ipv6(6, &tcp(80))));
+    let headers = Headers::parse(place(&mut buf, &frame, 0)).unwrap();
+    assert!(matches!(headers.tunnel, Some(Tunnel::Ip)));
+    assert_eq!(headers.lens().outer_l3_len, 40);
+    assert_eq!(headers.lens().l4_len, 20);
+
+    // The inner version must match an IP header.
+    let mut inner = ipv4(17, &udp(80, b""));
+    inner[0] = 0x55;
+    let frame = ether(0x0800, &ipv4(4, &inner));
+    assert!(Headers::parse(place(&mut buf, &frame, 0)).is_err());
+}
+
+#[test]
+fn parse_truncated() {
+    let mut buf = Vec::new();
+    let frames = [
+        ether(0x0800, &ipv4(6, &tcp(80))),
+        ether(0x86dd, &ipv6(17, &udp(80, b""))),
+        ether(
+            0x0800,
+            &ipv4(17, &udp(4789, &vxlan(&ether(0x0800, &ipv4(6, &tcp(80)))))),
+        ),
+        ether(
+            0x0800,
+            &ipv4(
+                17,
+                &udp(
+                    2152,
+                    &gtpu(0xff, &[0, 0, 0, 0x85, 1, 0, 0, 0], &ipv4(17, &udp(53, b""))),
+                ),
+            ),
+        ),
+    ];
+    // Without payload, every header is needed up to the last byte.
+    for frame in &frames {
+        assert!(Headers::parse(place(&mut buf, frame, 0)).is_ok());
+        for len in 0..frame.len() {
+            assert!(
+                Headers::parse(place(&mut buf, &frame[..len], 0)).is_err(),
+                "{} of {} bytes",
+                len,
+                frame.len()
+            );
+        }
+    }
+
+    // A TCP data offset beyond the packet.
+    let mut l4 = tcp(80);
+    l4[12] = 0x60;
+    let frame = ether(0x0800, &ipv4(6, &l4));
+    assert!(Headers::parse(place(&mut buf, &frame, 0)).is_err());
+}
+
+#[test]
+fn parse_misaligned() {
+    let mut buf = Vec::new();
+    let frame = ether(0x0800, &ipv4(17, &udp(80, b"")));
+    assert!(Headers::parse(place(&mut buf, &frame, 0)).is_ok());
+    assert!(Headers::parse(place(&mut buf, &frame, 1)).is_err());
+}
+
+#[test]
+fn parse_header_lens() {
+    let pool = common::pool("net_parse", 64);
+    let inner = ether(0x86dd, &ipv6(17, &udp(80, b"hello")));
+    let mut m = mbuf(&pool, &ether(0x0800, &ipv4(17, &udp(4789, &vxlan(&inner)))));
+
+    assert_eq!(m.header_lens(), HeaderLens::default());
+    let lens = m.parse_header_lens().unwrap();
+    assert_eq!(lens, m.headers().unwrap().lens());
+    assert_eq!(m.header_lens(), lens);
+    assert_eq!((lens.outer_l3_len, lens.l2_len, lens.l3_len), (20, 30, 40));
+
+    let mut m = mbuf(&pool, &ether(0x0800, &ipv4(6, &tcp(80))[..30]));
+    assert!(m.parse_header_lens().is_err());
+}
+
+const L2_TYPES: &[L2Type] = &[
+    L2Type::Unknown,
+    L2Type::Ether,
+    L2Type::Timesync,
+    L2Type::Arp,
+    L2Type::Lldp,
+    L2Type::Nsh,
+    L2Type::Vlan,
+    L2Type::Qinq,
+    L2Type::Pppoe,
+    L2Type::Fcoe,
+    L2Type::Mpls,
+];
+const INNER_L2_TYPES: &[L2Type] = &[L2Type::Unknown, L2Type::Ether, L2Type::Vlan, L2Type::Qinq];
+
+const L3_TYPES: &[L3Type] = &[
+    L3Type::Unknown,
+    L3Type::Ipv4,
+    L3Type::Ipv4Ext,
+    L3Type::Ipv4ExtUnknown,
+    L3Type::Ipv6,
+    L3Type::Ipv6Ext,
+    L3Type::Ipv6ExtUnknown,
+];
+
+const L4_TYPES: &[L4Type] = &[
+    L4Type::Unknown,
+    L4Type::Tcp,
+    L4Type::Udp,
+    L4Type::Frag,
+    L4Type::Sctp,
+    L4Type::Icmp,
+    L4Type::NonFrag,
+    L4Type::Igmp,
+];
+const INNER_L4_TYPES: &[L4Type] = &[
+    L4Type::Unknown,
+    L4Type::Tcp,
+    L4Type::Udp,
+    L4Type::Frag,
+    L4Type::Sctp,
+    L4Type::Icmp,
+    L4Type::NonFrag,
+];
+
+const TUNNEL_TYPES: &[Option<TunnelType>] = &[
+    None,
+    Some(TunnelType::Ip),
+    Some(TunnelType::Gre),
+    Some(TunnelType::Vxlan),
+    Some(TunnelType::Nvgre),
+    Some(TunnelType::Geneve),
+    Some(TunnelType::Grenat),
+    Some(TunnelType::Gtpc),
+    Some(TunnelType::Gtpu),
+    Some(TunnelType::Esp),
+    Some(TunnelType::L2tp),
+    Some(TunnelType::VxlanGpe),
+    Some(TunnelType::MplsInGre),
+    Some(TunnelType::MplsInUdp),
+];
+
+#[test]
+fn ptype_round_trip() {
+    let mut outer = Vec::new();
+    for &l2 in L2_TYPES {
+        for &l3 in L3_TYPES {
+            for &l4 in L4_TYPES {
+                outer.push((l2, l3, l4));
+            }
+        }
+    }
+    let mut inner = Vec::new();
+    for &inner_l2 in INNER_L2_TYPES {
+        for &inner_l3 in L3_TYPES {
+            for &inner_l4 in INNER_L4_TYPES {
+                inner.push((inner_l2, inner_l3, inner_l4));
+            }
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for &(l2, l3, l4) in &outer {
+        for &tunnel in TUNNEL_TYPES {
+            for &(inner_l2, inner_l3, inner_l4) in &inner {
+                let ptype = PacketType {
+                    l2,
+                    l3,
+                    l4,
+                    tunnel,
+                    inner_l2,
+                    inner_l3,
+                    inner_l4,
+                };
+                let raw = ptype.to_raw();
+                assert_eq!(PacketType::from_raw(raw), ptype, "{:#010x}", raw);
+                assert!(seen.insert(raw), "{:?} collides", ptype);
+            }
+        }
+    }
+    assert_eq!(PacketType::UNKNOWN.to_raw(), 0);
+    assert_eq!(PacketType::from(0), PacketType::default());
+}
+
+#[test]
+fn ptype_unknown_values() {
+    // Every value of every layer decodes, the ones without a variant as
+    // unknown, and those with one encode back the same.
+    let masks = [
+        RTE_PTYPE_L2_MASK,
+        RTE_PTYPE_L3_MASK,
+        RTE_PTYPE_L4_MASK,
+        RTE_PTYPE_TUNNEL_MASK,
+        RTE_PTYPE_INNER_L2_MASK,
+        RTE_PTYPE_INNER_L3_MASK,
+        RTE_PTYPE_INNER_L4_MASK,
+    ];
+    for mask in masks {
+        let shift = mask.trailing_zeros();
+        for value in 0..=mask >> shift {
+            let raw = value << shift;
+            let ptype = PacketType::from_raw(raw);
+            assert!(
+                ptype.to_raw() == raw || ptype.to_raw() == 0,
+                "{:#010x}",
+                raw
+            );
+        }
+    }
+    for raw in [u32::MAX, 0xdead_beef, 0x8000_0000] {
+        assert_eq!(
+            PacketType::from_raw(raw).to_raw() & !raw,
+            0,
+            "{:#010x}",
+            raw
+        );
+    }
+
+    // Outer only types have no inner encoding.
+    let ptype = PacketType {
+        inner_l2: L2Type::Arp,
+        inner_l4: L4Type::Igmp,
+        ..PacketType::UNKNOWN
+    };
+    assert_eq!(ptype.to_raw(), 0);
+}
+
+#[test]
+fn ptype_display() {
+    let ptype = PacketType {
+        l2: L2Type::Ether,
+        l3: L3Type::Ipv4,
+        l4: L4Type::Tcp,
+        ..PacketType::UNKNOWN
+    };
+    assert_eq!(ptype.to_string(), "L2_ETHER L3_IPV4 L4_TCP");
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "pcap")]
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dpdk_sys::ethdev::Port;
+use dpdk_sys::mbuf::Mbuf;
+use dpdk_sys::pcap::PcapVdev;
+
+const LINKTYPE_ETHERNET: u32 = 1;
+const MAGIC_USEC: u32 = 0xa1b2_c3d4;
+const MAGIC_NSEC: u32 = 0xa1b2_3c4d;
+
+fn tmp_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}-{}", std::process::id(), name))
+}
+
+fn write_pcap(path: &Path, packets: &[Vec<u8>]) {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&MAGIC_USEC.to_le_bytes());
+    buf.extend_from_slice(&2u16.to_le_bytes());
+    buf.extend_from_slice(&4u16.to_le_bytes());
+    buf.extend_from_slice(&0i32.to_le_bytes());
+    buf.extend_from_slice(&0u32.to_le_bytes());
+    buf.extend_from_slice(&65535u32.to_le_bytes());
+    buf.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+    for (i, pkt) in packets.iter().enumerate() {
+        buf.extend_from_slice(&(i as u32).to_le_bytes());
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf.extend_from_slice(&(pkt.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&(pkt.len() as u32).to_le_bytes());
+        buf.extend_from_slice(pkt);
+    }
+    fs::write(path, buf).unwrap();
+}
+
+fn read_pcap(path: &Path) -> Vec<Vec<u8>> {
+    let buf = fs::read(path).unwrap();
+    let u32_at = |off: usize| u32::from_le_bytes(buf[off..off + 4].try_into().unwrap());
+    assert!([MAGIC_USEC, MAGIC_NSEC].contains(&u32_at(0)));
+    assert_eq!(u32_at(20), LINKTYPE_ETHERNET);
+
+    let mut packets = Vec::new();
+    let mut off = 24;
+    while off < buf.len() {
+        let incl_len = u32_at(off + 8) as usize;
+        let orig_len = u32_at(off + 12) as usize;
+        assert_eq!(incl_len, orig_len, "packet truncated");
+        off += 16;
+        packets.push(buf[off..off + incl_len].to_vec());
+        off += incl_len;
+    }
+    packets
+}
+
+/// Ethernet, IPv4 and UDP packets of growing sizes with distinct payloads.
+fn packets(n: usize) -> Vec<Vec<u8>> {
+    (0..n)
+        .map(|i| {
+            let payload_len = 18 + i * 97;
+            let mut pkt = Vec::with_capacity(42 + payload_len);
+            pkt.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02, 0x02, 0, 0, 0, 0, 0x01]);
+            pkt.extend_from_slice(&0x0800u16.to_be_bytes());
+            pkt.extend_from_slice(&[0x45, 0]);
+            pkt.extend_from_slice(&((28 + payload_len) as u16).to_be_bytes());
+            pkt.extend_from_slice(&(i as u16).to_be_bytes());
+            pkt.extend_from_slice(&[0x40, 0, 64, 17, 0, 0]);
+            pkt.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+            pkt.extend_from_slice(&1234u16.to_be_bytes());
+            pkt.extend_from_slice(&5678u16.to_be_bytes());
+            pkt.extend_from_slice(&((8 + payload_len) as u16).to_be_bytes());
+            pkt.extend_from_slice(&[0, 0]);
+            pkt.extend((0..payload_len).map(|j| (i * 31 + j) as u8));
+            pkt
+        })
+        .collect()
+}
+
+fn receive(port: Port, n: usize) -> Vec<Mbuf> {
+    let mut pkts = Vec::new();
+    for _ in 0..1000 {
+        let missing = n - pkts.len();
+        if missing == 0 {
+            break;
+        }
+        port.rx_burst(0, &mut pkts, missing);
+    }
+    pkts
+}
+
+#[test]
+fn replay_and_capture_round_trip() {
+    let pool = common::pool("pcap_round_trip", 512);
+    let input = tmp_path("round_trip_in.pcap");
+    let output = tmp_path("round_trip_out.pcap");
+    let sent = packets(12);
+    write_pcap(&input, &sent);
+
+    let vdev = PcapVdev::new(0).rx_pcap(&input).tx_pcap(&output);
+    let port = common::start_vdev(vdev.devargs(), 1, &pool);
+
+    let mut received = receive(port, sent.len() + 1);
+    assert_eq!(received.len(), sent.len());
+    for (m, pkt) in received.iter().zip(&sent) {
+        assert_eq!(m.nb_segs(), 1);
+        assert_eq!(m.data(), &pkt[..]);
+    }
+
+    while !received.is_empty() {
+        port.tx_burst(0, &mut received);
+    }
+    // The output file is flushed when the port stops.
+    common::stop_vdev(vdev.devargs(), port);
+
+    assert_eq!(read_pcap(&output), sent);
+    assert!(pool.is_full());
+    fs::remove_file(input).unwrap();
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn infinite_rx_loops_over_the_file() {
+    let pool = common::pool("pcap_infinite", 512);
+    let input = tmp_path("infinite_in.pcap");
+    let sent = packets(3);
+    write_pcap(&input, &sent);
+
+    let vdev = PcapVdev::new(1).rx_pcap(&input).infinite_rx();
+    let port = common::start_vdev(vdev.devargs(), 1, &pool);
+
+    let received = receive(port, 4 * sent.len());
+    assert_eq!(received.len(), 4 * sent.len());
+    for (m, pkt) in received.iter().zip(sent.iter().cycle()) {
+        assert_eq!(m.data(), &pkt[..]);
+    }
+
+    drop(received);
+    common::stop_vdev(vdev.devargs(), port);
+    fs::remove_file(input).unwrap();
+}
+
+#[test]
+fn devargs() {
+    let vdev = PcapVdev::new(3)
+        .rx_pcap("/tmp/a.pcap")
+        .rx_pcap("/tmp/b.pcap")
+        .tx_pcap("/tmp/out.pcap")
+        .infinite_rx();
+    assert_eq!(
+        vdev.devargs().to_string(),
+        "vdev:net_pcap3,rx_pcap=/tmp/a.pcap,rx_pcap=/tmp/b.pcap,tx_pcap=/tmp/out.pcap,infinite_rx=1"
+    );
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use dpdk_sys::capture::{pcapng_mbuf_size, Direction, PcapngConfig, PcapngWriter};
+use dpdk_sys::error::EINVAL;
+use dpdk_sys::ethdev::LoopbackPort;
+use dpdk_sys::mbuf::{Mbuf, Mempool};
+
+const SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const INTERFACE_DESCRIPTION: u32 = 1;
+const INTERFACE_STATISTICS: u32 = 5;
+const ENHANCED_PACKET: u32 = 6;
+const BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+const LINKTYPE_ETHERNET: u16 = 1;
+
+fn tmp_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}-{}", std::process::id(), name))
+}
+
+fn packets(pool: &Mempool, n: usize) -> Vec<Mbuf> {
+    (0..n)
+        .map(|i| {
+            let mut m = pool.alloc().unwrap();
+            let data = m.append(60 + 7 * i as u16).unwrap();
+            for (j, b) in data.iter_mut().enumerate() {
+                *b = (i + j) as u8;
+            }
+            m
+        })
+        .collect()
+}
+
+/// The type and body of each block of a little endian pcapng file, checking
+/// that the lengths around each block agree.
+fn read_blocks(path: &Path) -> Vec<(u32, Vec<u8>)> {
+    let buf = fs::read(path).unwrap();
+    let u32_at = |off: usize| u32::from_le_bytes(buf[off..off + 4].try_into().unwrap());
+
+    let mut blocks = Vec::new();
+    let mut off = 0;
+    while off < buf.len() {
+        let len = u32_at(off + 4) as usize;
+        assert_eq!(len % 4, 0, "unaligned block at {}", off);
+        assert_eq!(u32_at(off + len - 4) as usize, len, "block at {}", off);
+        blocks.push((u32_at(off), buf[off + 8..off + len - 4].to_vec()));
+        off += len;
+    }
+    assert_eq!(off, buf.len());
+    blocks
+}
+
+fn u16_at(body: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes(body[off..off + 2].try_into().unwrap())
+}
+
+fn u32_at(body: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes(body[off..off + 4].try_into().unwrap())
+}
+
+#[test]
+fn loopback_capture() {
+    let pool = common::pool("pcapng_pkts", 63);
+    let (a, b) = LoopbackPort::pair("pcapng_lo", 1, 64, &pool).unwrap();
+    let mut pkts = packets(&pool, 5);
+    let sent: Vec<Vec<u8>> = pkts.iter().map(|m| m.data().to_vec()).collect();
+    assert_eq!(a.tx_burst(0, &mut pkts), 5);
+    let mut received = Vec::new();
+    assert_eq!(b.rx_burst(0, &mut received, 32), 5);
+
+    // The copies fit in the data room of the usual pools.
+    assert!(pcapng_mbuf_size(256) <= 2048 + 128);
+    let capture = common::pool("pcapng_capture", 63);
+    let path = tmp_path("loopback.pcapng");
+    let config = PcapngConfig {
+        appname: Some("dpdk-sys-test".to_string()),
+        comment: Some("loopback".to_string()),
+        ..PcapngConfig::default()
+    };
+    let mut writer = PcapngWriter::new(File::create(&path).unwrap(), &capture, &config).unwrap();
+    writer.add_interface(b.port(), Some("lo1"), None).unwrap();
+
+    // Packets of a port that was not added are refused.
+    let err = writer.write(a.port(), 0, Direction::Out, &received);
+    assert_eq!(err.unwrap_err().errno(), EINVAL);
+
+    let written = writer.write(b.port(), 0, Direction::In, &received).unwrap();
+    let stats = writer.write_stats(b.port(), 5, 0).unwrap();
+    drop(writer);
+    // The copies went back to their pool, the packets are untouched.
+    assert!(capture.is_full());
+    assert_eq!(
+        received
+            .iter()
+            .map(|m| m.data().to_vec())
+            .collect::<Vec<_>>(),
+        sent
+    );
+
+    let blocks = read_blocks(&path);
+    let types: Vec<u32> = blocks.iter().map(|(t, _)| *t).collect();
+    assert_eq!(
+        types,
+        [
+            SECTION_HEADER,
+            INTERFACE_DESCRIPTION,
+            ENHANCED_PACKET,
+            ENHANCED_PACKET,
+            ENHANCED_PACKET,
+            ENHANCED_PACKET,
+            ENHANCED_PACKET,
+            INTERFACE_STATISTICS,
+        ]
+    );
+
+    let (_, shb) = &blocks[0];
+    assert_eq!(u32_at(shb, 0), BYTE_ORDER_MAGIC);
+    assert_eq!((u16_at(shb, 4), u16_at(shb, 6)), (1, 0));
+    let (_, idb) = &blocks[1];
+    assert_eq!(u16_at(idb, 0), LINKTYPE_ETHERNET);
+
+    // Each packet block is the header, the padded data and the options.
+    let mut epb_bytes = 0;
+    for ((_, epb), pkt) in blocks[2..7].iter().zip(&sent) {
+        assert_eq!(u32_at(epb, 0), 0, "interface id");
+        assert_eq!(u32_at(epb, 12) as usize, pkt.len());
+        assert_eq!(u32_at(epb, 16) as usize, pkt.len());
+        assert_eq!(&epb[20..20 + pkt.len()], &pkt[..]);
+        epb_bytes += epb.len() + 12;
+    }
+    assert_eq!(epb_bytes, written);
+    let (_, isb) = &blocks[7];
+    assert_eq!(u32_at(isb, 0), 0, "interface id");
+    assert_eq!(isb.len() + 12, stats);
+
+    drop(received);
+    assert!(pool.is_full());
+    fs::remove_file(path).unwrap();
+}
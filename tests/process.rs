@@ -0,0 +1,123 @@
+use std::ffi::CStr;
+use std::fs::{self, File};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, IntoRawFd, OwnedFd};
+use std::os::raw::c_int;
+use std::os::unix::fs::MetadataExt;
+
+use dpdk_sys::error::{Result, E2BIG, EINVAL};
+use dpdk_sys::process::{from_raw, to_raw, Message};
+use dpdk_sys::{rte_mp_msg, RTE_MP_MAX_FD_NUM, RTE_MP_MAX_NAME_LEN, RTE_MP_MAX_PARAM_LEN};
+
+macro_rules! message {
+    ($ty:ident, $name:expr) => {
+        #[derive(Debug, Default)]
+        struct $ty {
+            data: Vec<u8>,
+            files: Vec<File>,
+        }
+
+        impl Message for $ty {
+            const NAME: &'static str = $name;
+
+            fn encode<'a>(&'a self, data: &mut Vec<u8>, fds: &mut Vec<BorrowedFd<'a>>) {
+                data.extend_from_slice(&self.data);
+                fds.extend(self.files.iter().map(|file| file.as_fd()));
+            }
+
+            fn decode(data: &[u8], fds: Vec<OwnedFd>) -> Result<Self> {
+                Ok(Self {
+                    data: data.to_vec(),
+                    files: fds.into_iter().map(File::from).collect(),
+                })
+            }
+        }
+    };
+}
+
+message!(Files, "test_files");
+message!(Empty, "");
+message!(Nul, "test\0files");
+message!(
+    Long,
+    "a_name_of_exactly_sixty_four_bytes_which_leaves_no_room_for_nul_"
+);
+
+fn errno<T>(result: Result<T>) -> i32 {
+    result.err().expect("expected an error").errno()
+}
+
+/// Emulate the receiving side, which gets its own copy of the
+/// descriptors.
+fn received(raw: &rte_mp_msg) -> rte_mp_msg {
+    let mut received = *raw;
+    for fd in &mut received.fds[..raw.num_fds as usize] {
+        let sent = unsafe { BorrowedFd::borrow_raw(*fd) };
+        *fd = sent.try_clone_to_owned().unwrap().into_raw_fd();
+    }
+    received
+}
+
+#[test]
+fn bad_names() {
+    assert_eq!(Long::NAME.len(), RTE_MP_MAX_NAME_LEN as usize);
+    assert_eq!(errno(to_raw(&Long::default())), EINVAL);
+    assert_eq!(errno(to_raw(&Nul::default())), EINVAL);
+    assert_eq!(errno(to_raw(&Empty::default())), EINVAL);
+}
+
+#[test]
+fn too_big() {
+    let raw = to_raw(&Files {
+        data: vec![1; RTE_MP_MAX_PARAM_LEN as usize],
+        files: Vec::new(),
+    })
+    .unwrap();
+    assert_eq!(raw.len_param, RTE_MP_MAX_PARAM_LEN as c_int);
+
+    let msg = Files {
+        data: vec![1; RTE_MP_MAX_PARAM_LEN as usize + 1],
+        files: Vec::new(),
+    };
+    assert_eq!(errno(to_raw(&msg)), E2BIG);
+
+    let files = (0..=RTE_MP_MAX_FD_NUM)
+        .map(|_| File::open("/dev/null").unwrap())
+        .collect();
+    let msg = Files {
+        data: Vec::new(),
+        files,
+    };
+    assert_eq!(errno(to_raw(&msg)), E2BIG);
+}
+
+#[test]
+fn round_trip() {
+    let path = std::env::temp_dir().join(format!("dpdk-sys-ipc-{}", std::process::id()));
+    let file = File::create(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let msg = Files {
+        data: b"hello".to_vec(),
+        files: vec![file, File::open("/dev/null").unwrap()],
+    };
+
+    let raw = to_raw(&msg).unwrap();
+    assert_eq!(
+        unsafe { CStr::from_ptr(raw.name.as_ptr()) }.to_bytes(),
+        b"test_files"
+    );
+    assert_eq!(raw.num_fds, 2);
+    let decoded: Files = unsafe { from_raw(&received(&raw)) }.unwrap();
+    assert_eq!(decoded.data, b"hello");
+    assert_eq!(decoded.files.len(), 2);
+    for (sent, got) in msg.files.iter().zip(&decoded.files) {
+        assert_ne!(sent.as_raw_fd(), got.as_raw_fd());
+        let (sent, got) = (sent.metadata().unwrap(), got.metadata().unwrap());
+        assert_eq!((sent.dev(), sent.ino()), (got.dev(), got.ino()));
+    }
+
+    // A negative length decodes as no data.
+    let mut raw = to_raw(&Files::default()).unwrap();
+    raw.len_param = -1;
+    let decoded: Files = unsafe { from_raw(&raw) }.unwrap();
+    assert!(decoded.data.is_empty());
+}
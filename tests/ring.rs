@@ -0,0 +1,261 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use dpdk_sys::error::{Error, EINVAL, ENOBUFS};
+use dpdk_sys::mbuf::Mbuf;
+use dpdk_sys::ring::{Ring, RingBuilder, SyncMode};
+
+/// A value counting its drops in `drops`.
+struct Counted {
+    value: u32,
+    drops: Arc<AtomicUsize>,
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// The rings move the boxes, not the vectors.
+#[allow(clippy::vec_box)]
+fn counted(values: std::ops::Range<u32>, drops: &Arc<AtomicUsize>) -> Vec<Box<Counted>> {
+    values
+        .map(|value| {
+            Box::new(Counted {
+                value,
+                drops: drops.clone(),
+            })
+        })
+        .collect()
+}
+
+fn values(boxes: &[Box<Counted>]) -> Vec<u32> {
+    boxes.iter().map(|b| b.value).collect()
+}
+
+#[test]
+fn boxed_values() {
+    common::eal();
+    let ring = Ring::<Box<String>>::new("ring_boxed", 8).unwrap();
+    assert_eq!(ring.name(), "ring_boxed");
+    assert!(ring.is_empty());
+    assert!(ring.dequeue().is_none());
+
+    for s in ["a", "b", "c"] {
+        ring.enqueue(Box::new(s.to_string())).unwrap();
+    }
+    assert_eq!(*ring.dequeue().unwrap(), "a");
+    assert_eq!(*ring.dequeue().unwrap(), "b");
+    ring.enqueue(Box::new("d".to_string())).unwrap();
+    assert_eq!(*ring.dequeue().unwrap(), "c");
+    assert_eq!(*ring.dequeue().unwrap(), "d");
+    assert!(ring.is_empty());
+}
+
+#[test]
+fn counts() {
+    common::eal();
+    // One slot of a ring of a power of two size stays unused.
+    let ring = Ring::<Box<u32>>::new("ring_counts", 8).unwrap();
+    assert_eq!(ring.capacity(), 7);
+    assert_eq!((ring.len(), ring.free_count()), (0, 7));
+
+    for i in 0..7 {
+        assert!(!ring.is_full());
+        ring.enqueue(Box::new(i)).unwrap();
+        assert_eq!((ring.len(), ring.free_count()), (i + 1, 6 - i));
+    }
+    assert!(ring.is_full());
+    // A full ring hands the value back.
+    assert_eq!(*ring.enqueue(Box::new(7)).unwrap_err(), 7);
+
+    let ring = RingBuilder::new("ring_counts_exact", 6)
+        .exact_size()
+        .create::<Box<u32>>()
+        .unwrap();
+    assert_eq!(ring.capacity(), 6);
+    assert_eq!(ring.free_count(), 6);
+}
+
+#[test]
+fn invalid_rings() {
+    common::eal();
+    let err = Error::new(EINVAL);
+    // Not a power of two.
+    assert_eq!(Ring::<Box<u32>>::new("ring_bad_count", 6).err(), Some(err));
+    // Elements must be a non-zero multiple of 4 bytes.
+    assert_eq!(Ring::<u8>::new("ring_bad_u8", 8).err(), Some(err));
+    assert_eq!(Ring::<()>::new("ring_bad_unit", 8).err(), Some(err));
+    assert_eq!(Ring::<Box<u32>>::new("ring\0nul", 8).err(), Some(err));
+}
+
+#[test]
+fn bulk_is_all_or_nothing() {
+    common::eal();
+    let drops = Arc::new(AtomicUsize::new(0));
+    let ring = Ring::new("ring_bulk", 8).unwrap();
+
+    let mut boxes = counted(0..10, &drops);
+    assert_eq!(ring.enqueue_bulk(&mut boxes), Err(Error::new(ENOBUFS)));
+    assert_eq!(values(&boxes), (0..10).collect::<Vec<_>>());
+    assert!(ring.is_empty());
+
+    boxes.truncate(5);
+    ring.enqueue_bulk(&mut boxes).unwrap();
+    assert!(boxes.is_empty());
+    assert_eq!(ring.len(), 5);
+
+    let mut out = Vec::new();
+    assert_eq!(ring.dequeue_bulk(&mut out, 6), Err(Error::new(ENOBUFS)));
+    assert!(out.is_empty());
+    ring.dequeue_bulk(&mut out, 3).unwrap();
+    assert_eq!(values(&out), [0, 1, 2]);
+    ring.dequeue_bulk(&mut out, 2).unwrap();
+    assert_eq!(values(&out), [0, 1, 2, 3, 4]);
+    ring.dequeue_bulk(&mut out, 0).unwrap();
+
+    // The boxes that did not fit were dropped with the vector, the others
+    // are owned by `out`.
+    assert_eq!(drops.load(Ordering::SeqCst), 5);
+    drop(out);
+    assert_eq!(drops.load(Ordering::SeqCst), 10);
+}
+
+#[test]
+fn burst_is_partial() {
+    common::eal();
+    let drops = Arc::new(AtomicUsize::new(0));
+    let ring = Ring::new("ring_burst", 8).unwrap();
+
+    let mut boxes = counted(0..10, &drops);
+    assert_eq!(ring.enqueue_burst(&mut boxes), 7);
+    // The values left over are the ones that did not fit, in order.
+    assert_eq!(values(&boxes), [7, 8, 9]);
+    assert_eq!(ring.enqueue_burst(&mut boxes), 0);
+    assert_eq!(boxes.len(), 3);
+
+    let mut out = Vec::new();
+    assert_eq!(ring.dequeue_burst(&mut out, 4), 4);
+    assert_eq!(ring.enqueue_burst(&mut boxes), 3);
+    assert!(boxes.is_empty());
+    assert_eq!(ring.dequeue_burst(&mut out, 16), 6);
+    assert_eq!(values(&out), (0..10).collect::<Vec<_>>());
+    assert_eq!(ring.dequeue_burst(&mut out, 16), 0);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn drop_drains_the_ring() {
+    common::eal();
+    let drops = Arc::new(AtomicUsize::new(0));
+    let ring = Ring::new("ring_drop", 8).unwrap();
+    let mut boxes = counted(0..5, &drops);
+    ring.enqueue_bulk(&mut boxes).unwrap();
+
+    // A looked up handle neither drains nor frees the ring.
+    let lookup = unsafe { Ring::<Box<Counted>>::lookup("ring_drop") }.unwrap();
+    assert_eq!(lookup.len(), 5);
+    drop(lookup);
+    assert_eq!(ring.len(), 5);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    drop(ring);
+    assert_eq!(drops.load(Ordering::SeqCst), 5);
+    // The name is free again once the ring is gone.
+    Ring::<Box<Counted>>::new("ring_drop", 8).unwrap();
+}
+
+#[test]
+fn mbufs() {
+    let pool = common::pool("ring_mbufs_pool", 15);
+    let ring = Ring::<Mbuf>::new("ring_mbufs", 16).unwrap();
+    let mut mbufs: Vec<Mbuf> = (0..8).map(|_| pool.alloc().unwrap()).collect();
+    ring.enqueue_bulk(&mut mbufs).unwrap();
+    assert_eq!(pool.avail_count(), 7);
+
+    let mut out = Vec::new();
+    ring.dequeue_bulk(&mut out, 3).unwrap();
+    drop(out);
+    assert_eq!(pool.avail_count(), 10);
+    // The mbufs left in the ring go back to the pool with it.
+    drop(ring);
+    assert!(pool.is_full());
+}
+
+/// Move `count` values from `producers` threads to `consumers` threads and
+/// check that each value arrives once.
+fn transfer(ring: Ring<Box<u32>>, producers: u32, consumers: u32, count: u32) {
+    let ring = Arc::new(ring);
+    let received = Arc::new(AtomicUsize::new(0));
+    let sum = Arc::new(AtomicUsize::new(0));
+    let mut threads = Vec::new();
+    for p in 0..producers {
+        let ring = ring.clone();
+        threads.push(thread::spawn(move || {
+            let mut values: Vec<Box<u32>> = (0..count)
+                .filter(|i| i % producers == p)
+                .map(Box::new)
+                .collect();
+            while !values.is_empty() {
+                if ring.enqueue_burst(&mut values) == 0 {
+                    thread::yield_now();
+                }
+            }
+        }));
+    }
+    for _ in 0..consumers {
+        let (ring, received, sum) = (ring.clone(), received.clone(), sum.clone());
+        threads.push(thread::spawn(move || {
+            let mut out = Vec::new();
+            while received.load(Ordering::SeqCst) < count as usize {
+                let n = ring.dequeue_burst(&mut out, 8);
+                if n == 0 {
+                    thread::yield_now();
+                    continue;
+                }
+                received.fetch_add(n, Ordering::SeqCst);
+                for value in out.drain(..) {
+                    sum.fetch_add(*value as usize, Ordering::SeqCst);
+                }
+            }
+        }));
+    }
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(received.load(Ordering::SeqCst), count as usize);
+    assert_eq!(
+        sum.load(Ordering::SeqCst),
+        (0..count as usize).sum::<usize>()
+    );
+    assert!(ring.is_empty());
+}
+
+#[test]
+fn sync_modes() {
+    common::eal();
+    let modes = [SyncMode::Multi, SyncMode::Rts, SyncMode::Hts];
+    for (i, producer) in modes.into_iter().enumerate() {
+        for (j, consumer) in modes.into_iter().enumerate() {
+            let ring = RingBuilder::new(format!("ring_sync_{}_{}", i, j), 64)
+                .producer(producer)
+                .consumer(consumer)
+                .create()
+                .unwrap();
+            transfer(ring, 3, 3, 10_000);
+        }
+    }
+
+    let ring = unsafe {
+        RingBuilder::new("ring_sync_single", 64)
+            .single_producer()
+            .single_consumer()
+            .create()
+            .unwrap()
+    };
+    transfer(ring, 1, 1, 10_000);
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "lpm")]
+
+mod common;
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use dpdk_sys::route::{
+    Fib, Fib6, FibConfig, Ipv4Prefix, Ipv6Prefix, Lpm, Lpm6, LpmConfig, NextHop,
+};
+
+// rte_lpm and rte_lpm6 have no default route, tests add one to the FIBs.
+const V4_ROUTES: &str = "
+10.0.0.0/8      2
+10.1.0.0/16     3
+10.1.2.0/24     4
+10.1.2.128/25   5
+10.1.2.200/32   6
+192.168.0.0/16  7
+";
+
+const V6_ROUTES: &str = "
+2001:db8::/32       2
+2001:db8:1::/48     3
+2001:db8:1:2::/64   4
+2001:db8:1:2::1/128 5
+fd00::/8            6
+";
+
+const V4_LOOKUPS: &[(&str, Option<u16>)] = &[
+    ("8.8.8.8", None),
+    ("10.200.0.1", Some(2)),
+    ("10.1.99.1", Some(3)),
+    ("10.1.2.1", Some(4)),
+    ("10.1.2.129", Some(5)),
+    ("10.1.2.200", Some(6)),
+    ("10.1.2.201", Some(5)),
+    ("192.168.10.10", Some(7)),
+];
+
+const V6_LOOKUPS: &[(&str, Option<u16>)] = &[
+    ("2001:4860::8888", None),
+    ("2001:db8:ffff::1", Some(2)),
+    ("2001:db8:1:ffff::1", Some(3)),
+    ("2001:db8:1:2::2", Some(4)),
+    ("2001:db8:1:2::1", Some(5)),
+    ("fd12::1", Some(6)),
+];
+
+/// A typed next hop, to check that ids round-trip through the tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Gateway(u16);
+
+impl NextHop for Gateway {
+    fn to_id(self) -> u64 {
+        self.0 as u64
+    }
+
+    fn from_id(id: u64) -> Self {
+        Gateway(id as u16)
+    }
+}
+
+fn routes<P: std::str::FromStr>(table: &str) -> Vec<(P, Gateway)>
+where
+    P::Err: std::fmt::Debug,
+{
+    table
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.split_whitespace();
+            let prefix = fields.next().unwrap().parse().unwrap();
+            let hop = fields.next().unwrap().parse().unwrap();
+            (prefix, Gateway(hop))
+        })
+        .collect()
+}
+
+fn expected(lookups: &[(&str, Option<u16>)]) -> Vec<Option<Gateway>> {
+    lookups.iter().map(|(_, hop)| hop.map(Gateway)).collect()
+}
+
+#[test]
+fn prefix_parsing() {
+    let prefix: Ipv4Prefix = "10.1.2.3/16".parse().unwrap();
+    assert_eq!(prefix.addr(), Ipv4Addr::new(10, 1, 0, 0));
+    assert_eq!(prefix.prefix_len(), 16);
+    assert_eq!(prefix.to_string(), "10.1.0.0/16");
+    assert!(prefix.contains(Ipv4Addr::new(10, 1, 255, 255)));
+    assert!(!prefix.contains(Ipv4Addr::new(10, 2, 0, 0)));
+    assert!("10.0.0.0/33".parse::<Ipv4Prefix>().is_err());
+
+    let prefix: Ipv6Prefix = "2001:db8::1/32".parse().unwrap();
+    assert_eq!(prefix.addr(), "2001:db8::".parse::<Ipv6Addr>().unwrap());
+    assert_eq!(prefix.to_string(), "2001:db8::/32");
+    assert!("::/129".parse::<Ipv6Prefix>().is_err());
+}
+
+#[test]
+fn lpm_lookup() {
+    common::eal();
+
+    let mut lpm = Lpm::<Gateway>::new("test_lpm", &LpmConfig::default()).unwrap();
+    for (prefix, hop) in routes::<Ipv4Prefix>(V4_ROUTES) {
+        lpm.add(prefix, hop).unwrap();
+    }
+    let addrs: Vec<Ipv4Addr> = V4_LOOKUPS.iter().map(|(a, _)| a.parse().unwrap()).collect();
+    for (addr, (_, hop)) in addrs.iter().zip(V4_LOOKUPS) {
+        assert_eq!(lpm.lookup(*addr), hop.map(Gateway), "{}", addr);
+    }
+    assert_eq!(lpm.lookup_bulk(&addrs), expected(V4_LOOKUPS));
+
+    let host = "10.1.2.200/32".parse().unwrap();
+    assert_eq!(lpm.get(host), Some(Gateway(6)));
+    lpm.delete(host).unwrap();
+    assert_eq!(lpm.get(host), None);
+    assert_eq!(lpm.lookup(Ipv4Addr::new(10, 1, 2, 200)), Some(Gateway(5)));
+
+    lpm.clear();
+    assert_eq!(lpm.lookup(Ipv4Addr::new(10, 1, 2, 1)), None);
+}
+
+#[test]
+fn lpm6_lookup() {
+    common::eal();
+
+    let mut lpm = Lpm6::<Gateway>::new("test_lpm6", &LpmConfig::default()).unwrap();
+    for (prefix, hop) in routes::<Ipv6Prefix>(V6_ROUTES) {
+        lpm.add(prefix, hop).unwrap();
+    }
+    let addrs: Vec<Ipv6Addr> = V6_LOOKUPS.iter().map(|(a, _)| a.parse().unwrap()).collect();
+    for (addr, (_, hop)) in addrs.iter().zip(V6_LOOKUPS) {
+        assert_eq!(lpm.lookup(*addr), hop.map(Gateway), "{}", addr);
+    }
+    assert_eq!(lpm.lookup_bulk(&addrs), expected(V6_LOOKUPS));
+
+    let host = "2001:db8:1:2::1/128".parse().unwrap();
+    assert_eq!(lpm.get(host), Some(Gateway(5)));
+    lpm.delete_bulk(&[host]).unwrap();
+    assert_eq!(
+        lpm.lookup("2001:db8:1:2::1".parse().unwrap()),
+        Some(Gateway(4))
+    );
+}
+
+#[test]
+fn fib_lookup() {
+    common::eal();
+
+    let mut fib = Fib::<Gateway>::new("test_fib", &FibConfig::default()).unwrap();
+    for (prefix, hop) in routes::<Ipv4Prefix>(V4_ROUTES) {
+        fib.add(prefix, hop).unwrap();
+    }
+    let addrs: Vec<Ipv4Addr> = V4_LOOKUPS.iter().map(|(a, _)| a.parse().unwrap()).collect();
+    assert_eq!(fib.lookup_bulk(&addrs), expected(V4_LOOKUPS));
+
+    let default = "0.0.0.0/0".parse().unwrap();
+    fib.add(default, Gateway(1)).unwrap();
+    assert_eq!(fib.lookup(Ipv4Addr::new(8, 8, 8, 8)), Some(Gateway(1)));
+    assert_eq!(fib.lookup(Ipv4Addr::new(10, 1, 2, 200)), Some(Gateway(6)));
+    fib.delete(default).unwrap();
+    assert_eq!(fib.lookup(Ipv4Addr::new(8, 8, 8, 8)), None);
+
+    // The largest id is reserved for lookups matching no route.
+    assert!(fib
+        .add("1.0.0.0/8".parse().unwrap(), Gateway(u16::MAX))
+        .is_err());
+}
+
+#[test]
+fn fib6_lookup() {
+    common::eal();
+
+    let mut fib = Fib6::<Gateway>::new("test_fib6", &FibConfig::default()).unwrap();
+    for (prefix, hop) in routes::<Ipv6Prefix>(V6_ROUTES) {
+        fib.add(prefix, hop).unwrap();
+    }
+    let addrs: Vec<Ipv6Addr> = V6_LOOKUPS.iter().map(|(a, _)| a.parse().unwrap()).collect();
+    assert_eq!(fib.lookup_bulk(&addrs), expected(V6_LOOKUPS));
+
+    let default = "::/0".parse().unwrap();
+    let addr = "2001:4860::8888".parse().unwrap();
+    fib.add(default, Gateway(1)).unwrap();
+    assert_eq!(fib.lookup(addr), Some(Gateway(1)));
+    fib.delete(default).unwrap();
+    assert_eq!(fib.lookup(addr), None);
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use dpdk_sys::error::{Error, EEXIST, EINVAL};
+use dpdk_sys::telemetry::{register_command, Value};
+
+use common::telemetry::TelemetryClient;
+
+#[test]
+fn dict_command() {
+    common::eal();
+    register_command("/rust_test/dict", "A dict of every kind of value", |_| {
+        Ok(Value::Dict(vec![
+            ("name".into(), "rust".into()),
+            ("delta".into(), (-3i64).into()),
+            ("packets".into(), 42u64.into()),
+            ("queues".into(), vec![0u16, 1].into()),
+            (
+                "port".into(),
+                Value::Dict(vec![("rx".into(), 1u64.into()), ("tx".into(), 2u64.into())]),
+            ),
+        ]))
+    })
+    .unwrap();
+
+    let mut client = TelemetryClient::connect();
+    assert_eq!(
+        client.query("/rust_test/dict"),
+        r#"{"/rust_test/dict":{"name":"rust","delta":-3,"packets":42,"queues":[0,1],"port":{"rx":1,"tx":2}}}"#
+    );
+}
+
+#[test]
+fn array_and_scalar_commands() {
+    common::eal();
+    register_command("/rust_test/strings", "Strings", |_| {
+        Ok(vec!["a", "b"].into())
+    })
+    .unwrap();
+    register_command("/rust_test/matrix", "Arrays of integers", |_| {
+        Ok(Value::Array(vec![vec![1i64, -1].into(), vec![2i64].into()]))
+    })
+    .unwrap();
+    register_command("/rust_test/int", "An integer", |_| Ok(7i64.into())).unwrap();
+    register_command("/rust_test/string", "A string", |_| Ok("up".into())).unwrap();
+
+    let mut client = TelemetryClient::connect();
+    assert_eq!(
+        client.query("/rust_test/strings"),
+        r#"{"/rust_test/strings":["a","b"]}"#
+    );
+    assert_eq!(
+        client.query("/rust_test/matrix"),
+        r#"{"/rust_test/matrix":[[1,-1],[2]]}"#
+    );
+    assert_eq!(client.query("/rust_test/int"), r#"{"/rust_test/int":"7"}"#);
+    assert_eq!(
+        client.query("/rust_test/string"),
+        r#"{"/rust_test/string":"up"}"#
+    );
+}
+
+#[test]
+fn params_are_passed() {
+    common::eal();
+    register_command("/rust_test/echo", "Echo the parameters", |params| {
+        Ok(params.unwrap_or("none").into())
+    })
+    .unwrap();
+
+    let mut client = TelemetryClient::connect();
+    assert_eq!(
+        client.query("/rust_test/echo,port0"),
+        r#"{"/rust_test/echo":"port0"}"#
+    );
+    assert_eq!(
+        client.query("/rust_test/echo"),
+        r#"{"/rust_test/echo":"none"}"#
+    );
+}
+
+#[test]
+fn failures_reply_null() {
+    common::eal();
+    register_command("/rust_test/error", "Always fails", |_| {
+        Err(Error::new(EINVAL))
+    })
+    .unwrap();
+    register_command("/rust_test/panic", "Always panics", |_| panic!("no value")).unwrap();
+    // Keys with spaces are rejected by the telemetry library.
+    register_command("/rust_test/bad_key", "Invalid key", |_| {
+        Ok(Value::Dict(vec![("not a key".into(), 1u64.into())]))
+    })
+    .unwrap();
+
+    let mut client = TelemetryClient::connect();
+    for cmd in ["/rust_test/error", "/rust_test/panic", "/rust_test/bad_key"] {
+        assert_eq!(client.query(cmd), format!(r#"{{"{}":null}}"#, cmd));
+    }
+    // The client is still served after failures.
+    assert!(client.query("/").contains(r#""/rust_test/error""#));
+}
+
+#[test]
+fn commands_are_listed_with_their_help() {
+    common::eal();
+    register_command("/rust_test/listed", "A listed command", |_| {
+        Ok(Value::Array(Vec::new()))
+    })
+    .unwrap();
+
+    let mut client = TelemetryClient::connect();
+    assert!(client.query("/").contains(r#""/rust_test/listed""#));
+    assert_eq!(
+        client.query("/help,/rust_test/listed"),
+        r#"{"/help":{"/rust_test/listed":"A listed command"}}"#
+    );
+    assert_eq!(
+        client.query("/rust_test/listed"),
+        r#"{"/rust_test/listed":[]}"#
+    );
+}
+
+#[test]
+fn invalid_registrations_are_rejected() {
+    common::eal();
+    register_command("/rust_test/once", "Registered once", |_| Ok(1u64.into())).unwrap();
+    let err = register_command("/rust_test/once", "Again", |_| Ok(2u64.into())).unwrap_err();
+    assert_eq!(err.errno(), EEXIST);
+
+    let err = register_command("rust_test/no_slash", "No slash", |_| Ok(1u64.into())).unwrap_err();
+    assert_eq!(err.errno(), EINVAL);
+}